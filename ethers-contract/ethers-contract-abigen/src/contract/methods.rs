@@ -90,7 +90,7 @@ impl Context {
             ///     abigen!(Greeter,"../greeter.json");
             ///
             ///    let greeter_contract = Greeter::deploy(client, "Hello world!".to_string()).unwrap().send().await.unwrap();
-            ///    let msg = greeter_contract.greet().call().await.unwrap();
+            ///    let msg = greeter_contract.greet().call().unwrap();
             /// # }
             /// ```
             pub fn deploy<T: #ethers_core::abi::Tokenize >(client: ::std::sync::Arc<M>, constructor_args: T) -> ::std::result::Result<#ethers_contract::builders::ContractDeployer<M, Self>, #ethers_contract::ContractError<M>> {
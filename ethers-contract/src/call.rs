@@ -4,13 +4,13 @@ use super::base::{decode_function_data, AbiError};
 use ethers_core::{
     abi::{AbiDecode, AbiEncode, Detokenize, Function, InvalidOutputType, Tokenizable},
     types::{
-        transaction::eip2718::TypedTransaction, Address, BlockId, Bytes, Selector,
-        TransactionRequest, U256,
+        transaction::{eip2718::TypedTransaction, eip2930::AccessList},
+        Address, BlockId, Bytes, Eip2930TransactionRequest, Selector, TransactionRequest, U256,
     },
     utils::id,
 };
 use ethers_providers::{
-    call_raw::{CallBuilder, RawCall},
+    call_raw::{spoof, CallBuilder, RawCall},
     Middleware, ProviderError,
 };
 
@@ -55,6 +55,11 @@ pub enum ContractError<M: Middleware> {
     #[error("{0}")]
     ProviderError(ProviderError),
 
+    /// Thrown when the call reverted, with the revert data returned by the node. Custom errors can
+    /// be decoded with [`ContractError::decode_revert`]
+    #[error("execution reverted: {0}")]
+    Revert(Bytes),
+
     /// Thrown during deployment if a constructor argument was passed in the `deploy`
     /// call but a constructor was not present in the ABI
     #[error("constructor is not defined in the ABI")]
@@ -64,6 +69,60 @@ pub enum ContractError<M: Middleware> {
     /// receipt
     #[error("Contract was not deployed")]
     ContractNotDeployed,
+
+    /// Thrown when predicting the address of a deployment whose sender is neither set on the
+    /// transaction nor the client's default sender
+    #[error("the sender of the deployment transaction is unknown")]
+    DeployerNotSet,
+
+    /// Thrown when decoding the constructor arguments of deployment calldata which does not start
+    /// with the contract's bytecode
+    #[error("the deployment calldata does not start with the contract's bytecode")]
+    BytecodeMismatch,
+}
+
+impl<M: Middleware> ContractError<M> {
+    /// Returns the revert data if the call reverted
+    pub fn as_revert(&self) -> Option<&Bytes> {
+        match self {
+            ContractError::Revert(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Decodes the revert data into `E`, e.g. a custom error deriving [`EthError`] or the enum of
+    /// a contract's errors generated by `abigen`. Returns `None` if the call did not revert or the
+    /// revert data is not an `E`.
+    ///
+    /// [`EthError`]: crate::EthError
+    pub fn decode_revert<E: AbiDecode>(&self) -> Option<E> {
+        self.as_revert().and_then(|data| E::decode(data).ok())
+    }
+
+    /// Wraps a middleware error, turning it into a [`ContractError::Revert`] if the provider
+    /// error it stems from carries revert data
+    fn from_middleware_error(err: M::Error) -> Self
+    where
+        M::Error: 'static,
+    {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&err);
+        while let Some(inner) = source {
+            if let Some(ProviderError::Revert(data)) = inner.downcast_ref::<ProviderError>() {
+                return ContractError::Revert(data.clone())
+            }
+            source = inner.source();
+        }
+        ContractError::MiddlewareError(err)
+    }
+
+    /// Wraps a provider error, turning it into a [`ContractError::Revert`] if it carries revert
+    /// data
+    fn from_provider_error(err: ProviderError) -> Self {
+        match err {
+            ProviderError::Revert(data) => ContractError::Revert(data),
+            err => ContractError::ProviderError(err),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -111,6 +170,18 @@ impl<M, D: Detokenize> ContractCall<M, D> {
         self
     }
 
+    /// Uses an EIP-2930 transaction with the provided access list to execute the call
+    pub fn access_list_tx(mut self, access_list: AccessList) -> Self {
+        let tx = match self.tx {
+            TypedTransaction::Legacy(inner) => inner,
+            TypedTransaction::Eip2930(inner) => inner.tx,
+            TypedTransaction::Eip1559(inner) => inner.into(),
+            TypedTransaction::Eip4844(inner) => inner.tx.into(),
+        };
+        self.tx = TypedTransaction::Eip2930(Eip2930TransactionRequest::new(tx, access_list));
+        self
+    }
+
     /// Sets the `gas` field in the transaction to the provided value
     pub fn gas<T: Into<U256>>(mut self, gas: T) -> Self {
         self.tx.set_gas(gas);
@@ -161,10 +232,21 @@ where
     /// If executed on a mutating smart contract function, it will do a "dry run" of the call
     /// and return the return type of the transaction without mutating the state
     ///
+    /// Structs returned by the function are decoded as tuples, so `D` can either be a type
+    /// deriving `EthAbiType` or a tuple mirroring the struct's fields. For example, a function
+    /// returning `struct Info { uint256 id; Owner owner; }` with
+    /// `struct Owner { address addr; uint256[] ids; }` can be decoded into
+    /// `(U256, (Address, Vec<U256>))`.
+    ///
     /// Note: this function _does not_ send a transaction from your account
-    pub async fn call(&self) -> Result<D, ContractError<M>> {
+    ///
+    /// If the call reverts, the revert data is returned as [`ContractError::Revert`].
+    pub fn call(&self) -> Result<D, ContractError<M>>
+    where
+        M::Error: 'static,
+    {
         let bytes =
-            self.client.call(&self.tx, self.block).await.map_err(ContractError::MiddlewareError)?;
+            self.client.call(&self.tx, self.block).map_err(ContractError::from_middleware_error)?;
 
         // decode output
         let data = decode_function_data(&self.function, &bytes, false)?;
@@ -183,11 +265,27 @@ where
     ) -> impl RawCall<'_> + Future<Output = Result<D, ContractError<M>>> + Debug {
         let call = self.call_raw_bytes();
         call.map(move |res: Result<Bytes, ProviderError>| {
-            let bytes = res.map_err(ContractError::ProviderError)?;
+            let bytes = res.map_err(ContractError::from_provider_error)?;
             decode_function_data(&self.function, &bytes, false).map_err(From::from)
         })
     }
 
+    /// Queries the blockchain via an `eth_call` for the provided transaction, executed against
+    /// the state with the given `overrides` applied, and decodes the returned data.
+    ///
+    /// This allows simulating a call against spoofed balances, nonces, code or storage without
+    /// having to deploy or modify anything on chain.
+    ///
+    /// Note: this function _does not_ send a transaction from your account
+    pub fn call_with_overrides(&self, overrides: &spoof::State) -> Result<D, ContractError<M>> {
+        let bytes = self
+            .call_raw_bytes()
+            .state(overrides)
+            .poll()
+            .map_err(ContractError::from_provider_error)?;
+        Ok(decode_function_data(&self.function, &bytes, false)?)
+    }
+
     /// Returns a [`CallBuilder`] which can be `.await`d to query the blochcain via `eth_call`,
     /// returning the raw bytes from the transaction.
     ///
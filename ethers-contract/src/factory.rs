@@ -1,11 +1,12 @@
 use crate::{Contract, ContractError};
 
 use ethers_core::{
-    abi::{Abi, Token, Tokenize},
+    abi::{self, Abi, Token, Tokenize},
     types::{
         transaction::eip2718::TypedTransaction, Address, BlockNumber, Bytes, NameOrAddress,
-        TransactionReceipt, TransactionRequest, U256, U64,
+        TransactionReceipt, TransactionRequest, H256, U256, U64,
     },
+    utils::{get_contract_address, get_create2_address},
 };
 use ethers_providers::{
     call_raw::{CallBuilder, RawCall},
@@ -125,6 +126,17 @@ impl<M: Middleware, C: From<Contract<M>>> ContractDeployer<M, C> {
         self.deployer.call_raw()
     }
 
+    /// Returns the address the contract will be deployed at, see [`Deployer::address`]
+    pub fn address(&self) -> Result<Address, ContractError<M>> {
+        self.deployer.address()
+    }
+
+    /// Returns the address the contract will be deployed at by a CREATE2 factory, see
+    /// [`Deployer::create2_address`]
+    pub fn create2_address(&self, factory: Address, salt: H256) -> Address {
+        self.deployer.create2_address(factory, salt)
+    }
+
     /// Returns a reference to the deployer's ABI
     pub fn abi(&self) -> &Abi {
         self.deployer.abi()
@@ -206,6 +218,35 @@ impl<M: Middleware> Deployer<M> {
         self.client.provider().call_raw(&self.tx).block(self.block.into())
     }
 
+    /// Returns the address the contract will be deployed at, derived from the sender and the
+    /// nonce of the deployment transaction.
+    ///
+    /// The sender defaults to the client's default sender and the nonce to the sender's pending
+    /// nonce, so the address is only final once the transaction's nonce is set.
+    pub fn address(&self) -> Result<Address, ContractError<M>> {
+        let from = self
+            .tx
+            .from()
+            .copied()
+            .or_else(|| self.client.default_sender())
+            .ok_or(ContractError::DeployerNotSet)?;
+        let nonce = match self.tx.nonce() {
+            Some(nonce) => *nonce,
+            None => self
+                .client
+                .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+                .map_err(ContractError::MiddlewareError)?,
+        };
+        Ok(get_contract_address(from, nonce))
+    }
+
+    /// Returns the address the contract will be deployed at when the deployment transaction's
+    /// data is passed as init code to the CREATE2 `factory` with the given `salt`
+    pub fn create2_address(&self, factory: Address, salt: H256) -> Address {
+        let init_code = self.tx.data().cloned().unwrap_or_default();
+        get_create2_address(factory, salt.as_bytes().to_vec(), init_code)
+    }
+
     /// Returns a reference to the deployer's ABI
     pub fn abi(&self) -> &Abi {
         &self.abi
@@ -270,6 +311,23 @@ impl<M: Middleware> ContractFactory<M> {
         })
     }
 
+    /// Decodes the constructor arguments of the contract's deployment calldata, e.g. to verify
+    /// the deployed contract, by stripping the bytecode and decoding the rest with the ABI's
+    /// constructor.
+    pub fn decode_constructor_args(&self, calldata: &[u8]) -> Result<Vec<Token>, ContractError<M>> {
+        let args =
+            calldata.strip_prefix(self.bytecode.as_ref()).ok_or(ContractError::BytecodeMismatch)?;
+        match self.abi.constructor() {
+            Some(constructor) => {
+                let types: Vec<_> =
+                    constructor.inputs.iter().map(|input| input.kind.clone()).collect();
+                Ok(abi::decode(&types, args)?)
+            }
+            None if args.is_empty() => Ok(Vec::new()),
+            None => Err(ContractError::ConstructorError),
+        }
+    }
+
     /// Constructs the deployment transaction based on the provided constructor
     /// arguments and returns a `Deployer` instance. You must call `send()` in order
     /// to actually deploy the contract.
@@ -282,3 +340,60 @@ impl<M: Middleware> ContractFactory<M> {
         self.deploy_tokens(constructor_args.into_tokens())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::abi::parse_abi;
+    use ethers_providers::Provider;
+
+    #[test]
+    fn predicts_deployment_address() {
+        let (provider, mock) = Provider::mocked();
+        let abi = parse_abi(&["constructor(uint256 x)"]).unwrap();
+        let factory = ContractFactory::new(abi, vec![0x60, 0x80].into(), Arc::new(provider));
+        let from: Address = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0".parse().unwrap();
+
+        let mut deployer = factory.deploy(U256::from(1)).unwrap();
+        assert!(matches!(deployer.address(), Err(ContractError::DeployerNotSet)));
+
+        // the nonce defaults to the sender's pending nonce
+        deployer.tx.set_from(from);
+        mock.push(U256::from(1)).unwrap();
+        let expected: Address = "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8".parse().unwrap();
+        assert_eq!(deployer.address().unwrap(), expected);
+        mock.assert_request("eth_getTransactionCount", (from, "pending")).unwrap();
+
+        deployer.tx.set_nonce(2);
+        let expected: Address = "0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91".parse().unwrap();
+        assert_eq!(deployer.address().unwrap(), expected);
+
+        // example 1 of EIP-1014
+        deployer.tx.set_data(vec![0].into());
+        let expected: Address = "0x4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38".parse().unwrap();
+        assert_eq!(deployer.create2_address(Address::zero(), H256::zero()), expected);
+    }
+
+    #[test]
+    fn decodes_constructor_args() {
+        let (provider, _mock) = Provider::mocked();
+        let abi =
+            parse_abi(&["constructor(address owner, uint256[] amounts, string name)"]).unwrap();
+        let factory = ContractFactory::new(abi, vec![0x60, 0x80].into(), Arc::new(provider));
+
+        let args = vec![
+            Token::Address(Address::repeat_byte(1)),
+            Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+            Token::String("vault".to_string()),
+        ];
+        let deployer = factory.clone().deploy_tokens(args.clone()).unwrap();
+        let calldata = deployer.tx.data().unwrap();
+        assert_eq!(factory.decode_constructor_args(calldata).unwrap(), args);
+
+        // the calldata of another contract is rejected
+        assert!(matches!(
+            factory.decode_constructor_args(&calldata[1..]),
+            Err(ContractError::BytecodeMismatch)
+        ));
+    }
+}
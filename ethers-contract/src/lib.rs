@@ -21,7 +21,7 @@ mod event;
 pub use event::EthEvent;
 
 mod log;
-pub use log::{decode_logs, EthLogDecode, LogMeta};
+pub use log::{decode_logs, EthLogDecode, LogMeta, MultiEventDecoder};
 
 pub mod stream;
 
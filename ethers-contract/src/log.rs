@@ -1,9 +1,11 @@
 //! Mod of types for ethereum logs
+use crate::EthEvent;
 use ethers_core::{
     abi::{Error, RawLog},
     types::{Address, Log, TxHash, H256, U256, U64},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// A trait for types (events) that can be decoded from a `RawLog`
 pub trait EthLogDecode: Send + Sync {
@@ -18,6 +20,56 @@ pub fn decode_logs<T: EthLogDecode>(logs: &[RawLog]) -> Result<Vec<T>, Error> {
     logs.iter().map(T::decode_log).collect()
 }
 
+/// Decodes the logs of several event types into a single type `E`, usually an enum with a variant
+/// per event, by dispatching on the first topic of a log to the decoder of the matching event.
+///
+/// This is useful for indexers watching a contract which emits many events. Anonymous events have
+/// no signature topic and can not be dispatched to.
+///
+/// ```ignore
+/// let decoder = MultiEventDecoder::<TokenEvents>::new().add::<Transfer>().add::<Approval>();
+/// let events = logs.iter().map(|log| decoder.decode(log)).collect::<Result<Vec<_>, _>>()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiEventDecoder<E> {
+    decoders: HashMap<H256, fn(&RawLog) -> Result<E, Error>>,
+}
+
+impl<E> Default for MultiEventDecoder<E> {
+    fn default() -> Self {
+        Self { decoders: HashMap::new() }
+    }
+}
+
+impl<E> MultiEventDecoder<E> {
+    /// Instantiates a decoder without any events
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the event `D`, which is converted into `E` once decoded
+    #[must_use]
+    pub fn add<D: EthEvent + Into<E>>(mut self) -> Self {
+        self.decoders.insert(D::signature(), decode_into::<D, E>);
+        self
+    }
+
+    /// Decodes the log with the decoder of the event matching its first topic. Returns
+    /// [`Error::InvalidData`] if no registered event matches.
+    pub fn decode(&self, log: &Log) -> Result<E, Error> {
+        let decode = log
+            .topics
+            .first()
+            .and_then(|topic| self.decoders.get(topic))
+            .ok_or(Error::InvalidData)?;
+        decode(&RawLog { topics: log.topics.clone(), data: log.data.to_vec() })
+    }
+}
+
+fn decode_into<D: EthEvent + Into<E>, E>(log: &RawLog) -> Result<E, Error> {
+    D::decode_log(log).map(Into::into)
+}
+
 /// Metadata inside a log
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LogMeta {
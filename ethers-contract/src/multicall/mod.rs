@@ -443,15 +443,18 @@ impl<M: Middleware> Multicall<M> {
     /// // 2. `returns (string, address)`
     /// // 3. `returns (bool)`
     /// // Version 1:
-    /// let result: (U256, (String, Address), bool) = multicall.call().await?;
+    /// let result: (U256, (String, Address), bool) = multicall.call()?;
     /// // Version 2 and above (each call returns also the success status as the first element):
-    /// let result: ((bool, U256), (bool, (String, Address)), (bool, bool)) = multicall.call().await?;
+    /// let result: ((bool, U256), (bool, (String, Address)), (bool, bool)) = multicall.call()?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn call<D: Detokenize>(&self) -> Result<D, M> {
+    pub fn call<D: Detokenize>(&self) -> Result<D, M>
+    where
+        M::Error: 'static,
+    {
         assert!(self.calls.len() < 16, "Cannot decode more than 16 calls");
-        let tokens = self.call_raw().await?;
+        let tokens = self.call_raw()?;
         let tokens = vec![Token::Tuple(tokens)];
         let data = D::from_tokens(tokens).map_err(ContractError::DetokenizationError)?;
         Ok(data)
@@ -478,7 +481,7 @@ impl<M: Middleware> Multicall<M> {
     /// # let multicall = Multicall::new(client, None).await?;
     /// // The consumer of the API is responsible for detokenizing the results
     /// // as the results will be a Vec<Token>
-    /// let tokens = multicall.call_raw().await?;
+    /// let tokens = multicall.call_raw()?;
     /// # Ok(())
     /// # }
     /// ```
@@ -486,12 +489,15 @@ impl<M: Middleware> Multicall<M> {
     /// Note: this method _does not_ send a transaction from your account
     ///
     /// [`ContractError<M>`]: crate::ContractError<M>
-    pub async fn call_raw(&self) -> Result<Vec<Token>, M> {
+    pub fn call_raw(&self) -> Result<Vec<Token>, M>
+    where
+        M::Error: 'static,
+    {
         // Different call result types based on version
         let tokens: Vec<Token> = match self.version {
             MulticallVersion::Multicall => {
                 let call = self.as_aggregate();
-                let (_, return_data) = call.call().await?;
+                let (_, return_data) = call.call()?;
                 self.calls
                     .iter()
                     .zip(&return_data)
@@ -512,7 +518,7 @@ impl<M: Middleware> Multicall<M> {
             v @ (MulticallVersion::Multicall2 | MulticallVersion::Multicall3) => {
                 let is_v2 = v == MulticallVersion::Multicall2;
                 let call = if is_v2 { self.as_try_aggregate() } else { self.as_aggregate_3() };
-                let return_data = call.call().await?;
+                let return_data = call.call()?;
                 self.calls
                     .iter()
                     .zip(&return_data)
@@ -1,14 +1,20 @@
 #![cfg(feature = "abigen")]
 #![allow(unused)]
 //! Test cases to validate the `abigen!` macro
-use ethers_contract::{abigen, Abigen, EthCall, EthEvent};
+use ethers_contract::{abigen, Abigen, EthCall, EthEvent, MultiEventDecoder};
 use ethers_core::{
-    abi::{AbiDecode, AbiEncode, Address, Tokenizable},
-    types::{transaction::eip2718::TypedTransaction, Chain, Eip1559TransactionRequest, U256},
+    abi::{AbiDecode, AbiEncode, Address, Token, Tokenizable},
+    types::{
+        transaction::{
+            eip2718::TypedTransaction,
+            eip2930::{AccessList, AccessListItem},
+        },
+        BlockNumber, Bytes, Chain, Eip1559TransactionRequest, Log, H256, U256,
+    },
     utils::Anvil,
 };
 use ethers_middleware::SignerMiddleware;
-use ethers_providers::{MockProvider, Provider};
+use ethers_providers::{call_raw::spoof, JsonRpcError, MockProvider, Provider};
 use ethers_signers::{LocalWallet, Signer};
 use ethers_solc::Solc;
 use std::{
@@ -598,3 +604,136 @@ fn can_generate_event_with_structs() {
     assert_eq!("MyEvent((uint256,uint256),uint256)", MyEventFilter::abi_signature());
     assert_event::<MyEventFilter>();
 }
+
+#[test]
+fn can_decode_mixed_events() {
+    abigen!(
+        Token,
+        r#"[
+            event Transfer(address indexed from, address indexed to, uint256 value)
+            event Approval(address indexed owner, address indexed spender, uint256 value)
+    ]"#
+    );
+
+    #[derive(Debug, PartialEq)]
+    enum Decoded {
+        Transfer(TransferFilter),
+        Approval(ApprovalFilter),
+    }
+    impl From<TransferFilter> for Decoded {
+        fn from(event: TransferFilter) -> Self {
+            Decoded::Transfer(event)
+        }
+    }
+    impl From<ApprovalFilter> for Decoded {
+        fn from(event: ApprovalFilter) -> Self {
+            Decoded::Approval(event)
+        }
+    }
+
+    let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let log = |signature: H256, value: u64| Log {
+        topics: vec![signature, H256::from(a), H256::from(b)],
+        data: ethers_core::abi::encode(&[Token::Uint(value.into())]).into(),
+        ..Default::default()
+    };
+    let logs = vec![
+        log(TransferFilter::signature(), 1),
+        log(ApprovalFilter::signature(), 2),
+        log(TransferFilter::signature(), 3),
+    ];
+
+    let decoder =
+        MultiEventDecoder::<Decoded>::new().add::<TransferFilter>().add::<ApprovalFilter>();
+    let decoded: Vec<_> = logs.iter().map(|log| decoder.decode(log).unwrap()).collect();
+    assert_eq!(
+        decoded,
+        vec![
+            Decoded::Transfer(TransferFilter { from: a, to: b, value: 1.into() }),
+            Decoded::Approval(ApprovalFilter { owner: a, spender: b, value: 2.into() }),
+            Decoded::Transfer(TransferFilter { from: a, to: b, value: 3.into() }),
+        ]
+    );
+
+    // logs of unregistered events are rejected
+    let unknown = log(H256::repeat_byte(0xff), 4);
+    assert!(decoder.decode(&unknown).is_err());
+}
+
+#[test]
+fn can_decode_custom_error_revert() {
+    abigen!(
+        Vault,
+        r#"[
+            function balanceOf(address owner) view returns (uint256)
+            error Unauthorized(address caller)
+            error InsufficientBalance(uint256 available, uint256 required)
+    ]"#
+    );
+
+    let (provider, mock) = Provider::mocked();
+    let vault = Vault::new(Address::repeat_byte(1), Arc::new(provider));
+
+    let error = InsufficientBalance { available: 1.into(), required: 2.into() };
+    let data = error.clone().encode();
+    mock.push_error(JsonRpcError {
+        code: 3,
+        message: "execution reverted".to_string(),
+        data: Some(serde_json::json!(ethers_core::types::Bytes::from(data.clone()))),
+    });
+
+    let err = vault.balance_of(Address::zero()).call().unwrap_err();
+    assert_eq!(err.as_revert().map(|data| data.to_vec()), Some(data));
+    assert_eq!(err.decode_revert::<VaultErrors>(), Some(VaultErrors::InsufficientBalance(error)));
+    assert_eq!(err.decode_revert::<Unauthorized>(), None);
+}
+
+#[test]
+fn can_call_with_overrides() {
+    abigen!(
+        Vault,
+        r#"[
+            function balanceOf(address owner) view returns (uint256)
+    ]"#
+    );
+
+    let (provider, mock) = Provider::mocked();
+    let vault = Vault::new(Address::repeat_byte(1), Arc::new(provider));
+
+    // the call is executed against a spoofed storage slot of the vault
+    let mut state = spoof::state();
+    state.account(vault.address()).store(H256::zero(), H256::from_low_u64_be(100));
+    mock.push(Bytes::from(U256::from(100).encode())).unwrap();
+
+    let call = vault.balance_of(Address::repeat_byte(2));
+    assert_eq!(call.call_with_overrides(&state).unwrap(), U256::from(100));
+    mock.assert_request("eth_call", (&call.tx, BlockNumber::Latest, &state)).unwrap();
+}
+
+#[test]
+fn can_build_access_list_tx() {
+    abigen!(
+        Vault,
+        r#"[
+            function withdraw(uint256 amount)
+    ]"#
+    );
+
+    let (provider, _mock) = Provider::mocked();
+    let vault = Vault::new(Address::repeat_byte(1), Arc::new(provider));
+    let access_list = AccessList(vec![AccessListItem {
+        address: Address::repeat_byte(1),
+        storage_keys: vec![H256::zero()],
+    }]);
+
+    let call = vault.withdraw(1.into()).gas(50_000).access_list_tx(access_list.clone());
+    match call.tx {
+        TypedTransaction::Eip2930(ref inner) => {
+            assert_eq!(inner.access_list, access_list);
+            assert_eq!(inner.tx.to, Some(Address::repeat_byte(1).into()));
+            assert_eq!(inner.tx.gas, Some(50_000.into()));
+            assert_eq!(inner.tx.data, Some(WithdrawCall { amount: 1.into() }.encode().into()));
+        }
+        _ => panic!("expected an EIP-2930 transaction"),
+    }
+}
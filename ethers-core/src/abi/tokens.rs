@@ -571,4 +571,34 @@ mod tests {
         let data: Result<U256, InvalidOutputType> = Detokenize::from_tokens(tokens);
         assert!(data.is_err());
     }
+
+    #[test]
+    fn should_detokenize_nested_struct_output() {
+        let abi = crate::abi::parse_abi(&[
+            "struct Point { uint8 x; bool y; }",
+            "struct Owner { address addr; uint256[] ids; }",
+            "struct Info { uint256 id; Owner owner; Point[] points; }",
+            "function info() external view returns (Info)",
+            "function infoAndCount() external view returns (Info, uint256)",
+        ])
+        .unwrap();
+
+        type Info = (U256, (Address, Vec<U256>), Vec<(u8, bool)>);
+        let info: Info = (
+            7.into(),
+            (Address::repeat_byte(1), vec![1.into(), 2.into()]),
+            vec![(1, true), (2, false)],
+        );
+
+        // a function returning a single struct decodes to a single tuple token
+        let encoded = crate::abi::encode(&[info.clone().into_token()]);
+        let tokens = abi.function("info").unwrap().decode_output(&encoded).unwrap();
+        assert_eq!(Info::from_tokens(tokens).unwrap(), info);
+
+        // a struct returned alongside other values is nested in the outer tuple
+        let encoded = crate::abi::encode(&(info.clone(), U256::from(3)).into_tokens());
+        let tokens = abi.function("infoAndCount").unwrap().decode_output(&encoded).unwrap();
+        let data: (Info, U256) = Detokenize::from_tokens(tokens).unwrap();
+        assert_eq!(data, (info, 3.into()));
+    }
 }
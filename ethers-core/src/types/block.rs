@@ -1,5 +1,5 @@
 // Taken from <https://github.com/tomusdrw/rust-web3/blob/master/src/types/block.rs>
-use crate::types::{Address, Bloom, Bytes, Transaction, TxHash, H256, U256, U64};
+use crate::types::{Address, Bloom, Bytes, Transaction, TxHash, Withdrawal, H256, U256, U64};
 use chrono::{DateTime, TimeZone, Utc};
 #[cfg(not(feature = "celo"))]
 use core::cmp::Ordering;
@@ -85,6 +85,12 @@ pub struct Block<TX> {
     /// Base fee per unit of gas (if past London)
     #[serde(rename = "baseFeePerGas")]
     pub base_fee_per_gas: Option<U256>,
+    /// Withdrawals root hash (if past Shanghai)
+    #[serde(default, rename = "withdrawalsRoot", skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<H256>,
+    /// Withdrawals (if past Shanghai)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals: Option<Vec<Withdrawal>>,
 
     #[cfg(feature = "celo")]
     #[cfg_attr(docsrs, doc(cfg(feature = "celo")))]
@@ -188,6 +194,36 @@ impl<TX> Block<TX> {
         let secs = self.timestamp.as_u64() as i64;
         Ok(Utc.timestamp(secs, 0))
     }
+
+    /// Returns the [`BlockSummary`] of this block
+    pub fn summary(&self) -> BlockSummary {
+        BlockSummary {
+            number: self.number,
+            hash: self.hash,
+            timestamp: self.timestamp,
+            tx_count: self.transactions.len(),
+            gas_used: self.gas_used,
+            base_fee: self.base_fee_per_gas,
+        }
+    }
+}
+
+/// The header fields of a block needed to follow the chain, along with its number of
+/// transactions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockSummary {
+    /// Block number. None if pending.
+    pub number: Option<U64>,
+    /// Hash of the block. None if pending.
+    pub hash: Option<H256>,
+    /// Timestamp
+    pub timestamp: U256,
+    /// Number of transactions in the block
+    pub tx_count: usize,
+    /// Gas Used
+    pub gas_used: U256,
+    /// Base fee per unit of gas (if past London)
+    pub base_fee: Option<U256>,
 }
 
 impl Block<TxHash> {
@@ -217,6 +253,8 @@ impl Block<TxHash> {
                 mix_hash,
                 nonce,
                 base_fee_per_gas,
+                withdrawals_root,
+                withdrawals,
                 other,
                 ..
             } = self;
@@ -242,6 +280,8 @@ impl Block<TxHash> {
                 mix_hash,
                 nonce,
                 base_fee_per_gas,
+                withdrawals_root,
+                withdrawals,
                 transactions,
                 other,
             }
@@ -265,6 +305,8 @@ impl Block<TxHash> {
                 seal_fields,
                 size,
                 base_fee_per_gas,
+                withdrawals_root,
+                withdrawals,
                 randomness,
                 epoch_snark_data,
                 ..
@@ -286,6 +328,8 @@ impl Block<TxHash> {
                 seal_fields,
                 size,
                 base_fee_per_gas,
+                withdrawals_root,
+                withdrawals,
                 randomness,
                 epoch_snark_data,
                 transactions,
@@ -321,6 +365,8 @@ impl From<Block<Transaction>> for Block<TxHash> {
                 mix_hash,
                 nonce,
                 base_fee_per_gas,
+                withdrawals_root,
+                withdrawals,
                 other,
             } = full;
             Block {
@@ -345,6 +391,8 @@ impl From<Block<Transaction>> for Block<TxHash> {
                 mix_hash,
                 nonce,
                 base_fee_per_gas,
+                withdrawals_root,
+                withdrawals,
                 transactions: transactions.iter().map(|tx| tx.hash).collect(),
                 other,
             }
@@ -369,6 +417,8 @@ impl From<Block<Transaction>> for Block<TxHash> {
                 transactions,
                 size,
                 base_fee_per_gas,
+                withdrawals_root,
+                withdrawals,
                 randomness,
                 epoch_snark_data,
             } = full;
@@ -389,6 +439,8 @@ impl From<Block<Transaction>> for Block<TxHash> {
                 seal_fields,
                 size,
                 base_fee_per_gas,
+                withdrawals_root,
+                withdrawals,
                 randomness,
                 epoch_snark_data,
                 transactions: transactions.iter().map(|tx| tx.hash).collect(),
@@ -530,11 +582,15 @@ impl<'de> Deserialize<'de> for BlockId {
     }
 }
 
-/// A block Number (or tag - "latest", "earliest", "pending")
+/// A block Number (or tag - "latest", "earliest", "pending", "safe", "finalized")
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum BlockNumber {
     /// Latest block
     Latest,
+    /// Latest block considered safe from reorgs by the consensus layer
+    Safe,
+    /// Latest block finalized by the consensus layer
+    Finalized,
     /// Earliest block (genesis)
     Earliest,
     /// Pending block (not yet part of the blockchain)
@@ -562,6 +618,16 @@ impl BlockNumber {
         matches!(self, BlockNumber::Latest)
     }
 
+    /// Returns `true` if it's "safe"
+    pub fn is_safe(&self) -> bool {
+        matches!(self, BlockNumber::Safe)
+    }
+
+    /// Returns `true` if it's "finalized"
+    pub fn is_finalized(&self) -> bool {
+        matches!(self, BlockNumber::Finalized)
+    }
+
     /// Returns `true` if it's "pending"
     pub fn is_pending(&self) -> bool {
         matches!(self, BlockNumber::Pending)
@@ -587,6 +653,8 @@ impl Serialize for BlockNumber {
         match *self {
             BlockNumber::Number(ref x) => serializer.serialize_str(&format!("0x{:x}", x)),
             BlockNumber::Latest => serializer.serialize_str("latest"),
+            BlockNumber::Safe => serializer.serialize_str("safe"),
+            BlockNumber::Finalized => serializer.serialize_str("finalized"),
             BlockNumber::Earliest => serializer.serialize_str("earliest"),
             BlockNumber::Pending => serializer.serialize_str("pending"),
         }
@@ -609,6 +677,8 @@ impl FromStr for BlockNumber {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let block = match s {
             "latest" => Self::Latest,
+            "safe" => Self::Safe,
+            "finalized" => Self::Finalized,
             "earliest" => Self::Earliest,
             "pending" => Self::Pending,
             n => BlockNumber::Number(n.parse::<U64>().map_err(|err| err.to_string())?),
@@ -622,6 +692,8 @@ impl fmt::Display for BlockNumber {
         match self {
             BlockNumber::Number(ref x) => format!("0x{:x}", x).fmt(f),
             BlockNumber::Latest => f.write_str("latest"),
+            BlockNumber::Safe => f.write_str("safe"),
+            BlockNumber::Finalized => f.write_str("finalized"),
             BlockNumber::Earliest => f.write_str("earliest"),
             BlockNumber::Pending => f.write_str("pending"),
         }
@@ -698,7 +770,13 @@ mod tests {
 
     #[test]
     fn serde_block_number() {
-        for b in &[BlockNumber::Latest, BlockNumber::Earliest, BlockNumber::Pending] {
+        for b in &[
+            BlockNumber::Latest,
+            BlockNumber::Safe,
+            BlockNumber::Finalized,
+            BlockNumber::Earliest,
+            BlockNumber::Pending,
+        ] {
             let b_ser = serde_json::to_string(&b).unwrap();
             let b_de: BlockNumber = serde_json::from_str(&b_ser).unwrap();
             assert_eq!(b_de, *b);
@@ -709,6 +787,11 @@ mod tests {
         let b_de: BlockNumber = serde_json::from_str(&b_ser).unwrap();
         assert_eq!(b_ser, "\"0x412\"");
         assert_eq!(b_de, b);
+
+        assert_eq!(serde_json::to_string(&BlockNumber::Safe).unwrap(), "\"safe\"");
+        assert_eq!(serde_json::to_string(&BlockNumber::Finalized).unwrap(), "\"finalized\"");
+        assert_eq!("finalized".parse::<BlockNumber>().unwrap(), BlockNumber::Finalized);
+        assert_eq!(BlockNumber::Safe.to_string(), "safe");
     }
 
     #[test]
@@ -823,6 +906,10 @@ mod tests {
         );
         let block: Block<H256> = serde_json::from_value(json).unwrap();
         assert!(block.author.is_none());
+        assert!(block.hash.is_none());
+        assert!(block.nonce.is_none());
+        assert!(block.total_difficulty.is_none());
+        assert_eq!(block.number, Some(0xe1a6ee.into()));
     }
 
     #[test]
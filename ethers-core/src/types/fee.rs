@@ -20,6 +20,97 @@ pub struct FeeHistory {
     pub reward: Vec<Vec<U256>>,
 }
 
+impl FeeHistory {
+    /// Returns the gas used ratio of each block along with the block's number, oldest first
+    pub fn gas_used_ratios(&self) -> impl Iterator<Item = (U256, f64)> + '_ {
+        self.gas_used_ratio
+            .iter()
+            .enumerate()
+            .map(move |(i, ratio)| (self.oldest_block + U256::from(i), *ratio))
+    }
+
+    /// Classifies how congested the network is from the average gas used ratio of the blocks,
+    /// e.g. for a "network busy" indicator. Returns `None` if the history covers no blocks.
+    pub fn congestion_level(&self) -> Option<Congestion> {
+        if self.gas_used_ratio.is_empty() {
+            return None
+        }
+        let average = self.gas_used_ratio.iter().sum::<f64>() / self.gas_used_ratio.len() as f64;
+        Some(Congestion::from_gas_used_ratio(average))
+    }
+
+    /// Projects the base fee of the block `blocks_ahead` blocks after the newest block of the
+    /// history, assuming the following blocks are as full as the average block of the history.
+    /// The base fee of the next block (`blocks_ahead` of 1, or 0) is known exactly, later ones
+    /// apply the EIP-1559 adjustment to it once per block.
+    ///
+    /// Returns `None` if the history has no base fees, e.g. on chains without EIP-1559.
+    pub fn predict_base_fee(&self, blocks_ahead: u64) -> Option<U256> {
+        let mut base_fee = *self.base_fee_per_gas.last()?;
+        let ratios = &self.gas_used_ratio;
+        let average = if ratios.is_empty() {
+            // blocks at the gas target leave the base fee unchanged
+            0.5
+        } else {
+            ratios.iter().sum::<f64>() / ratios.len() as f64
+        };
+        for _ in 1..blocks_ahead {
+            base_fee = next_base_fee(base_fee, average);
+        }
+        Some(base_fee)
+    }
+}
+
+/// The base fee changes by at most `1 / BASE_FEE_MAX_CHANGE_DENOMINATOR` from block to block
+pub const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Returns the base fee of the block following a block with the given base fee and gas used
+/// ratio, as specified by EIP-1559. Blocks above the gas target of half the gas limit raise the
+/// base fee by up to 12.5%, blocks below it lower it by up to 12.5%.
+pub fn next_base_fee(base_fee: U256, gas_used_ratio: f64) -> U256 {
+    const PRECISION: i64 = 1_000_000;
+    // (gas used - gas target) / gas target, in millionths
+    let deviation =
+        ((gas_used_ratio.clamp(0.0, 1.0) * 2.0 - 1.0) * PRECISION as f64).round() as i64;
+    let change = |deviation: i64| {
+        base_fee * U256::from(deviation) / (PRECISION as u64 * BASE_FEE_MAX_CHANGE_DENOMINATOR)
+    };
+    match deviation {
+        0 => base_fee,
+        d if d > 0 => base_fee + change(d).max(U256::one()),
+        d => base_fee - change(-d),
+    }
+}
+
+/// How congested the network is, see [`FeeHistory::congestion_level`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Congestion {
+    /// Blocks are well below the gas target, base fees are falling
+    Low,
+    /// Blocks are around the gas target of half the gas limit
+    Medium,
+    /// Blocks are well above the gas target, base fees are rising
+    High,
+}
+
+impl Congestion {
+    /// Average gas used ratios below this are [`Congestion::Low`]
+    pub const LOW_THRESHOLD: f64 = 0.3;
+    /// Average gas used ratios from this on are [`Congestion::High`]
+    pub const HIGH_THRESHOLD: f64 = 0.7;
+
+    /// Classifies a gas used ratio
+    pub fn from_gas_used_ratio(ratio: f64) -> Self {
+        if ratio < Self::LOW_THRESHOLD {
+            Congestion::Low
+        } else if ratio < Self::HIGH_THRESHOLD {
+            Congestion::Medium
+        } else {
+            Congestion::High
+        }
+    }
+}
+
 fn from_int_or_hex<'de, D>(deserializer: D) -> Result<U256, D::Error>
 where
     D: Deserializer<'de>,
@@ -35,3 +126,69 @@ where
         IntOrHex::Hex(s) => U256::from_str(s.as_str()).map_err(serde::de::Error::custom),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(gas_used_ratio: Vec<f64>) -> FeeHistory {
+        FeeHistory {
+            base_fee_per_gas: vec![],
+            gas_used_ratio,
+            oldest_block: 100.into(),
+            reward: vec![],
+        }
+    }
+
+    #[test]
+    fn congestion_level() {
+        assert_eq!(history(vec![]).congestion_level(), None);
+        assert_eq!(history(vec![0.1, 0.2, 0.3]).congestion_level(), Some(Congestion::Low));
+        assert_eq!(history(vec![0.5, 0.2, 0.5]).congestion_level(), Some(Congestion::Medium));
+        assert_eq!(history(vec![1.0, 0.4, 1.0]).congestion_level(), Some(Congestion::High));
+
+        // the thresholds are inclusive lower bounds
+        assert_eq!(Congestion::from_gas_used_ratio(0.29), Congestion::Low);
+        assert_eq!(Congestion::from_gas_used_ratio(0.3), Congestion::Medium);
+        assert_eq!(Congestion::from_gas_used_ratio(0.69), Congestion::Medium);
+        assert_eq!(Congestion::from_gas_used_ratio(0.7), Congestion::High);
+    }
+
+    #[test]
+    fn predict_base_fee() {
+        let gwei = |n: u64| U256::from(n) * U256::exp10(9);
+
+        // full blocks raise the base fee by 12.5% per block
+        let mut full = history(vec![1.0]);
+        full.base_fee_per_gas = vec![gwei(100), gwei(112) + gwei(1) / 2];
+        assert_eq!(full.predict_base_fee(0), Some(U256::from(112_500_000_000u64)));
+        assert_eq!(full.predict_base_fee(1), Some(U256::from(112_500_000_000u64)));
+        assert_eq!(full.predict_base_fee(2), Some(U256::from(126_562_500_000u64)));
+        assert_eq!(full.predict_base_fee(3), Some(U256::from(142_382_812_500u64)));
+
+        // blocks a quarter full lower it by 6.25% per block
+        let mut quiet = history(vec![0.0, 0.5]);
+        quiet.base_fee_per_gas = vec![gwei(2), gwei(2), U256::from(1_600_000_000u64)];
+        assert_eq!(quiet.predict_base_fee(2), Some(U256::from(1_500_000_000u64)));
+        assert_eq!(quiet.predict_base_fee(3), Some(U256::from(1_406_250_000u64)));
+
+        assert_eq!(history(vec![]).predict_base_fee(2), None);
+    }
+
+    #[test]
+    fn next_base_fee_follows_eip1559() {
+        let base_fee = U256::from(1_000_000_000u64);
+        assert_eq!(next_base_fee(base_fee, 0.5), base_fee);
+        assert_eq!(next_base_fee(base_fee, 1.0), U256::from(1_125_000_000u64));
+        assert_eq!(next_base_fee(base_fee, 0.0), U256::from(875_000_000u64));
+        assert_eq!(next_base_fee(base_fee, 0.75), U256::from(1_062_500_000u64));
+        // the base fee rises by at least 1 wei above the target
+        assert_eq!(next_base_fee(U256::from(7), 0.51), U256::from(8));
+    }
+
+    #[test]
+    fn gas_used_ratios_by_block() {
+        let ratios: Vec<_> = history(vec![0.5, 0.25]).gas_used_ratios().collect();
+        assert_eq!(ratios, vec![(100.into(), 0.5), (101.into(), 0.25)]);
+    }
+}
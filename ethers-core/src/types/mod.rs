@@ -11,6 +11,7 @@ pub mod transaction;
 pub use transaction::{
     eip1559::Eip1559TransactionRequest,
     eip2930::Eip2930TransactionRequest,
+    eip4844::{BlobSidecar, Eip4844TransactionRequest},
     request::TransactionRequest,
     response::{Transaction, TransactionReceipt},
 };
@@ -34,7 +35,7 @@ mod bytes;
 pub use self::bytes::{deserialize_bytes, serialize_bytes, Bytes, ParseBytesError};
 
 mod block;
-pub use block::{Block, BlockId, BlockNumber, TimeError};
+pub use block::{Block, BlockId, BlockNumber, BlockSummary, TimeError};
 
 #[cfg(feature = "celo")]
 pub use block::Randomness;
@@ -73,4 +74,7 @@ pub use other::OtherFields;
 pub mod serde_helpers;
 
 mod syncing;
-pub use syncing::{SyncProgress, SyncingStatus};
+pub use syncing::{SyncProgress, SyncStage, SyncingStatus};
+
+mod withdrawal;
+pub use withdrawal::Withdrawal;
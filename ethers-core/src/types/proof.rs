@@ -20,6 +20,31 @@ pub struct EIP1186ProofResponse {
     pub storage_proof: Vec<StorageProof>,
 }
 
+/// An account's state, as returned by `eth_getAccount`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    /// The balance of the account in wei
+    pub balance: U256,
+    /// The number of transactions sent from the account
+    pub nonce: U256,
+    /// The keccak256 hash of the account's code
+    pub code_hash: H256,
+    /// The root hash of the account's storage trie
+    pub storage_root: H256,
+}
+
+impl From<EIP1186ProofResponse> for Account {
+    fn from(proof: EIP1186ProofResponse) -> Self {
+        Account {
+            balance: proof.balance,
+            nonce: proof.nonce.as_u64().into(),
+            code_hash: proof.code_hash,
+            storage_root: proof.storage_hash,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
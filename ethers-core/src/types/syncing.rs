@@ -62,12 +62,25 @@ impl<'de> Deserialize<'de> for SyncingStatus {
 /// > highestBlock: QUANTITY - The estimated highest block
 ///
 /// Geth returns additional fields: <https://github.com/ethereum/go-ethereum/blob/0ce494b60cd00d70f1f9f2dd0b9bfbd76204168a/ethclient/ethclient.go#L597-L617>
+///
+/// Erigon reports the progress of its staged sync in `stages` instead, and omits `startingBlock`,
+/// which then defaults to zero.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncProgress {
     pub current_block: U64,
     pub highest_block: U64,
+    #[serde(default)]
     pub starting_block: U64,
+    /// The block reached by each stage of Erigon's staged sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stages: Option<Vec<SyncStage>>,
+    /// The number of blocks whose transactions Geth has indexed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_index_finished_blocks: Option<U64>,
+    /// The number of blocks whose transactions Geth has yet to index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_index_remaining_blocks: Option<U64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pulled_states: Option<U64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -98,6 +111,15 @@ pub struct SyncProgress {
     pub synced_storage_bytes: Option<U64>,
 }
 
+/// The progress of a stage of Erigon's staged sync
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SyncStage {
+    #[serde(alias = "stageName")]
+    pub stage_name: String,
+    #[serde(alias = "blockNumber")]
+    pub block_number: U64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,6 +154,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn deserialize_sync_erigon() {
+        let s = r#"{
+        "currentBlock": "0x0",
+        "highestBlock": "0xf4a3a2",
+        "stages": [
+            { "stage_name": "Snapshots", "block_number": "0xf3ba27" },
+            { "stage_name": "Headers", "block_number": "0xf4a3a2" },
+            { "stage_name": "Execution", "block_number": "0x0" }
+        ]
+    }"#;
+
+        let sync: SyncingStatus = serde_json::from_str(s).unwrap();
+        let progress = match sync {
+            SyncingStatus::IsSyncing(progress) => progress,
+            SyncingStatus::IsFalse => panic!("unexpected variant"),
+        };
+        assert_eq!(progress.starting_block, U64::zero());
+        assert_eq!(progress.highest_block, 0xf4a3a2u64.into());
+        let stages = progress.stages.unwrap();
+        assert_eq!(stages.len(), 3);
+        assert_eq!(
+            stages[0],
+            SyncStage { stage_name: "Snapshots".to_string(), block_number: 0xf3ba27u64.into() }
+        );
+        assert_eq!(stages[2].block_number, U64::zero());
+    }
+
+    #[test]
+    fn deserialize_sync_geth_tx_index() {
+        let s = r#"{
+        "currentBlock": "0x10",
+        "highestBlock": "0x10",
+        "startingBlock": "0x0",
+        "healedBytecodeBytes": "0x20",
+        "txIndexFinishedBlocks": "0x8",
+        "txIndexRemainingBlocks": "0x9"
+    }"#;
+
+        let sync: SyncingStatus = serde_json::from_str(s).unwrap();
+        let progress = match sync {
+            SyncingStatus::IsSyncing(progress) => progress,
+            SyncingStatus::IsFalse => panic!("unexpected variant"),
+        };
+        assert_eq!(progress.healed_bytecode_bytes, Some(0x20u64.into()));
+        assert_eq!(progress.tx_index_finished_blocks, Some(8u64.into()));
+        assert_eq!(progress.tx_index_remaining_blocks, Some(9u64.into()));
+        assert_eq!(progress.stages, None);
+    }
+
     #[test]
     fn deserialize_sync_minimal() {
         let s = r#"{
@@ -145,8 +217,16 @@ mod tests {
             SyncingStatus::IsFalse => {
                 panic!("unexpected variant")
             }
-            SyncingStatus::IsSyncing(_) => {}
+            SyncingStatus::IsSyncing(ref progress) => {
+                assert_eq!(progress.starting_block, 0xea97eeu64.into());
+                assert_eq!(progress.stages, None);
+            }
         }
+        // the fields which were not returned are not serialized either
+        assert_eq!(
+            serde_json::to_value(&sync).unwrap(),
+            serde_json::from_str::<serde_json::Value>(s).unwrap()
+        );
     }
 
     #[test]
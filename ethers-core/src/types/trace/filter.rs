@@ -1,5 +1,8 @@
 //! Types for the Parity Transaction-Trace Filtering API
-use crate::types::{Address, BlockNumber, Bytes, H160, H256, U256};
+use crate::{
+    abi::{Abi, Token},
+    types::{Address, BlockNumber, Bytes, H160, H256, U256},
+};
 use serde::{Deserialize, Serialize};
 
 /// Trace filter
@@ -107,6 +110,52 @@ pub struct Trace {
     pub error: Option<String>,
 }
 
+impl Trace {
+    /// Decodes the input of the traced call with the ABI of the called contract.
+    ///
+    /// Returns `None` if the trace is not a call, or if its selector does not match a function of
+    /// the ABI or the arguments fail to decode.
+    pub fn decode_call(&self, abi: &Abi) -> Option<DecodedCall> {
+        let input = match self.action {
+            Action::Call(ref call) => &call.input,
+            _ => return None,
+        };
+        if input.len() < 4 {
+            return None
+        }
+        let (selector, args) = input.split_at(4);
+        let function = abi.functions().find(|f| f.short_signature() == selector)?;
+        let args = function.decode_input(args).ok()?;
+        Some(DecodedCall { name: function.name.clone(), args })
+    }
+}
+
+/// A call of a [`Trace`] decoded with the ABI of the called contract
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedCall {
+    /// The name of the called function
+    pub name: String,
+    /// The decoded arguments of the call
+    pub args: Vec<Token>,
+}
+
+/// A [`Trace`] along with its decoded call, see [`Trace::decode_call`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedTrace {
+    /// The raw trace
+    pub trace: Trace,
+    /// The decoded call, `None` if the trace could not be decoded with the ABI
+    pub call: Option<DecodedCall>,
+}
+
+impl DecodedTrace {
+    /// Decodes the trace with the given ABI
+    pub fn new(trace: Trace, abi: &Abi) -> Self {
+        let call = trace.decode_call(abi);
+        Self { trace, call }
+    }
+}
+
 /// Response
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(untagged)]
@@ -357,6 +406,47 @@ mod tests {
         "type": "reward"
     }"#;
 
+    #[test]
+    fn test_decode_trace_call() {
+        // the top level call of the transaction in `example-trace-str.rs`
+        let trace: Trace = serde_json::from_value(serde_json::json!({
+            "action": {
+                "callType": "call",
+                "from": "0x01f0eb5c4b0a9d8285b67195f5f10ce22971a102",
+                "gas": "0xa5f8",
+                "input": "0x1a695230000000000000000000000000c227a75b32ed37d3f9d6341b9904d003dad3b1b3",
+                "to": "0x0b95993a39a363d99280ac950f5e4536ab5c5566",
+                "value": "0x1550f7dca70000"
+            },
+            "blockHash": "0x6474a53a9ebf72d306a1406ec12ded12e210b6c3141b4373bfb3a3cea987dfb8",
+            "blockNumber": 988775,
+            "result": { "gasUsed": "0x1ddf", "output": "0x" },
+            "subtraces": 1,
+            "traceAddress": [],
+            "transactionHash": "0x342c284238149db221f9d87db87f90ffad7ac0aac57c0c480142f4c21b63f652",
+            "transactionPosition": 1,
+            "type": "call"
+        }))
+        .unwrap();
+        let abi = crate::abi::parse_abi(&[
+            "function balanceOf(address owner) view returns (uint256)",
+            "function transfer(address to)",
+        ])
+        .unwrap();
+
+        let call = trace.decode_call(&abi).unwrap();
+        assert_eq!(call.name, "transfer");
+        let to: Address = "0xc227a75b32ed37d3f9d6341b9904d003dad3b1b3".parse().unwrap();
+        assert_eq!(call.args, vec![Token::Address(to)]);
+
+        // the selector of this call is not part of the ABI
+        let trace: Trace = serde_json::from_str(EXAMPLE_TRACE_CALL).unwrap();
+        assert_eq!(trace.decode_call(&abi), None);
+        // and create traces have no call to decode
+        let trace: Trace = serde_json::from_str(EXAMPLE_TRACE_CREATE).unwrap();
+        assert_eq!(DecodedTrace::new(trace, &abi).call, None);
+    }
+
     #[test]
     fn test_deserialize_trace() {
         let _trace: Trace = serde_json::from_str(EXAMPLE_TRACE_CALL).unwrap();
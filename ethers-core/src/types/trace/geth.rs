@@ -1,16 +1,124 @@
-use crate::types::{Bytes, H256, U256};
-use serde::{Deserialize, Serialize, Serializer};
+use crate::types::{Address, Bytes, H256, U256};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::BTreeMap;
 
 // https://github.com/ethereum/go-ethereum/blob/a9ef135e2dd53682d106c6a2aede9187026cc1de/eth/tracers/logger/logger.go#L406-L411
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct GethTrace {
     pub failed: bool,
     pub gas: u64,
-    #[serde(serialize_with = "serialize_bytes", rename = "returnValue")]
     pub return_value: Bytes,
-    #[serde(rename = "structLogs")]
     pub struct_logs: Vec<StructLog>,
+    /// The output of a built-in tracer other than the struct logger, e.g. `prestateTracer` or
+    /// `callTracer`. The other fields are left to their defaults for such traces, and are not
+    /// serialized.
+    pub tracer_output: BTreeMap<String, serde_json::Value>,
+}
+
+impl Serialize for GethTrace {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct StructLoggerTrace<'a> {
+            failed: bool,
+            gas: u64,
+            #[serde(serialize_with = "serialize_bytes", rename = "returnValue")]
+            return_value: &'a Bytes,
+            #[serde(rename = "structLogs")]
+            struct_logs: &'a [StructLog],
+        }
+
+        // the output of other tracers is written as is, so that it is read back the same
+        if !self.tracer_output.is_empty() {
+            return self.tracer_output.serialize(serializer)
+        }
+        StructLoggerTrace {
+            failed: self.failed,
+            gas: self.gas,
+            return_value: &self.return_value,
+            struct_logs: &self.struct_logs,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GethTrace {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct StructLoggerTrace {
+            failed: bool,
+            gas: u64,
+            #[serde(rename = "returnValue")]
+            return_value: Bytes,
+            #[serde(rename = "structLogs")]
+            struct_logs: Vec<StructLog>,
+        }
+
+        // the struct logger always returns `structLogs`, the output of other tracers is kept as
+        // is, as their fields may clash with the struct logger's, e.g. the hex `gas` of the
+        // `callTracer`
+        let output = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+        if !output.is_empty() && !output.contains_key("structLogs") {
+            return Ok(GethTrace { tracer_output: output, ..Default::default() })
+        }
+        let trace: StructLoggerTrace =
+            serde_json::from_value(serde_json::Value::Object(output.into_iter().collect()))
+                .map_err(D::Error::custom)?;
+        Ok(GethTrace {
+            failed: trace.failed,
+            gas: trace.gas,
+            return_value: trace.return_value,
+            struct_logs: trace.struct_logs,
+            tracer_output: BTreeMap::new(),
+        })
+    }
+}
+
+impl GethTrace {
+    /// Decodes the output of the `prestateTracer`
+    pub fn into_prestate(self) -> Result<PreStateTrace, serde_json::Error> {
+        serde_json::from_value(serde_json::Value::Object(self.tracer_output.into_iter().collect()))
+    }
+
+    /// Decodes the output of the `prestateTracer` run with `diffMode` enabled
+    pub fn into_prestate_diff(self) -> Result<PreStateDiff, serde_json::Error> {
+        serde_json::from_value(serde_json::Value::Object(self.tracer_output.into_iter().collect()))
+    }
+}
+
+/// The state of every account touched by a transaction before it was executed, as returned by the
+/// `prestateTracer`
+///
+/// See <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#prestate-tracer>
+pub type PreStateTrace = BTreeMap<Address, AccountState>;
+
+/// The state changes of a transaction, as returned by the `prestateTracer` with `diffMode`
+/// enabled. `pre` holds the modified accounts before the transaction, `post` the fields which
+/// changed after it. Storage slots in `pre` which are missing in `post` were cleared.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreStateDiff {
+    #[serde(default)]
+    pub pre: PreStateTrace,
+    #[serde(default)]
+    pub post: PreStateTrace,
+}
+
+/// The state of an account in a [`PreStateTrace`], fields are omitted by the node when empty
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<H256, H256>>,
 }
 
 // https://github.com/ethereum/go-ethereum/blob/366d2169fbc0e0f803b68c042b77b6b480836dbc/eth/tracers/logger/logger.go#L413-L426
@@ -11,6 +11,9 @@ pub use filter::*;
 mod geth;
 pub use geth::*;
 
+mod vm;
+pub use vm::*;
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 /// Description of the type of trace to make
 pub enum TraceType {
@@ -173,6 +176,7 @@ pub struct StorageDiff {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::Address;
 
     // tx: https://etherscan.io/tx/0x4a91b11dbd2b11c308cfe7775eac2036f20c501691e3f8005d83b2dcce62d6b5
     // using the 'trace_replayTransaction' API function
@@ -202,4 +206,117 @@ mod tests {
     fn test_deserialize_blocktraces() {
         let _traces: Vec<BlockTrace> = serde_json::from_str(EXAMPLE_TRACES).unwrap();
     }
+
+    #[test]
+    fn test_deserialize_prestate_trace() {
+        // `debug_traceTransaction` with `{"tracer": "prestateTracer"}`
+        let trace: GethTrace = serde_json::from_str(
+            r#"{
+                "0x0000000000000000000000000000000000000002": {
+                    "balance": "0x0"
+                },
+                "0x008b3b2f992c0e14edaa6e2c662bec549caa8df1": {
+                    "balance": "0x2638035a26d133809"
+                },
+                "0x35a9f94af726f07b5162df7e828cc9dc8439e7d0": {
+                    "balance": "0x7a48734599f7284",
+                    "nonce": 1133
+                },
+                "0xc8ba32cab1757528daf49033e3673fae77dcf05d": {
+                    "balance": "0x0",
+                    "code": "0x608060405234801561001057600080fd5b50",
+                    "nonce": 1,
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000000": "0x000000000000000000000000000000000000000000000000000000000024aea6",
+                        "0x59fb7853eb21f604d010b94c123acbeae621f09ce15ee5d7616485b1e78a72e9": "0x00000000000000c42b56a52aedf18667c8ae258a0280a8912641c80c48cd9548"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let prestate = trace.into_prestate().unwrap();
+        assert_eq!(prestate.len(), 4);
+
+        let sender: Address = "0x35a9f94af726f07b5162df7e828cc9dc8439e7d0".parse().unwrap();
+        assert_eq!(prestate[&sender].nonce, Some(1133));
+        assert_eq!(prestate[&sender].storage, None);
+
+        let contract: Address = "0xc8ba32cab1757528daf49033e3673fae77dcf05d".parse().unwrap();
+        let storage = prestate[&contract].storage.as_ref().unwrap();
+        assert_eq!(storage.len(), 2);
+        assert_eq!(storage[&H256::zero()], H256::from_low_u64_be(0x24aea6));
+        let code: Bytes = "0x608060405234801561001057600080fd5b50".parse().unwrap();
+        assert_eq!(prestate[&contract].code, Some(code));
+    }
+
+    #[test]
+    fn test_deserialize_geth_trace() {
+        let trace: GethTrace = serde_json::from_str(
+            r#"{ "failed": true, "gas": 21000, "returnValue": "", "structLogs": [] }"#,
+        )
+        .unwrap();
+        assert!(trace.failed);
+        assert_eq!(trace.gas, 21_000);
+        assert!(trace.tracer_output.is_empty());
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(serde_json::from_value::<GethTrace>(json).unwrap(), trace);
+
+        // `debug_traceTransaction` with `{"tracer": "callTracer"}`, whose `gas` is hex encoded
+        let trace: GethTrace = serde_json::from_str(
+            r#"{
+                "type": "CALL",
+                "from": "0x35a9f94af726f07b5162df7e828cc9dc8439e7d0",
+                "to": "0xc8ba32cab1757528daf49033e3673fae77dcf05d",
+                "value": "0x0",
+                "gas": "0x5208",
+                "gasUsed": "0x5208",
+                "input": "0x",
+                "output": "0x"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(trace.gas, 0);
+        assert_eq!(trace.tracer_output["gas"], "0x5208");
+
+        // tracer outputs are serialized as is
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json.get("structLogs"), None);
+        assert_eq!(serde_json::from_value::<GethTrace>(json).unwrap(), trace);
+
+        // an empty object is neither a struct logger trace nor a tracer's output
+        assert!(serde_json::from_str::<GethTrace>("{}").is_err());
+    }
+
+    #[test]
+    fn test_read_vm_trace() {
+        // `trace_call` with 'vmTrace' of a call running `PUSH1 0x80 PUSH1 0x40 MSTORE` and then
+        // calling a contract which returns right away
+        let trace: VMTrace = serde_json::from_str(
+            r#"{
+                "code": "0x6080604052f1",
+                "ops": [
+                    { "pc": 0, "cost": 3, "ex": { "used": 97, "push": ["0x80"], "mem": null, "store": null }, "sub": null },
+                    { "pc": 2, "cost": 3, "ex": { "used": 94, "push": ["0x40"], "mem": null, "store": null }, "sub": null },
+                    { "pc": 4, "cost": 12, "ex": { "used": 82, "push": [], "mem": { "off": 64, "data": "0x0000000000000000000000000000000000000000000000000000000000000080" }, "store": null }, "sub": null },
+                    { "pc": 5, "cost": 40, "ex": { "used": 40, "push": ["0x1"], "mem": null, "store": null }, "sub": {
+                        "code": "0x00",
+                        "ops": [
+                            { "pc": 0, "cost": 0, "ex": { "used": 2, "push": [], "mem": null, "store": null }, "sub": null }
+                        ]
+                    } }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let steps: Vec<_> = VmTraceReader::new(&trace).collect();
+        let ops: Vec<_> = steps.iter().map(|step| (step.depth, step.pc, step.op)).collect();
+        // PUSH1, PUSH1, MSTORE, CALL and the STOP of the called contract
+        assert_eq!(ops, vec![(0, 0, 0x60), (0, 2, 0x60), (0, 4, 0x52), (0, 5, 0xf1), (1, 0, 0x00)]);
+
+        assert_eq!(steps[0].stack_top, Some(0x80.into()));
+        assert_eq!(steps[2].stack_top, None);
+        assert_eq!(steps.iter().map(|step| step.gas_cost).sum::<u64>(), 58);
+    }
 }
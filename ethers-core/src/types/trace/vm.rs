@@ -0,0 +1,66 @@
+use super::VMTrace;
+use crate::types::U256;
+
+/// A single executed opcode of a flattened [`VMTrace`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpStep {
+    /// The call depth the opcode was executed at, 0 for the outermost call
+    pub depth: usize,
+    /// The program counter
+    pub pc: usize,
+    /// The opcode byte at the program counter
+    pub op: u8,
+    /// The gas cost of the opcode
+    pub gas_cost: u64,
+    /// The item the opcode placed on top of the stack, if any
+    pub stack_top: Option<U256>,
+}
+
+/// Iterator flattening a [`VMTrace`] into the sequence of executed opcodes, e.g. for finding the
+/// gas hotspots of a call.
+///
+/// The opcodes of a nested CALL/CREATE follow the opcode which started it, so the steps are
+/// yielded in execution order.
+#[derive(Debug, Clone)]
+pub struct VmTraceReader<'a> {
+    /// The traces being walked, along with the index of their next operation
+    traces: Vec<(&'a VMTrace, usize)>,
+}
+
+impl<'a> VmTraceReader<'a> {
+    pub fn new(trace: &'a VMTrace) -> Self {
+        Self { traces: vec![(trace, 0)] }
+    }
+}
+
+impl<'a> Iterator for VmTraceReader<'a> {
+    type Item = OpStep;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.traces.len().checked_sub(1)?;
+            let (trace, next) = self.traces.last_mut()?;
+            let trace: &'a VMTrace = trace;
+
+            let op = match trace.ops.get(*next) {
+                Some(op) => op,
+                None => {
+                    self.traces.pop();
+                    continue
+                }
+            };
+            *next += 1;
+            if let Some(ref sub) = op.sub {
+                self.traces.push((sub, 0));
+            }
+
+            return Some(OpStep {
+                depth,
+                pc: op.pc,
+                op: trace.code.get(op.pc).copied().unwrap_or_default(),
+                gas_cost: op.cost,
+                stack_top: op.ex.as_ref().and_then(|ex| ex.push.last().copied()),
+            })
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use super::{
     eip1559::{Eip1559RequestError, Eip1559TransactionRequest},
     eip2930::{AccessList, Eip2930RequestError, Eip2930TransactionRequest},
+    eip4844::{Eip4844TransactionRequest, BLOB_TX_TYPE},
     request::RequestError,
 };
 use crate::{
@@ -19,6 +20,7 @@ use thiserror::Error;
 /// 1. Legacy (pre-EIP2718) [`TransactionRequest`]
 /// 2. EIP2930 (state access lists) [`Eip2930TransactionRequest`]
 /// 3. EIP1559 [`Eip1559TransactionRequest`]
+/// 4. EIP4844 (blob transactions) [`Eip4844TransactionRequest`]
 ///
 /// To support Kovan and other non-London-compatbile networks, please enable
 /// the `legacy` crate feature. This will disable the `type` flag in the
@@ -37,6 +39,9 @@ pub enum TypedTransaction {
     // 0x02
     #[serde(rename = "0x02")]
     Eip1559(Eip1559TransactionRequest),
+    // 0x03
+    #[serde(rename = "0x03")]
+    Eip4844(Eip4844TransactionRequest),
 }
 
 /// An error involving a typed transaction request.
@@ -84,6 +89,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.from.as_ref(),
             Eip2930(inner) => inner.tx.from.as_ref(),
             Eip1559(inner) => inner.from.as_ref(),
+            Eip4844(inner) => inner.tx.from.as_ref(),
         }
     }
 
@@ -92,6 +98,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.from = Some(from),
             Eip2930(inner) => inner.tx.from = Some(from),
             Eip1559(inner) => inner.from = Some(from),
+            Eip4844(inner) => inner.tx.from = Some(from),
         };
         self
     }
@@ -101,6 +108,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.to.as_ref(),
             Eip2930(inner) => inner.tx.to.as_ref(),
             Eip1559(inner) => inner.to.as_ref(),
+            Eip4844(inner) => inner.tx.to.as_ref(),
         }
     }
 
@@ -114,6 +122,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.to = Some(to),
             Eip2930(inner) => inner.tx.to = Some(to),
             Eip1559(inner) => inner.to = Some(to),
+            Eip4844(inner) => inner.tx.to = Some(to),
         };
         self
     }
@@ -123,6 +132,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.nonce.as_ref(),
             Eip2930(inner) => inner.tx.nonce.as_ref(),
             Eip1559(inner) => inner.nonce.as_ref(),
+            Eip4844(inner) => inner.tx.nonce.as_ref(),
         }
     }
 
@@ -132,6 +142,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.nonce = Some(nonce),
             Eip2930(inner) => inner.tx.nonce = Some(nonce),
             Eip1559(inner) => inner.nonce = Some(nonce),
+            Eip4844(inner) => inner.tx.nonce = Some(nonce),
         };
         self
     }
@@ -141,6 +152,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.value.as_ref(),
             Eip2930(inner) => inner.tx.value.as_ref(),
             Eip1559(inner) => inner.value.as_ref(),
+            Eip4844(inner) => inner.tx.value.as_ref(),
         }
     }
 
@@ -150,6 +162,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.value = Some(value),
             Eip2930(inner) => inner.tx.value = Some(value),
             Eip1559(inner) => inner.value = Some(value),
+            Eip4844(inner) => inner.tx.value = Some(value),
         };
         self
     }
@@ -159,6 +172,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.gas.as_ref(),
             Eip2930(inner) => inner.tx.gas.as_ref(),
             Eip1559(inner) => inner.gas.as_ref(),
+            Eip4844(inner) => inner.tx.gas.as_ref(),
         }
     }
 
@@ -167,6 +181,7 @@ impl TypedTransaction {
             Legacy(inner) => &mut inner.gas,
             Eip2930(inner) => &mut inner.tx.gas,
             Eip1559(inner) => &mut inner.gas,
+            Eip4844(inner) => &mut inner.tx.gas,
         }
     }
 
@@ -176,6 +191,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.gas = Some(gas),
             Eip2930(inner) => inner.tx.gas = Some(gas),
             Eip1559(inner) => inner.gas = Some(gas),
+            Eip4844(inner) => inner.tx.gas = Some(gas),
         };
         self
     }
@@ -184,7 +200,7 @@ impl TypedTransaction {
         match self {
             Legacy(inner) => inner.gas_price,
             Eip2930(inner) => inner.tx.gas_price,
-            Eip1559(inner) => {
+            Eip1559(inner) | Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => {
                 match (inner.max_fee_per_gas, inner.max_priority_fee_per_gas) {
                     (Some(max_fee), Some(_)) => Some(max_fee),
                     // this also covers the None, None case
@@ -200,7 +216,7 @@ impl TypedTransaction {
         match self {
             Legacy(inner) => inner.gas_price = Some(gas_price),
             Eip2930(inner) => inner.tx.gas_price = Some(gas_price),
-            Eip1559(inner) => {
+            Eip1559(inner) | Eip4844(Eip4844TransactionRequest { tx: inner, .. }) => {
                 inner.max_fee_per_gas = Some(gas_price);
                 inner.max_priority_fee_per_gas = Some(gas_price);
             }
@@ -213,6 +229,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.chain_id,
             Eip2930(inner) => inner.tx.chain_id,
             Eip1559(inner) => inner.chain_id,
+            Eip4844(inner) => inner.tx.chain_id,
         }
     }
 
@@ -222,6 +239,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.chain_id = Some(chain_id),
             Eip2930(inner) => inner.tx.chain_id = Some(chain_id),
             Eip1559(inner) => inner.chain_id = Some(chain_id),
+            Eip4844(inner) => inner.tx.chain_id = Some(chain_id),
         };
         self
     }
@@ -231,6 +249,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.data.as_ref(),
             Eip2930(inner) => inner.tx.data.as_ref(),
             Eip1559(inner) => inner.data.as_ref(),
+            Eip4844(inner) => inner.tx.data.as_ref(),
         }
     }
 
@@ -239,6 +258,7 @@ impl TypedTransaction {
             Legacy(_) => None,
             Eip2930(inner) => Some(&inner.access_list),
             Eip1559(inner) => Some(&inner.access_list),
+            Eip4844(inner) => Some(&inner.tx.access_list),
         }
     }
 
@@ -247,6 +267,7 @@ impl TypedTransaction {
             Legacy(_) => {}
             Eip2930(inner) => inner.access_list = access_list,
             Eip1559(inner) => inner.access_list = access_list,
+            Eip4844(inner) => inner.tx.access_list = access_list,
         };
         self
     }
@@ -256,6 +277,7 @@ impl TypedTransaction {
             Legacy(inner) => inner.data = Some(data),
             Eip2930(inner) => inner.tx.data = Some(data),
             Eip1559(inner) => inner.data = Some(data),
+            Eip4844(inner) => inner.tx.data = Some(data),
         };
         self
     }
@@ -274,10 +296,25 @@ impl TypedTransaction {
                 encoded.extend_from_slice(&[0x2]);
                 encoded.extend_from_slice(inner.rlp_signed(signature).as_ref());
             }
+            Eip4844(inner) => {
+                encoded.extend_from_slice(&[BLOB_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp_signed(signature).as_ref());
+            }
         };
         encoded.into()
     }
 
+    /// Produces the encoding of the signed transaction sent to the node with
+    /// `eth_sendRawTransaction`. It is the same as [`rlp_signed`](Self::rlp_signed), except for
+    /// blob transactions which are wrapped together with their sidecar, see
+    /// [`Eip4844TransactionRequest::rlp_network`].
+    pub fn rlp_network(&self, signature: &Signature) -> Bytes {
+        match self {
+            Eip4844(inner) => inner.rlp_network(signature),
+            _ => self.rlp_signed(signature),
+        }
+    }
+
     pub fn rlp(&self) -> Bytes {
         let mut encoded = vec![];
         match self {
@@ -292,6 +329,10 @@ impl TypedTransaction {
                 encoded.extend_from_slice(&[0x2]);
                 encoded.extend_from_slice(inner.rlp().as_ref());
             }
+            Eip4844(inner) => {
+                encoded.extend_from_slice(&[BLOB_TX_TYPE]);
+                encoded.extend_from_slice(inner.rlp().as_ref());
+            }
         };
 
         encoded.into()
@@ -303,13 +344,17 @@ impl TypedTransaction {
         keccak256(encoded).into()
     }
 
-    /// Max cost of the transaction
+    /// Max cost of the transaction, including the blob gas of blob transactions
     pub fn max_cost(&self) -> Option<U256> {
         let gas_limit = self.gas();
         let gas_price = self.gas_price();
-        match (gas_limit, gas_price) {
-            (Some(gas_limit), Some(gas_price)) => Some(gas_limit * gas_price),
-            _ => None,
+        let cost = match (gas_limit, gas_price) {
+            (Some(gas_limit), Some(gas_price)) => gas_limit * gas_price,
+            _ => return None,
+        };
+        match self {
+            Eip4844(inner) => Some(cost + inner.blob_gas() * inner.max_fee_per_blob_gas),
+            _ => Some(cost),
         }
     }
 
@@ -397,6 +442,12 @@ impl From<Eip1559TransactionRequest> for TypedTransaction {
     }
 }
 
+impl From<Eip4844TransactionRequest> for TypedTransaction {
+    fn from(src: Eip4844TransactionRequest) -> TypedTransaction {
+        TypedTransaction::Eip4844(src)
+    }
+}
+
 impl From<&Transaction> for TypedTransaction {
     fn from(tx: &Transaction) -> TypedTransaction {
         match tx.transaction_type {
@@ -438,6 +489,12 @@ impl TypedTransaction {
             _ => None,
         }
     }
+    pub fn as_eip4844_ref(&self) -> Option<&Eip4844TransactionRequest> {
+        match self {
+            Eip4844(tx) => Some(tx),
+            _ => None,
+        }
+    }
 
     pub fn as_legacy_mut(&mut self) -> Option<&mut TransactionRequest> {
         match self {
@@ -457,12 +514,19 @@ impl TypedTransaction {
             _ => None,
         }
     }
+    pub fn as_eip4844_mut(&mut self) -> Option<&mut Eip4844TransactionRequest> {
+        match self {
+            Eip4844(tx) => Some(tx),
+            _ => None,
+        }
+    }
 }
 
 impl TypedTransaction {
     fn into_eip1559(self) -> Eip1559TransactionRequest {
         match self {
             Eip1559(tx) => tx,
+            Eip4844(tx) => tx.tx,
             _ => Eip1559TransactionRequest {
                 from: self.from().copied(),
                 to: self.to().cloned(),
@@ -489,7 +553,7 @@ impl TypedTransaction {
         match self {
             Legacy(tx) => tx,
             Eip2930(tx) => tx.tx,
-            Eip1559(_) => TransactionRequest {
+            Eip1559(_) | Eip4844(_) => TransactionRequest {
                 from: self.from().copied(),
                 to: self.to().cloned(),
                 nonce: self.nonce().copied(),
@@ -525,7 +589,7 @@ impl TypedTransaction {
         match self {
             Eip2930(tx) => tx,
             Legacy(tx) => Eip2930TransactionRequest { tx, access_list },
-            Eip1559(_) => Eip2930TransactionRequest {
+            Eip1559(_) | Eip4844(_) => Eip2930TransactionRequest {
                 tx: TransactionRequest {
                     from: self.from().copied(),
                     to: self.to().cloned(),
@@ -45,6 +45,74 @@ impl From<Vec<AccessListItem>> for AccessList {
     }
 }
 
+// EIP-2929 access costs and EIP-2930 access list costs
+const COLD_ACCOUNT_ACCESS_COST: i64 = 2600;
+const COLD_SLOAD_COST: i64 = 2100;
+const WARM_STORAGE_READ_COST: i64 = 100;
+const ACCESS_LIST_ADDRESS_COST: i64 = 2400;
+const ACCESS_LIST_STORAGE_KEY_COST: i64 = 1900;
+
+impl AccessList {
+    /// Annotates each entry of the access list with the gas it is estimated to save.
+    ///
+    /// Listing an address or storage key costs gas upfront and makes its first access warm. This
+    /// pays off for cold addresses and storage keys, while addresses which are warm anyway, i.e.
+    /// the precompiles and the given `warm` addresses (usually the sender and the recipient), only
+    /// add to the cost of the transaction.
+    pub fn report(&self, warm: &[Address]) -> AccessListReport {
+        let entries: Vec<_> = self
+            .0
+            .iter()
+            .map(|item| {
+                let cold = !warm.contains(&item.address) && !is_precompile(item.address);
+                let address_saved = if cold {
+                    COLD_ACCOUNT_ACCESS_COST - WARM_STORAGE_READ_COST - ACCESS_LIST_ADDRESS_COST
+                } else {
+                    -ACCESS_LIST_ADDRESS_COST
+                };
+                let key_saved =
+                    COLD_SLOAD_COST - WARM_STORAGE_READ_COST - ACCESS_LIST_STORAGE_KEY_COST;
+                AccessListEntryReport {
+                    address: item.address,
+                    cold,
+                    storage_keys: item.storage_keys.len(),
+                    gas_saved: address_saved + key_saved * item.storage_keys.len() as i64,
+                }
+            })
+            .collect();
+        let gas_saved = entries.iter().map(|entry| entry.gas_saved).sum();
+        AccessListReport { entries, gas_saved }
+    }
+}
+
+/// Returns `true` if the address is one of the precompiles, which are always warm
+fn is_precompile(address: Address) -> bool {
+    address[..19].iter().all(|byte| *byte == 0) && (1..=9).contains(&address[19])
+}
+
+/// The estimated gas savings of an access list, see [`AccessList::report`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessListReport {
+    /// The savings of each entry, in the order of the access list
+    pub entries: Vec<AccessListEntryReport>,
+    /// The total gas saved by the access list, negative if it costs more than it saves
+    pub gas_saved: i64,
+}
+
+/// The estimated gas savings of a single access list entry
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessListEntryReport {
+    /// The listed address
+    pub address: Address,
+    /// Whether the address would be cold without the access list, i.e. whether listing it
+    /// converts its first access from cold to warm
+    pub cold: bool,
+    /// The number of listed storage keys of the address
+    pub storage_keys: usize,
+    /// The gas saved by the entry, negative if it costs more than it saves
+    pub gas_saved: i64,
+}
+
 impl TransactionRequest {
     /// Sets the `access_list` field in the transaction (converts the [`TransactionRequest`] to
     /// an [`Eip2930TransactionRequest`])
@@ -193,6 +261,27 @@ mod tests {
     use crate::types::{transaction::eip2718::TypedTransaction, U256};
     use std::str::FromStr;
 
+    #[test]
+    fn access_list_report() {
+        let recipient = Address::repeat_byte(1);
+        let token = Address::repeat_byte(2);
+        let ecrecover = Address::from_low_u64_be(1);
+        let access_list = AccessList(vec![
+            AccessListItem { address: recipient, storage_keys: vec![H256::zero(); 2] },
+            AccessListItem { address: token, storage_keys: vec![H256::zero(); 3] },
+            AccessListItem { address: ecrecover, storage_keys: vec![] },
+        ]);
+
+        let report = access_list.report(&[recipient]);
+        let cold: Vec<_> = report.entries.iter().map(|entry| entry.cold).collect();
+        assert_eq!(cold, vec![false, true, false]);
+        // every listed storage key saves 100 gas, as does listing a cold address, while listing a
+        // warm address costs 2400 gas
+        let saved: Vec<_> = report.entries.iter().map(|entry| entry.gas_saved).collect();
+        assert_eq!(saved, vec![-2200, 400, -2400]);
+        assert_eq!(report.gas_saved, -4200);
+    }
+
     #[test]
     #[cfg_attr(feature = "celo", ignore)]
     // https://github.com/ethereum/go-ethereum/blob/c503f98f6d5e80e079c1d8a3601d188af2a899da/core/types/transaction_test.go#L59-L67
@@ -0,0 +1,259 @@
+use super::{eip1559::Eip1559TransactionRequest, normalize_v};
+use crate::{
+    types::{Bytes, Signature, H256, U256, U64},
+    utils::keccak256,
+};
+use rlp::RlpStream;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The EIP-2718 type of blob transactions
+pub const BLOB_TX_TYPE: u8 = 0x03;
+
+/// The version byte of the versioned hash of a KZG commitment
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The size of a blob in bytes
+pub const BYTES_PER_BLOB: usize = 131_072;
+
+/// The blob gas used by each blob of a transaction
+pub const GAS_PER_BLOB: u64 = 131_072;
+
+/// EIP-4844 transactions have 11 fields
+const NUM_TX_FIELDS: usize = 11;
+
+/// The blobs of an EIP-4844 transaction, along with their KZG commitments and proofs.
+///
+/// The sidecar is not part of the signed transaction, which only commits to the versioned hashes
+/// of the commitments. It is sent to the node alongside the transaction, see
+/// [`Eip4844TransactionRequest::rlp_network`]. The commitments and proofs are computed from the
+/// blobs with the KZG trusted setup, which is not part of this crate.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct BlobSidecar {
+    /// The blobs, [`BYTES_PER_BLOB`] bytes each
+    pub blobs: Vec<Bytes>,
+    /// The KZG commitment of each blob
+    pub commitments: Vec<Bytes>,
+    /// The KZG proof of each blob
+    pub proofs: Vec<Bytes>,
+}
+
+impl BlobSidecar {
+    /// Returns the versioned hashes of the blob commitments, in the order of the blobs
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        self.commitments.iter().map(|commitment| kzg_to_versioned_hash(commitment)).collect()
+    }
+}
+
+/// Returns the versioned hash of a KZG commitment: its SHA-256 hash with the first byte replaced by
+/// the version
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256(hash)
+}
+
+/// An EIP-4844 transaction is an EIP-1559 transaction carrying blobs, which it commits to with the
+/// versioned hashes of their KZG commitments.
+///
+/// Blob transactions must be sent to an address, contract creations are not allowed. Their
+/// `max_fee_per_blob_gas` has no default, as a zero fee makes the transaction invalid.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct Eip4844TransactionRequest {
+    #[serde(flatten)]
+    pub tx: Eip1559TransactionRequest,
+
+    /// The maximum fee per gas the sender is willing to pay for blob gas
+    #[serde(rename = "maxFeePerBlobGas")]
+    pub max_fee_per_blob_gas: U256,
+
+    /// The versioned hashes of the blobs carried by the transaction, computed from the sidecar
+    /// when the transaction is filled if empty, see [`Self::fill_versioned_hashes`]
+    #[serde(rename = "blobVersionedHashes", default)]
+    pub blob_versioned_hashes: Vec<H256>,
+
+    /// The blobs carried by the transaction, sent alongside it with `eth_sendRawTransaction`
+    #[serde(skip)]
+    pub blob_sidecar: Option<BlobSidecar>,
+}
+
+impl Eip4844TransactionRequest {
+    pub fn new<T: Into<U256>>(tx: Eip1559TransactionRequest, max_fee_per_blob_gas: T) -> Self {
+        Self {
+            tx,
+            max_fee_per_blob_gas: max_fee_per_blob_gas.into(),
+            blob_versioned_hashes: Vec::new(),
+            blob_sidecar: None,
+        }
+    }
+
+    /// Sets the `max_fee_per_blob_gas` field in the transaction to the provided value
+    #[must_use]
+    pub fn max_fee_per_blob_gas<T: Into<U256>>(mut self, max_fee_per_blob_gas: T) -> Self {
+        self.max_fee_per_blob_gas = max_fee_per_blob_gas.into();
+        self
+    }
+
+    /// Sets the `blob_sidecar` field in the transaction to the provided value
+    #[must_use]
+    pub fn blob_sidecar(mut self, sidecar: BlobSidecar) -> Self {
+        self.blob_sidecar = Some(sidecar);
+        self
+    }
+
+    /// Sets the `blob_versioned_hashes` field to the versioned hashes of the sidecar's blobs, if
+    /// the transaction has a sidecar and no versioned hashes yet
+    pub fn fill_versioned_hashes(&mut self) {
+        if let Some(ref sidecar) = self.blob_sidecar {
+            if self.blob_versioned_hashes.is_empty() {
+                self.blob_versioned_hashes = sidecar.versioned_hashes();
+            }
+        }
+    }
+
+    /// Returns the blob gas used by the transaction
+    pub fn blob_gas(&self) -> U256 {
+        U256::from(GAS_PER_BLOB) * self.blob_versioned_hashes.len()
+    }
+
+    /// Gets the unsigned transaction's RLP encoding
+    pub fn rlp(&self) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(NUM_TX_FIELDS);
+        self.rlp_base(&mut rlp);
+        rlp.out().freeze().into()
+    }
+
+    /// Hashes the transaction's data for signing
+    pub fn sighash(&self) -> H256 {
+        let encoded = [&[BLOB_TX_TYPE][..], self.rlp().as_ref()].concat();
+        keccak256(encoded).into()
+    }
+
+    /// Produces the RLP encoding of the transaction with the provided signature
+    pub fn rlp_signed(&self, signature: &Signature) -> Bytes {
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(NUM_TX_FIELDS + 3);
+        self.rlp_base(&mut rlp);
+
+        // if the chain_id is none we assume mainnet and choose one
+        let chain_id = self.tx.chain_id.unwrap_or_else(U64::one);
+
+        // append the signature
+        let v = normalize_v(signature.v, chain_id);
+        rlp.append(&v);
+        rlp.append(&signature.r);
+        rlp.append(&signature.s);
+        rlp.out().freeze().into()
+    }
+
+    /// Produces the network encoding of the signed transaction wrapped together with its blob
+    /// sidecar, as sent to the node with `eth_sendRawTransaction`:
+    /// `0x03 || rlp([tx_payload_body, blobs, commitments, proofs])`. Transactions without a
+    /// sidecar are encoded as `0x03 || tx_payload_body`.
+    pub fn rlp_network(&self, signature: &Signature) -> Bytes {
+        let sidecar = match self.blob_sidecar {
+            Some(ref sidecar) => sidecar,
+            None => return [&[BLOB_TX_TYPE][..], &self.rlp_signed(signature)].concat().into(),
+        };
+        let mut rlp = RlpStream::new();
+        rlp.begin_list(4);
+        rlp.append_raw(&self.rlp_signed(signature), 1);
+        for items in [&sidecar.blobs, &sidecar.commitments, &sidecar.proofs] {
+            rlp.begin_list(items.len());
+            for item in items {
+                rlp.append(&item.as_ref());
+            }
+        }
+        [&[BLOB_TX_TYPE][..], &rlp.out()].concat().into()
+    }
+
+    fn rlp_base(&self, rlp: &mut RlpStream) {
+        self.tx.rlp_base(rlp);
+        rlp.append(&self.max_fee_per_blob_gas);
+        rlp.append_list(&self.blob_versioned_hashes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{transaction::eip2718::TypedTransaction, Address};
+    use rlp::Rlp;
+
+    #[test]
+    fn blob_tx_network_encoding() {
+        // the commitment and proof of the zero blob are the point at infinity
+        let infinity = Bytes::from([&[0xc0][..], &[0u8; 47]].concat());
+        let sidecar = BlobSidecar {
+            blobs: vec![vec![0u8; BYTES_PER_BLOB].into()],
+            commitments: vec![infinity.clone()],
+            proofs: vec![infinity],
+        };
+
+        let mut tx = Eip4844TransactionRequest::new(
+            Eip1559TransactionRequest::new()
+                .to(Address::repeat_byte(1))
+                .nonce(0)
+                .gas(21_000)
+                .max_fee_per_gas(10)
+                .max_priority_fee_per_gas(1)
+                .chain_id(1),
+            1,
+        )
+        .blob_sidecar(sidecar.clone());
+        tx.fill_versioned_hashes();
+
+        let expected: H256 =
+            "0x010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014".parse().unwrap();
+        assert_eq!(tx.blob_versioned_hashes, vec![expected]);
+
+        let signature = Signature { r: 1.into(), s: 2.into(), v: 0 };
+        let network = tx.rlp_network(&signature);
+        assert_eq!(network[0], BLOB_TX_TYPE);
+
+        let rlp = Rlp::new(&network[1..]);
+        assert_eq!(rlp.item_count().unwrap(), 4);
+        assert_eq!(rlp.at(0).unwrap().as_raw(), tx.rlp_signed(&signature).as_ref());
+        let blobs = rlp.at(1).unwrap();
+        assert_eq!(blobs.item_count().unwrap(), 1);
+        assert_eq!(blobs.at(0).unwrap().data().unwrap().len(), BYTES_PER_BLOB);
+        let commitments: Vec<Vec<u8>> = rlp.list_at(2).unwrap();
+        assert_eq!(commitments, vec![sidecar.commitments[0].to_vec()]);
+
+        // the signed payload commits to the versioned hash, not to the blob
+        let signed = rlp.at(0).unwrap();
+        assert_eq!(signed.item_count().unwrap(), NUM_TX_FIELDS + 3);
+        let hashes: Vec<H256> = signed.list_at(10).unwrap();
+        assert_eq!(hashes, vec![expected]);
+
+        // without a sidecar, only the signed payload is sent
+        tx.blob_sidecar = None;
+        let network = tx.rlp_network(&signature);
+        assert_eq!(network[1..], tx.rlp_signed(&signature)[..]);
+    }
+
+    #[test]
+    fn typed_blob_tx() {
+        let mut tx = Eip4844TransactionRequest::new(
+            Eip1559TransactionRequest::new()
+                .to(Address::repeat_byte(1))
+                .gas(21_000)
+                .max_fee_per_gas(10)
+                .max_priority_fee_per_gas(1),
+            2,
+        );
+        tx.blob_versioned_hashes = vec![H256::repeat_byte(1)];
+        let tx: TypedTransaction = tx.into();
+
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(json["type"], "0x03");
+        assert_eq!(json["maxFeePerBlobGas"], "0x2");
+        assert_eq!(json["blobVersionedHashes"], serde_json::json!([H256::repeat_byte(1)]));
+        assert_eq!(serde_json::from_value::<TypedTransaction>(json).unwrap(), tx);
+
+        assert_eq!(tx.rlp()[0], BLOB_TX_TYPE);
+        // the blob gas is paid on top of the execution gas
+        assert_eq!(tx.max_cost(), Some(U256::from(21_000 * 10 + GAS_PER_BLOB * 2)));
+    }
+}
@@ -1,7 +1,7 @@
 //! Transaction types
 use super::{
-    decode_signature, eip2718::TypedTransaction, eip2930::AccessList, normalize_v, rlp_opt,
-    rlp_opt_list,
+    decode_signature, eip2718::TypedTransaction, eip2930::AccessList, extract_chain_id,
+    normalize_v, rlp_opt, rlp_opt_list,
 };
 use crate::{
     types::{Address, Bloom, Bytes, Log, Signature, SignatureError, H256, U256, U64},
@@ -326,6 +326,16 @@ impl Transaction {
         signature.recover(typed_tx.sighash())
     }
 
+    /// Recover the sender of the tx from signature and check that it matches the from field,
+    /// e.g. to validate a transaction received out-of-band before relaying it
+    pub fn recover_sender(&self) -> Result<Address, SignatureError> {
+        let from = self.recover_from()?;
+        if from != self.from {
+            return Err(SignatureError::VerificationError(self.from, from))
+        }
+        Ok(from)
+    }
+
     /// Recover the sender of the tx from signature and set the from field
     pub fn recover_from_mut(&mut self) -> Result<Address, SignatureError> {
         let from = self.recover_from()?;
@@ -373,6 +383,11 @@ impl Decodable for Transaction {
         txn.s = sig.s;
         txn.v = sig.v.into();
 
+        // the chain id of legacy transactions is encoded in `v`, see EIP-155
+        if txn.chain_id.is_none() {
+            txn.chain_id = extract_chain_id(sig.v).map(|id| U256::from(id.as_u64()));
+        }
+
         Ok(txn)
     }
 }
@@ -427,6 +442,20 @@ pub struct TransactionReceipt {
     pub effective_gas_price: Option<U256>,
 }
 
+impl TransactionReceipt {
+    /// Returns whether the transaction succeeded, i.e. its status is 1. Receipts predating
+    /// [EIP-658](https://eips.ethereum.org/EIPS/eip-658) have no status and are reported as failed.
+    pub fn succeeded(&self) -> bool {
+        self.status == Some(1u64.into())
+    }
+
+    /// Returns the fee paid for the transaction, i.e. the gas used times the effective gas price,
+    /// or `None` if either of them is missing from the receipt
+    pub fn total_fee(&self) -> Option<U256> {
+        Some(self.gas_used? * self.effective_gas_price?)
+    }
+}
+
 impl rlp::Encodable for TransactionReceipt {
     fn rlp_append(&self, s: &mut RlpStream) {
         s.begin_list(4);
@@ -874,6 +903,32 @@ mod tests {
         assert_eq!(tx.from, tx.recover_from().unwrap());
     }
 
+    #[test]
+    fn recover_sender() {
+        let rlp_bytes = hex::decode("f8aa808512ec276caf83010e2b94dac17f958d2ee523a2206206994597c13d831ec780b844a9059cbb000000000000000000000000fdae129ecc2c27d166a3131098bc05d143fa258e0000000000000000000000000000000000000000000000000000000002faf08025a0c81e70f9e49e0d3b854720143e86d172fecc9e76ef8a8666f2fdc017017c5141a01dd3410180f6a6ca3e25ad3058789cd0df3321ed76b5b4dbe0a2bb2dc28ae274").unwrap();
+        let mut tx = Transaction::decode(&rlp::Rlp::new(&rlp_bytes)).unwrap();
+        let sender = Address::from_str("c26ad91f4e7a0cad84c4b9315f420ca9217e315d").unwrap();
+        assert_eq!(tx.chain_id, Some(U256::from(1)));
+
+        tx.from = sender;
+        assert_eq!(tx.recover_sender().unwrap(), sender);
+
+        let stated = Address::repeat_byte(1);
+        tx.from = stated;
+        match tx.recover_sender() {
+            Err(SignatureError::VerificationError(expected, recovered)) => {
+                assert_eq!(expected, stated);
+                assert_eq!(recovered, sender);
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+
+        // a tampered transaction recovers to another sender
+        tx.from = sender;
+        tx.value = 1.into();
+        assert!(tx.recover_sender().is_err());
+    }
+
     #[test]
     fn decode_transaction_receipt() {
         let _res: TransactionReceipt = serde_json::from_str(
@@ -993,6 +1048,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn receipt_status_and_fee() {
+        let receipt = TransactionReceipt {
+            status: Some(1u64.into()),
+            gas_used: Some(21_000u64.into()),
+            effective_gas_price: Some(30_000_000_000u64.into()),
+            ..Default::default()
+        };
+        assert!(receipt.succeeded());
+        assert_eq!(receipt.total_fee(), Some(630_000_000_000_000u64.into()));
+
+        let failed = TransactionReceipt { status: Some(0u64.into()), ..receipt.clone() };
+        assert!(!failed.succeeded());
+        assert_eq!(failed.total_fee(), receipt.total_fee());
+
+        // pre-London receipts may lack the effective gas price
+        let legacy = TransactionReceipt { effective_gas_price: None, ..receipt };
+        assert_eq!(legacy.total_fee(), None);
+    }
+
     #[test]
     fn can_sort_receipts() {
         let mut a = TransactionReceipt { block_number: Some(0u64.into()), ..Default::default() };
@@ -0,0 +1,304 @@
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip2930::AccessListWithGasUsed},
+    *,
+};
+use ethers_providers::{call_raw::spoof, FromErr, Middleware};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+
+#[derive(Clone, Debug)]
+/// Middleware used for resolving ENS names before they reach the inner layers. Every method
+/// taking a [`NameOrAddress`] (or a transaction whose recipient is a name) resolves it to an
+/// address first, so that the layers below never see ENS names.
+///
+/// Resolved names are cached for the lifetime of the middleware, and the cache is shared between
+/// clones.
+pub struct EnsMiddleware<M> {
+    inner: M,
+    cache: Arc<Mutex<HashMap<String, Address>>>,
+}
+
+impl<M> EnsMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Instantiates the ENS middleware with an empty cache
+    pub fn new(inner: M) -> Self {
+        Self { inner, cache: Default::default() }
+    }
+
+    /// Clears all cached name resolutions
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// Resolves `who` to an address, using the cache if the name was resolved before
+    fn resolve(&self, who: NameOrAddress) -> Result<Address, EnsMiddlewareError<M>> {
+        match who {
+            NameOrAddress::Name(ens_name) => self.resolve_name(&ens_name),
+            NameOrAddress::Address(addr) => Ok(addr),
+        }
+    }
+
+    /// Returns a copy of `tx` with the ENS name in its `to` field, if any, resolved
+    fn resolve_recipient(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<TypedTransaction, EnsMiddlewareError<M>> {
+        let mut tx = tx.clone();
+        if let Some(NameOrAddress::Name(ens_name)) = tx.to() {
+            let addr = self.resolve_name(ens_name)?;
+            tx.set_to(addr);
+        }
+        Ok(tx)
+    }
+}
+
+#[derive(Error, Debug)]
+/// Thrown when an error happens at the ENS middleware
+pub enum EnsMiddlewareError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> FromErr<M::Error> for EnsMiddlewareError<M> {
+    fn from(src: M::Error) -> Self {
+        EnsMiddlewareError::MiddlewareError(src)
+    }
+}
+
+impl<M> Middleware for EnsMiddleware<M>
+where
+    M: Middleware,
+{
+    type Error = EnsMiddlewareError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    fn resolve_name(&self, ens_name: &str) -> Result<Address, Self::Error> {
+        if let Some(addr) = self.cache.lock().unwrap().get(ens_name) {
+            return Ok(*addr)
+        }
+
+        let addr = self.inner().resolve_name(ens_name).map_err(FromErr::from)?;
+        self.cache.lock().unwrap().insert(ens_name.to_string(), addr);
+        Ok(addr)
+    }
+
+    fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        if let Some(NameOrAddress::Name(ens_name)) = tx.to() {
+            let addr = self.resolve_name(ens_name)?;
+            tx.set_to(addr);
+        }
+        self.inner().fill_transaction(tx, block).map_err(FromErr::from)
+    }
+
+    fn get_transaction_count<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        let from = self.resolve(from.into())?;
+        self.inner().get_transaction_count(from, block).map_err(FromErr::from)
+    }
+
+    fn estimate_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        let tx = self.resolve_recipient(tx)?;
+        self.inner().estimate_gas(&tx, block).map_err(FromErr::from)
+    }
+
+    fn call(&self, tx: &TypedTransaction, block: Option<BlockId>) -> Result<Bytes, Self::Error> {
+        let tx = self.resolve_recipient(tx)?;
+        self.inner().call(&tx, block).map_err(FromErr::from)
+    }
+
+    fn call_with_overrides(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        overrides: &spoof::State,
+    ) -> Result<Bytes, Self::Error> {
+        let tx = self.resolve_recipient(tx)?;
+        self.inner().call_with_overrides(&tx, block, overrides).map_err(FromErr::from)
+    }
+
+    fn call_with_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(Bytes, U256), Self::Error> {
+        let tx = self.resolve_recipient(tx)?;
+        self.inner().call_with_gas(&tx, block).map_err(FromErr::from)
+    }
+
+    fn estimate_gas_buffered(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        multiplier: f64,
+    ) -> Result<U256, Self::Error> {
+        let tx = self.resolve_recipient(tx)?;
+        self.inner().estimate_gas_buffered(&tx, block, multiplier).map_err(FromErr::from)
+    }
+
+    fn get_balance<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        let from = self.resolve(from.into())?;
+        self.inner().get_balance(from, block).map_err(FromErr::from)
+    }
+
+    fn get_code<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        at: T,
+        block: Option<BlockId>,
+    ) -> Result<Bytes, Self::Error> {
+        let at = self.resolve(at.into())?;
+        self.inner().get_code(at, block).map_err(FromErr::from)
+    }
+
+    fn get_storage_at<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        location: H256,
+        block: Option<BlockId>,
+    ) -> Result<H256, Self::Error> {
+        let from = self.resolve(from.into())?;
+        self.inner().get_storage_at(from, location, block).map_err(FromErr::from)
+    }
+
+    fn get_proof<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        locations: Vec<H256>,
+        block: Option<BlockId>,
+    ) -> Result<EIP1186ProofResponse, Self::Error> {
+        let from = self.resolve(from.into())?;
+        self.inner().get_proof(from, locations, block).map_err(FromErr::from)
+    }
+
+    fn get_account<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        account: T,
+        block: Option<BlockId>,
+    ) -> Result<Account, Self::Error> {
+        let account = self.resolve(account.into())?;
+        self.inner().get_account(account, block).map_err(FromErr::from)
+    }
+
+    fn trace_call<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        req: T,
+        trace_type: Vec<TraceType>,
+        block: Option<BlockNumber>,
+    ) -> Result<BlockTrace, Self::Error> {
+        let req = self.resolve_recipient(&req.into())?;
+        self.inner().trace_call(req, trace_type, block).map_err(FromErr::from)
+    }
+
+    fn trace_call_many<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        req: Vec<(T, Vec<TraceType>)>,
+        block: Option<BlockNumber>,
+    ) -> Result<Vec<BlockTrace>, Self::Error> {
+        let req = req
+            .into_iter()
+            .map(|(tx, trace_type)| Ok((self.resolve_recipient(&tx.into())?, trace_type)))
+            .collect::<Result<Vec<_>, Self::Error>>()?;
+        self.inner().trace_call_many(req, block).map_err(FromErr::from)
+    }
+
+    fn create_access_list(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<AccessListWithGasUsed, Self::Error> {
+        let tx = self.resolve_recipient(tx)?;
+        self.inner().create_access_list(&tx, block).map_err(FromErr::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::abi::{self, Token};
+    use ethers_providers::{ens, Provider};
+
+    #[test]
+    fn resolves_names_before_the_provider() {
+        let (provider, mock) = Provider::mocked();
+        let client = EnsMiddleware::new(provider);
+
+        let name = "vitalik.eth";
+        let resolver: Address = "0x0000000000000000000000000000000000000010".parse().unwrap();
+        let addr: Address = "0x0000000000000000000000000000000000000020".parse().unwrap();
+        let balance = U256::from(100);
+
+        mock.push(balance).unwrap();
+        mock.push(balance).unwrap();
+        mock.push::<Bytes, Bytes>(abi::encode(&[Token::Address(addr)]).into()).unwrap();
+        mock.push::<Bytes, Bytes>(abi::encode(&[Token::Bool(true)]).into()).unwrap();
+        mock.push::<Bytes, Bytes>(abi::encode(&[Token::Address(resolver)]).into()).unwrap();
+
+        assert_eq!(client.get_balance(name, None).unwrap(), balance);
+
+        let calls: [TypedTransaction; 3] = [
+            ens::get_resolver(ens::ENS_ADDRESS, name).into(),
+            ens::supports_interface(resolver, ens::ADDR_SELECTOR).into(),
+            ens::resolve(resolver, ens::ADDR_SELECTOR, name, None).into(),
+        ];
+        for call in calls {
+            mock.assert_request("eth_call", (call, BlockNumber::Latest)).unwrap();
+        }
+        mock.assert_request("eth_getBalance", (addr, BlockNumber::Latest)).unwrap();
+
+        // the second lookup is served from the cache
+        assert_eq!(client.get_balance(name, None).unwrap(), balance);
+        mock.assert_request("eth_getBalance", (addr, BlockNumber::Latest)).unwrap();
+    }
+
+    #[test]
+    fn resolves_recipients_of_gas_calls() {
+        let (provider, mock) = Provider::mocked();
+        let client = EnsMiddleware::new(provider);
+        let addr = Address::repeat_byte(0x20);
+        client.cache.lock().unwrap().insert("vitalik.eth".to_owned(), addr);
+
+        let tx: TypedTransaction = TransactionRequest::new().to("vitalik.eth").into();
+        let resolved: TypedTransaction = TransactionRequest::new().to(addr).into();
+
+        let trace = serde_json::json!({
+            "failed": false,
+            "gas": 21_000,
+            "returnValue": "",
+            "structLogs": []
+        });
+        mock.push(trace).unwrap();
+        assert_eq!(client.call_with_gas(&tx, None).unwrap().1, 21_000.into());
+        let options =
+            serde_json::json!({ "disableStorage": true, "disableStack": true, "limit": 1 });
+        mock.assert_request("debug_traceCall", (&resolved, "latest", &options)).unwrap();
+
+        mock.push(U256::from(20_000)).unwrap();
+        assert_eq!(client.estimate_gas_buffered(&tx, None, 1.5).unwrap(), 30_000.into());
+        mock.assert_request("eth_estimateGas", [&resolved]).unwrap();
+    }
+}
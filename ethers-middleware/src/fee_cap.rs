@@ -0,0 +1,176 @@
+use ethers_core::types::{transaction::eip2718::TypedTransaction, *};
+use ethers_providers::{FromErr, Middleware};
+use thiserror::Error;
+
+/// What the [`FeeCapMiddleware`] does with transactions paying more than its cap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeCapMode {
+    /// Fail with [`FeeCapMiddlewareError::FeeTooHigh`]
+    Reject,
+    /// Lower the fee to the cap, at the risk of the transaction not being included while fees
+    /// stay above it
+    Clamp,
+}
+
+#[derive(Clone, Debug)]
+/// Middleware enforcing a ceiling on the gas price of legacy and EIP-2930 transactions and on the
+/// max fee per gas of EIP-1559 transactions, e.g. to keep automated senders from overpaying during
+/// gas spikes. The fees are checked after the inner middlewares have filled the transaction, so
+/// estimated and explicitly set fees are both covered.
+pub struct FeeCapMiddleware<M> {
+    inner: M,
+    cap: U256,
+    mode: FeeCapMode,
+}
+
+impl<M> FeeCapMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Instantiates the middleware with the maximum fee per gas in wei
+    pub fn new(inner: M, cap: U256, mode: FeeCapMode) -> Self {
+        Self { inner, cap, mode }
+    }
+
+    /// Returns the maximum fee per gas in wei
+    pub fn cap(&self) -> U256 {
+        self.cap
+    }
+
+    /// Rejects or clamps the fees of `tx` exceeding the cap, depending on the mode
+    fn enforce_cap(&self, tx: &mut TypedTransaction) -> Result<(), FeeCapMiddlewareError<M>> {
+        let fee = match tx {
+            TypedTransaction::Legacy(ref mut inner) => &mut inner.gas_price,
+            TypedTransaction::Eip2930(ref mut inner) => &mut inner.tx.gas_price,
+            TypedTransaction::Eip1559(ref mut inner) |
+            TypedTransaction::Eip4844(Eip4844TransactionRequest { tx: ref mut inner, .. }) => {
+                &mut inner.max_fee_per_gas
+            }
+        };
+        match *fee {
+            Some(value) if value > self.cap => match self.mode {
+                FeeCapMode::Reject => {
+                    return Err(FeeCapMiddlewareError::FeeTooHigh { fee: value, cap: self.cap })
+                }
+                FeeCapMode::Clamp => *fee = Some(self.cap),
+            },
+            _ => {}
+        }
+
+        // the priority fee can not exceed the max fee
+        if let TypedTransaction::Eip1559(ref mut inner) |
+        TypedTransaction::Eip4844(Eip4844TransactionRequest { tx: ref mut inner, .. }) = tx
+        {
+            if let (Some(max_fee), Some(priority_fee)) =
+                (inner.max_fee_per_gas, inner.max_priority_fee_per_gas)
+            {
+                inner.max_priority_fee_per_gas = Some(priority_fee.min(max_fee));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+/// Thrown when an error happens at the fee cap middleware
+pub enum FeeCapMiddlewareError<M: Middleware> {
+    /// Thrown when the transaction's fee per gas exceeds the cap in [`FeeCapMode::Reject`] mode
+    #[error("fee per gas of {fee} wei exceeds the cap of {cap} wei")]
+    FeeTooHigh { fee: U256, cap: U256 },
+
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> FromErr<M::Error> for FeeCapMiddlewareError<M> {
+    fn from(src: M::Error) -> Self {
+        FeeCapMiddlewareError::MiddlewareError(src)
+    }
+}
+
+impl<M> Middleware for FeeCapMiddleware<M>
+where
+    M: Middleware,
+{
+    type Error = FeeCapMiddlewareError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        // cap explicitly set fees before the gas is estimated with them
+        self.enforce_cap(tx)?;
+        self.inner().fill_transaction(tx, block).map_err(FromErr::from)?;
+        self.enforce_cap(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_providers::Provider;
+
+    fn gwei(n: u64) -> U256 {
+        U256::from(n) * U256::exp10(9)
+    }
+
+    #[test]
+    fn rejects_fees_above_the_cap() {
+        let (provider, mock) = Provider::mocked();
+        let client = FeeCapMiddleware::new(provider, gwei(100), FeeCapMode::Reject);
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::zero()).gas(21_000).into();
+
+        mock.push(gwei(150)).unwrap();
+        let err = client.fill_transaction(&mut tx.clone(), None).unwrap_err();
+        assert!(matches!(
+            err,
+            FeeCapMiddlewareError::FeeTooHigh { fee, cap } if fee == gwei(150) && cap == gwei(100)
+        ));
+        mock.assert_request("eth_gasPrice", ()).unwrap();
+
+        mock.push(gwei(80)).unwrap();
+        let mut filled = tx.clone();
+        client.fill_transaction(&mut filled, None).unwrap();
+        assert_eq!(filled.gas_price(), Some(gwei(80)));
+
+        // explicitly set fees are rejected before anything is requested
+        let mut tx: TypedTransaction =
+            Eip1559TransactionRequest::new().max_fee_per_gas(gwei(101)).into();
+        assert!(client.fill_transaction(&mut tx, None).is_err());
+    }
+
+    #[test]
+    fn clamps_fees_to_the_cap() {
+        let (provider, mock) = Provider::mocked();
+        let client = FeeCapMiddleware::new(provider, gwei(100), FeeCapMode::Clamp);
+
+        let mut tx: TypedTransaction =
+            TransactionRequest::new().to(Address::zero()).gas(21_000).into();
+        mock.push(gwei(150)).unwrap();
+        client.fill_transaction(&mut tx, None).unwrap();
+        assert_eq!(tx.gas_price(), Some(gwei(100)));
+
+        let mut tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(Address::zero())
+            .gas(21_000)
+            .max_fee_per_gas(gwei(300))
+            .max_priority_fee_per_gas(gwei(120))
+            .into();
+        client.fill_transaction(&mut tx, None).unwrap();
+        match tx {
+            TypedTransaction::Eip1559(ref inner) => {
+                assert_eq!(inner.max_fee_per_gas, Some(gwei(100)));
+                assert_eq!(inner.max_priority_fee_per_gas, Some(gwei(100)));
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use ethers_core::types::{transaction::eip2718::TypedTransaction, *};
+use ethers_providers::{FromErr, Middleware};
+use thiserror::Error;
+
+#[derive(Clone, Debug)]
+/// Middleware answering gas price, EIP-1559 fee and gas estimates with configured constants
+/// instead of querying the node. This keeps tests deterministic regardless of the network's gas
+/// market, while every other call is passed through to the inner middleware.
+pub struct FixedGasMiddleware<M> {
+    inner: M,
+    gas_price: U256,
+    max_fee_per_gas: U256,
+    max_priority_fee_per_gas: U256,
+    gas: U256,
+}
+
+impl<M> FixedGasMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Instantiates the middleware with a fixed gas price and gas estimate. The gas price is
+    /// also used as both EIP-1559 fees unless they are set with
+    /// [`eip1559_fees`](Self::eip1559_fees).
+    pub fn new(inner: M, gas_price: U256, gas: U256) -> Self {
+        Self {
+            inner,
+            gas_price,
+            max_fee_per_gas: gas_price,
+            max_priority_fee_per_gas: gas_price,
+            gas,
+        }
+    }
+
+    /// Sets the fixed max fee and max priority fee per gas of EIP-1559 transactions
+    #[must_use]
+    pub fn eip1559_fees(mut self, max_fee_per_gas: U256, max_priority_fee_per_gas: U256) -> Self {
+        self.max_fee_per_gas = max_fee_per_gas;
+        self.max_priority_fee_per_gas = max_priority_fee_per_gas;
+        self
+    }
+}
+
+#[derive(Error, Debug)]
+/// Thrown when an error happens at the fixed gas middleware
+pub enum FixedGasMiddlewareError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> FromErr<M::Error> for FixedGasMiddlewareError<M> {
+    fn from(src: M::Error) -> Self {
+        FixedGasMiddlewareError::MiddlewareError(src)
+    }
+}
+
+impl<M> Middleware for FixedGasMiddleware<M>
+where
+    M: Middleware,
+{
+    type Error = FixedGasMiddlewareError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        match tx {
+            TypedTransaction::Legacy(ref mut tx) => {
+                tx.gas_price.get_or_insert(self.gas_price);
+            }
+            TypedTransaction::Eip2930(ref mut inner) => {
+                inner.tx.gas_price.get_or_insert(self.gas_price);
+            }
+            TypedTransaction::Eip1559(ref mut inner) |
+            TypedTransaction::Eip4844(Eip4844TransactionRequest { tx: ref mut inner, .. }) => {
+                inner.max_fee_per_gas.get_or_insert(self.max_fee_per_gas);
+                inner.max_priority_fee_per_gas.get_or_insert(self.max_priority_fee_per_gas);
+            }
+        };
+        if tx.gas().is_none() {
+            tx.set_gas(self.gas);
+        }
+
+        self.inner().fill_transaction(tx, block).map_err(FromErr::from)
+    }
+
+    fn get_gas_price(&self) -> Result<U256, Self::Error> {
+        Ok(self.gas_price)
+    }
+
+    fn estimate_eip1559_fees(
+        &self,
+        _: Option<fn(U256, Vec<Vec<U256>>) -> (U256, U256)>,
+    ) -> Result<(U256, U256), Self::Error> {
+        Ok((self.max_fee_per_gas, self.max_priority_fee_per_gas))
+    }
+
+    fn estimate_gas(&self, _: &TypedTransaction, _: Option<BlockId>) -> Result<U256, Self::Error> {
+        Ok(self.gas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_providers::Provider;
+
+    #[test]
+    fn fills_fixed_values() {
+        let (provider, _mock) = Provider::mocked();
+        let from = Address::repeat_byte(1);
+        let client = FixedGasMiddleware::new(provider.with_sender(from), 10.into(), 21_000.into())
+            .eip1559_fees(30.into(), 2.into());
+
+        // no responses are queued, so any request to the node would fail
+        let mut tx: TypedTransaction = TransactionRequest::new().to(Address::zero()).into();
+        client.fill_transaction(&mut tx, None).unwrap();
+        assert_eq!(tx.gas_price(), Some(10.into()));
+        assert_eq!(tx.gas(), Some(&21_000.into()));
+
+        let mut tx: TypedTransaction = Eip1559TransactionRequest::new().into();
+        client.fill_transaction(&mut tx, None).unwrap();
+        match tx {
+            TypedTransaction::Eip1559(ref inner) => {
+                assert_eq!(inner.max_fee_per_gas, Some(30.into()));
+                assert_eq!(inner.max_priority_fee_per_gas, Some(2.into()));
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(tx.gas(), Some(&21_000.into()));
+
+        // an explicit gas limit is kept
+        let mut tx: TypedTransaction = TransactionRequest::new().gas(50_000).into();
+        client.fill_transaction(&mut tx, None).unwrap();
+        assert_eq!(tx.gas(), Some(&50_000.into()));
+    }
+}
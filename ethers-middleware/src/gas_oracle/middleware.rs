@@ -72,7 +72,8 @@ where
                     inner.tx.gas_price = Some(self.get_gas_price().await?);
                 }
             }
-            TypedTransaction::Eip1559(ref mut inner) => {
+            TypedTransaction::Eip1559(ref mut inner) |
+            TypedTransaction::Eip4844(Eip4844TransactionRequest { tx: ref mut inner, .. }) => {
                 if inner.max_priority_fee_per_gas.is_none() || inner.max_fee_per_gas.is_none() {
                     let (max_fee_per_gas, max_priority_fee_per_gas) =
                         self.estimate_eip1559_fees(None).await?;
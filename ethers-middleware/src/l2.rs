@@ -0,0 +1,325 @@
+use ethers_core::{
+    abi::{self, ParamType, Token},
+    types::{transaction::eip2718::TypedTransaction, *},
+    utils::id,
+};
+use ethers_providers::{FromErr, Middleware};
+use thiserror::Error;
+
+/// The `GasPriceOracle` predeploy of OP-stack chains
+pub const OP_GAS_PRICE_ORACLE: Address = H160([
+    0x42, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x0f,
+]);
+
+/// The `NodeInterface` precompile of Arbitrum chains
+pub const ARB_NODE_INTERFACE: Address = H160([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0xc8,
+]);
+
+/// The rollup stack of an L2 chain, which determines how its L1 data fee is charged
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum L2Kind {
+    /// OP-stack chains (Optimism, Base, ...), which deduct the L1 fee from the sender's balance
+    /// besides the gas
+    OpStack,
+    /// Arbitrum chains, which charge the L1 fee as additional gas included in `eth_estimateGas`
+    Arbitrum,
+}
+
+/// A gas estimate split into the L2 execution gas and the L1 data fee
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct L2GasEstimate {
+    /// The estimated gas of the transaction's execution on the L2, without the L1 component
+    pub gas: U256,
+    /// The fee in wei for posting the transaction's data to the L1
+    pub l1_fee: U256,
+}
+
+#[derive(Clone, Debug)]
+/// Middleware used for accounting for the L1 data fee of transactions on OP-stack and Arbitrum
+/// chains.
+///
+/// Besides the L2 gas, these rollups charge for posting a transaction's data to the L1. The fee
+/// is quoted by the chain's gas oracle: the `GasPriceOracle` predeploy on OP-stack chains and the
+/// `NodeInterface` precompile on Arbitrum.
+///
+/// Gas estimates passing through the middleware include the L1 fee as gas, so that the maximum
+/// cost of a filled transaction covers it. Arbitrum nodes already do so, on OP-stack chains the
+/// fee is converted to gas at the transaction's gas price.
+pub struct L2Middleware<M> {
+    inner: M,
+    kind: L2Kind,
+}
+
+impl<M> L2Middleware<M>
+where
+    M: Middleware,
+{
+    /// Instantiates the L2 middleware for a chain of the given `kind`
+    pub fn new(inner: M, kind: L2Kind) -> Self {
+        Self { inner, kind }
+    }
+
+    /// Returns the fee in wei for posting `tx` to the L1
+    pub fn estimate_l1_fee(&self, tx: &TypedTransaction) -> Result<U256, L2MiddlewareError<M>> {
+        match self.kind {
+            L2Kind::OpStack => self.op_l1_fee(tx),
+            L2Kind::Arbitrum => {
+                let (l1_gas, base_fee) = self.arb_l1_component(tx)?;
+                Ok(l1_gas * base_fee)
+            }
+        }
+    }
+
+    /// Estimates the L2 execution gas of `tx` and the L1 data fee charged besides it
+    pub fn estimate_gas_with_l1_fee(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<L2GasEstimate, L2MiddlewareError<M>> {
+        let gas = self.inner.estimate_gas(tx, block).map_err(L2MiddlewareError::MiddlewareError)?;
+        match self.kind {
+            L2Kind::OpStack => Ok(L2GasEstimate { gas, l1_fee: self.op_l1_fee(tx)? }),
+            L2Kind::Arbitrum => {
+                // the node's estimate already includes the L1 gas
+                let (l1_gas, base_fee) = self.arb_l1_component(tx)?;
+                Ok(L2GasEstimate { gas: gas.saturating_sub(l1_gas), l1_fee: l1_gas * base_fee })
+            }
+        }
+    }
+
+    /// Returns the L1 fee of `tx` as gas paid at its gas price, rounded up
+    fn op_l1_gas(&self, tx: &TypedTransaction) -> Result<U256, L2MiddlewareError<M>> {
+        let gas_price = match tx.gas_price() {
+            Some(gas_price) => gas_price,
+            None => self.get_gas_price()?,
+        };
+        if gas_price.is_zero() {
+            return Ok(U256::zero())
+        }
+        let (gas, rem) = self.op_l1_fee(tx)?.div_mod(gas_price);
+        Ok(if rem.is_zero() { gas } else { gas + 1 })
+    }
+
+    /// Calls `getL1Fee(bytes)` on the `GasPriceOracle` with the RLP encoded transaction
+    fn op_l1_fee(&self, tx: &TypedTransaction) -> Result<U256, L2MiddlewareError<M>> {
+        let data =
+            [&id("getL1Fee(bytes)")[..], &abi::encode(&[Token::Bytes(tx.rlp().to_vec())])].concat();
+        let tokens = self.call_oracle(OP_GAS_PRICE_ORACLE, data, &[ParamType::Uint(256)])?;
+        Ok(tokens[0].clone().into_uint().unwrap_or_default())
+    }
+
+    /// Calls `gasEstimateL1Component(address,bool,bytes)` on the `NodeInterface`, which returns the
+    /// L1 gas alongside the L2 base fee it is paid with
+    fn arb_l1_component(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<(U256, U256), L2MiddlewareError<M>> {
+        let to = match tx.to() {
+            Some(NameOrAddress::Name(ens_name)) => Some(self.resolve_name(ens_name)?),
+            Some(NameOrAddress::Address(addr)) => Some(*addr),
+            None => None,
+        };
+        let input = tx.data().cloned().unwrap_or_default();
+        let args = [
+            Token::Address(to.unwrap_or_default()),
+            Token::Bool(to.is_none()),
+            Token::Bytes(input.to_vec()),
+        ];
+        let data =
+            [&id("gasEstimateL1Component(address,bool,bytes)")[..], &abi::encode(&args)].concat();
+        let outputs = [ParamType::Uint(64), ParamType::Uint(256), ParamType::Uint(256)];
+        let tokens = self.call_oracle(ARB_NODE_INTERFACE, data, &outputs)?;
+
+        let l1_gas = tokens[0].clone().into_uint().unwrap_or_default();
+        let base_fee = tokens[1].clone().into_uint().unwrap_or_default();
+        Ok((l1_gas, base_fee))
+    }
+
+    fn call_oracle(
+        &self,
+        oracle: Address,
+        data: Vec<u8>,
+        outputs: &[ParamType],
+    ) -> Result<Vec<Token>, L2MiddlewareError<M>> {
+        let call = TransactionRequest::new().to(oracle).data(data).into();
+        let res = self.call(&call, None)?;
+        Ok(abi::decode(outputs, res.as_ref())?)
+    }
+}
+
+#[derive(Error, Debug)]
+/// Thrown when an error happens at the L2 middleware
+pub enum L2MiddlewareError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+
+    /// Thrown when the gas oracle's response cannot be decoded
+    #[error(transparent)]
+    AbiError(#[from] abi::Error),
+}
+
+impl<M: Middleware> FromErr<M::Error> for L2MiddlewareError<M> {
+    fn from(src: M::Error) -> Self {
+        L2MiddlewareError::MiddlewareError(src)
+    }
+}
+
+impl<M> Middleware for L2Middleware<M>
+where
+    M: Middleware,
+{
+    type Error = L2MiddlewareError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    fn estimate_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        let gas = self.inner.estimate_gas(tx, block).map_err(L2MiddlewareError::MiddlewareError)?;
+        match self.kind {
+            L2Kind::OpStack => Ok(gas + self.op_l1_gas(tx)?),
+            L2Kind::Arbitrum => Ok(gas),
+        }
+    }
+
+    fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        let estimated = tx.gas().is_none();
+        self.inner.fill_transaction(tx, block).map_err(L2MiddlewareError::MiddlewareError)?;
+        // the gas estimated by the lower layers lacks the L1 fee, explicit gas is left untouched
+        if estimated && self.kind == L2Kind::OpStack {
+            let gas = tx.gas().copied().unwrap_or_default() + self.op_l1_gas(tx)?;
+            tx.set_gas(gas);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_providers::{Provider, ProviderError};
+
+    fn tx() -> TypedTransaction {
+        TransactionRequest::new()
+            .to(Address::repeat_byte(1))
+            .data(vec![1, 2, 3])
+            .nonce(0)
+            .gas(21_000)
+            .gas_price(1)
+            .into()
+    }
+
+    fn op_oracle_call(tx: &TypedTransaction) -> TypedTransaction {
+        let data =
+            [&id("getL1Fee(bytes)")[..], &abi::encode(&[Token::Bytes(tx.rlp().to_vec())])].concat();
+        TransactionRequest::new().to(OP_GAS_PRICE_ORACLE).data(data).into()
+    }
+
+    #[test]
+    fn op_stack_l1_fee() {
+        let (provider, mock) = Provider::mocked();
+        let client = L2Middleware::new(provider, L2Kind::OpStack);
+        let tx = tx();
+
+        mock.push::<Bytes, Bytes>(abi::encode(&[Token::Uint(1_000.into())]).into()).unwrap();
+        assert_eq!(client.estimate_l1_fee(&tx).unwrap(), 1_000.into());
+        mock.assert_request("eth_call", (op_oracle_call(&tx), BlockNumber::Latest)).unwrap();
+    }
+
+    #[test]
+    fn op_stack_gas_includes_l1_fee() {
+        let (provider, mock) = Provider::mocked();
+        let client = L2Middleware::new(provider, L2Kind::OpStack);
+        let unfilled: TypedTransaction =
+            TransactionRequest::new().to(Address::repeat_byte(1)).nonce(0).gas_price(2).into();
+
+        // an L1 fee of 1_001 wei at a gas price of 2 wei is paid with 501 gas
+        let fee = || -> Bytes { abi::encode(&[Token::Uint(1_001.into())]).into() };
+        mock.push::<Bytes, _>(fee()).unwrap();
+        mock.push(U256::from(21_000)).unwrap();
+        assert_eq!(client.estimate_gas(&unfilled, None).unwrap(), 21_501.into());
+        mock.assert_request("eth_estimateGas", [&unfilled]).unwrap();
+        mock.assert_request("eth_call", (op_oracle_call(&unfilled), BlockNumber::Latest)).unwrap();
+
+        // the fee is quoted for the filled transaction
+        let mut tx = unfilled.clone();
+        mock.push::<Bytes, _>(fee()).unwrap();
+        mock.push(U256::from(21_000)).unwrap();
+        client.fill_transaction(&mut tx, None).unwrap();
+        assert_eq!(tx.gas(), Some(&21_501.into()));
+        let mut estimated = unfilled.clone();
+        estimated.set_gas(21_000);
+        mock.assert_request("eth_estimateGas", [&unfilled]).unwrap();
+        mock.assert_request("eth_call", (op_oracle_call(&estimated), BlockNumber::Latest)).unwrap();
+
+        // explicit gas is left untouched
+        let mut explicit = unfilled.clone();
+        explicit.set_gas(50_000);
+        client.fill_transaction(&mut explicit, None).unwrap();
+        assert_eq!(explicit.gas(), Some(&50_000.into()));
+    }
+
+    #[test]
+    fn rejects_invalid_gas_multipliers() {
+        let (provider, mock) = Provider::mocked();
+        let client = L2Middleware::new(provider, L2Kind::OpStack);
+
+        // rejected before estimating the gas
+        let err = client.estimate_gas_buffered(&tx(), None, 0.5).unwrap_err();
+        assert!(matches!(
+            err,
+            L2MiddlewareError::MiddlewareError(ProviderError::InvalidGasMultiplier(_))
+        ));
+        assert!(mock.assert_request("eth_estimateGas", [&tx()]).is_err());
+    }
+
+    #[test]
+    fn arbitrum_l1_fee() {
+        let (provider, mock) = Provider::mocked();
+        let client = L2Middleware::new(provider, L2Kind::Arbitrum);
+        let tx = tx();
+
+        // 500 L1 gas paid at an L2 base fee of 100 wei
+        let response = abi::encode(&[
+            Token::Uint(500.into()),
+            Token::Uint(100.into()),
+            Token::Uint(30.into()),
+        ]);
+        mock.push::<Bytes, Bytes>(response.into()).unwrap();
+        mock.push(U256::from(21_500)).unwrap();
+
+        // the node's estimate includes the L1 gas, which is not counted twice
+        let estimate = client.estimate_gas_with_l1_fee(&tx, None).unwrap();
+        assert_eq!(estimate, L2GasEstimate { gas: 21_000.into(), l1_fee: 50_000.into() });
+
+        let args = [
+            Token::Address(Address::repeat_byte(1)),
+            Token::Bool(false),
+            Token::Bytes(vec![1, 2, 3]),
+        ];
+        let data =
+            [&id("gasEstimateL1Component(address,bool,bytes)")[..], &abi::encode(&args)].concat();
+        let call: TypedTransaction =
+            TransactionRequest::new().to(ARB_NODE_INTERFACE).data(data).into();
+        mock.assert_request("eth_estimateGas", [&tx]).unwrap();
+        mock.assert_request("eth_call", (call, BlockNumber::Latest)).unwrap();
+
+        // estimates passing through the middleware are left as is
+        mock.push(U256::from(21_500)).unwrap();
+        assert_eq!(client.estimate_gas(&tx, None).unwrap(), 21_500.into());
+    }
+}
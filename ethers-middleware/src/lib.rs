@@ -27,11 +27,41 @@ pub mod transformer;
 pub mod signer;
 pub use signer::SignerMiddleware;
 
+/// The [MultiSigner](crate::MultiSignerMiddleware) is used to locally sign transactions and
+/// messages with one of several signers, picked by the sender address
+pub mod multi_signer;
+pub use multi_signer::MultiSignerMiddleware;
+
 /// The [Policy](crate::PolicyMiddleware) is used to ensure transactions comply with the rules
 /// configured in the `PolicyMiddleware` before sending them.
 pub mod policy;
 pub use policy::PolicyMiddleware;
 
+/// The [Ens](crate::EnsMiddleware) middleware is used to resolve (and cache) ENS names in all
+/// calls, so that the inner layers only ever see addresses
+pub mod ens;
+pub use ens::EnsMiddleware;
+
+/// The [RateLimit](crate::RateLimitMiddleware) middleware is used to keep the rate of requests
+/// sent to a node below a configured quota
+pub mod rate_limit;
+pub use rate_limit::RateLimitMiddleware;
+
+/// The [FixedGas](crate::FixedGasMiddleware) middleware answers gas price and gas estimates
+/// with constants, for deterministic tests
+pub mod fixed_gas;
+pub use fixed_gas::FixedGasMiddleware;
+
+/// The [FeeCap](crate::FeeCapMiddleware) middleware rejects or clamps transactions paying more
+/// than a configured fee per gas
+pub mod fee_cap;
+pub use fee_cap::FeeCapMiddleware;
+
+/// The [L2](crate::L2Middleware) middleware estimates the L1 data fee charged by OP-stack and
+/// Arbitrum chains, and includes it in the gas estimates passing through it
+pub mod l2;
+pub use l2::{L2Kind, L2Middleware};
+
 /// The [TimeLag](crate::TimeLag) provides safety against reorgs by querying state N blocks
 /// before the chain tip
 pub mod timelag;
@@ -0,0 +1,192 @@
+use ethers_core::types::{transaction::eip2718::TypedTransaction, Address, Bytes, Signature};
+use ethers_providers::{FromErr, Middleware};
+use ethers_signers::Signer;
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Clone, Debug)]
+/// Middleware signing with one of several signers, picked by the `from` address of the
+/// transaction, e.g. for services managing many hot wallets. This avoids stacking a
+/// [`SignerMiddleware`](crate::SignerMiddleware) per address.
+///
+/// Signers of different kinds can be combined by wrapping them in an enum implementing
+/// [`Signer`].
+pub struct MultiSignerMiddleware<M, S> {
+    inner: M,
+    signers: HashMap<Address, S>,
+}
+
+impl<M: Middleware, S: Signer> FromErr<M::Error> for MultiSignerMiddlewareError<M, S> {
+    fn from(src: M::Error) -> MultiSignerMiddlewareError<M, S> {
+        MultiSignerMiddlewareError::MiddlewareError(src)
+    }
+}
+
+#[derive(Error, Debug)]
+/// Error thrown when the client interacts with the blockchain
+pub enum MultiSignerMiddlewareError<M: Middleware, S: Signer> {
+    #[error("{0}")]
+    /// Thrown when the internal call to the signer fails
+    SignerError(S::Error),
+
+    #[error("{0}")]
+    /// Thrown when an internal middleware errors
+    MiddlewareError(M::Error),
+
+    /// Thrown if the transaction does not specify the address to sign with
+    #[error("no from address was specified")]
+    FromMissing,
+
+    /// Thrown if a signature is requested from an address without a signer
+    #[error("no signer for address {0:?}")]
+    WrongSigner(Address),
+}
+
+impl<M, S> MultiSignerMiddleware<M, S>
+where
+    M: Middleware,
+    S: Signer,
+{
+    /// Creates a new client signing with the given signers, keyed by their addresses
+    pub fn new(inner: M, signers: impl IntoIterator<Item = S>) -> Self {
+        let signers = signers.into_iter().map(|signer| (signer.address(), signer)).collect();
+        MultiSignerMiddleware { inner, signers }
+    }
+
+    /// Adds a signer, replacing the one with the same address if any
+    #[must_use]
+    pub fn with_signer(mut self, signer: S) -> Self {
+        self.signers.insert(signer.address(), signer);
+        self
+    }
+
+    /// Returns the signer of `address`, if any
+    pub fn signer(&self, address: &Address) -> Option<&S> {
+        self.signers.get(address)
+    }
+
+    /// Returns the addresses the client can sign for
+    pub fn addresses(&self) -> impl Iterator<Item = &Address> {
+        self.signers.keys()
+    }
+
+    /// Signs the transaction with the signer of its `from` address and returns its RLP encoding,
+    /// without filling in any of its fields or sending it. If the transaction does not have a
+    /// chain id set, it sets it to the signer's chain id.
+    pub async fn sign_transaction_raw(
+        &self,
+        mut tx: TypedTransaction,
+    ) -> Result<Bytes, MultiSignerMiddlewareError<M, S>> {
+        let from = *tx.from().ok_or(MultiSignerMiddlewareError::FromMissing)?;
+        let signer = self.signer_for(&from)?;
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(signer.chain_id());
+        }
+        let signature =
+            signer.sign_transaction(&tx).await.map_err(MultiSignerMiddlewareError::SignerError)?;
+        Ok(tx.rlp_network(&signature))
+    }
+
+    fn signer_for(&self, address: &Address) -> Result<&S, MultiSignerMiddlewareError<M, S>> {
+        self.signers.get(address).ok_or(MultiSignerMiddlewareError::WrongSigner(*address))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<M, S> Middleware for MultiSignerMiddleware<M, S>
+where
+    M: Middleware,
+    S: Signer,
+{
+    type Error = MultiSignerMiddlewareError<M, S>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// `MultiSignerMiddleware` is instantiated with signers.
+    async fn is_signer(&self) -> bool {
+        true
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+        from: Address,
+    ) -> Result<Signature, Self::Error> {
+        let signer = self.signer_for(&from)?;
+        let mut tx = tx.clone();
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(signer.chain_id());
+        }
+        signer.sign_transaction(&tx).await.map_err(MultiSignerMiddlewareError::SignerError)
+    }
+
+    /// Signs a message with the signer of `from`
+    async fn sign<T: Into<Bytes> + Send + Sync>(
+        &self,
+        data: T,
+        from: &Address,
+    ) -> Result<Signature, Self::Error> {
+        self.signer_for(from)?
+            .sign_message(data.into())
+            .await
+            .map_err(MultiSignerMiddlewareError::SignerError)
+    }
+}
+
+#[cfg(all(test, not(feature = "celo"), not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use ethers_core::types::TransactionRequest;
+    use ethers_providers::Provider;
+    use ethers_signers::LocalWallet;
+
+    #[tokio::test]
+    async fn signs_with_the_signer_of_the_sender() {
+        let alice = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1u64);
+        let bob = "0000000000000000000000000000000000000000000000000000000000000001"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1u64);
+        let (alice_address, bob_address) = (alice.address(), bob.address());
+
+        let (provider, _mock) = Provider::mocked();
+        let client = MultiSignerMiddleware::new(provider, vec![alice, bob]);
+
+        for from in [alice_address, bob_address] {
+            let tx: TypedTransaction = TransactionRequest::new()
+                .from(from)
+                .to(Address::zero())
+                .nonce(0)
+                .gas(21_000)
+                .gas_price(1)
+                .chain_id(1)
+                .into();
+            let signature = client.sign_transaction(&tx, from).await.unwrap();
+            assert_eq!(signature.recover(tx.sighash()).unwrap(), from);
+
+            let signed = client.sign_transaction_raw(tx.clone()).await.unwrap();
+            assert_eq!(signed, tx.rlp_signed(&signature));
+        }
+
+        let stranger = Address::repeat_byte(1);
+        let tx: TypedTransaction = TransactionRequest::new().from(stranger).into();
+        assert!(matches!(
+            client.sign_transaction(&tx, stranger).await,
+            Err(MultiSignerMiddlewareError::WrongSigner(address)) if address == stranger
+        ));
+        assert!(matches!(
+            client.sign_transaction_raw(TransactionRequest::new().into()).await,
+            Err(MultiSignerMiddlewareError::FromMissing)
+        ));
+    }
+}
@@ -0,0 +1,638 @@
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip2930::AccessListWithGasUsed},
+    *,
+};
+use ethers_providers::{call_raw::spoof, erc, FilterKind, FromErr, Middleware, ProviderError};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+use url::Url;
+
+/// A token bucket which hands out one token per request, refilled at a fixed rate.
+///
+/// The limiter is shared through an [`Arc`], so that all clones of a [`RateLimitMiddleware`] (or
+/// several middleware stacks talking to the same endpoint) draw from the same quota.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Tokens added per second
+    rate: f64,
+    /// Maximum number of tokens the bucket can hold
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a new limiter allowing `requests_per_second` on average, with up to `burst`
+    /// requests being sent back to back. The bucket starts out full.
+    ///
+    /// # Panics
+    ///
+    /// If `requests_per_second` or `burst` is zero.
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        assert!(requests_per_second > 0, "rate limit must be non-zero");
+        assert!(burst > 0, "burst size must be non-zero");
+        Self {
+            rate: requests_per_second as f64,
+            burst: burst as f64,
+            bucket: Mutex::new(Bucket { tokens: burst as f64, last_refill: Instant::now() }),
+        }
+    }
+
+    /// Takes a token from the bucket, blocking the current thread until one is available.
+    pub fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+                bucket.last_refill = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate)
+            };
+            thread::sleep(wait);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Middleware used to keep the request rate below a provider's quota. Every call delegated to
+/// the inner middleware first takes a token from a shared [`RateLimiter`], waiting for the bucket
+/// to refill instead of erroring when it is empty.
+///
+/// Note that requests issued internally by the inner layers (e.g. gas estimation performed as
+/// part of `fill_transaction`) count as a single call.
+pub struct RateLimitMiddleware<M> {
+    inner: M,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<M> RateLimitMiddleware<M>
+where
+    M: Middleware,
+{
+    /// Instantiates the middleware with a new limiter allowing `requests_per_second` on average
+    /// and bursts of up to `burst` requests.
+    pub fn new(inner: M, requests_per_second: u32, burst: u32) -> Self {
+        Self::with_limiter(inner, Arc::new(RateLimiter::new(requests_per_second, burst)))
+    }
+
+    /// Instantiates the middleware with an existing, possibly shared, limiter
+    pub fn with_limiter(inner: M, limiter: Arc<RateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    /// Returns the limiter used by this middleware
+    pub fn limiter(&self) -> &Arc<RateLimiter> {
+        &self.limiter
+    }
+}
+
+#[derive(Error, Debug)]
+/// Thrown when an error happens at the Rate Limit middleware
+pub enum RateLimitError<M: Middleware> {
+    /// Thrown when the internal middleware errors
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> FromErr<M::Error> for RateLimitError<M> {
+    fn from(src: M::Error) -> Self {
+        RateLimitError::MiddlewareError(src)
+    }
+}
+
+impl<M> Middleware for RateLimitMiddleware<M>
+where
+    M: Middleware,
+{
+    type Error = RateLimitError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    fn client_version(&self) -> Result<String, Self::Error> {
+        self.limiter.acquire();
+        self.inner().client_version().map_err(FromErr::from)
+    }
+
+    fn web3_sha3(&self, data: Bytes) -> Result<H256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().web3_sha3(data).map_err(FromErr::from)
+    }
+
+    fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        self.limiter.acquire();
+        self.inner().fill_transaction(tx, block).map_err(FromErr::from)
+    }
+
+    fn get_block_number(&self) -> Result<U64, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_block_number().map_err(FromErr::from)
+    }
+
+    fn resolve_name(&self, ens_name: &str) -> Result<Address, Self::Error> {
+        self.limiter.acquire();
+        self.inner().resolve_name(ens_name).map_err(FromErr::from)
+    }
+
+    fn lookup_address(&self, address: Address) -> Result<String, Self::Error> {
+        self.limiter.acquire();
+        self.inner().lookup_address(address).map_err(FromErr::from)
+    }
+
+    fn resolve_avatar(&self, ens_name: &str) -> Result<Url, Self::Error> {
+        self.limiter.acquire();
+        self.inner().resolve_avatar(ens_name).map_err(FromErr::from)
+    }
+
+    fn resolve_nft(&self, token: erc::ERCNFT) -> Result<Url, Self::Error> {
+        self.limiter.acquire();
+        self.inner().resolve_nft(token).map_err(FromErr::from)
+    }
+
+    fn resolve_field(&self, ens_name: &str, field: &str) -> Result<String, Self::Error> {
+        self.limiter.acquire();
+        self.inner().resolve_field(ens_name, field).map_err(FromErr::from)
+    }
+
+    fn get_block<T: Into<BlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+    ) -> Result<Option<Block<TxHash>>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_block(block_hash_or_number).map_err(FromErr::from)
+    }
+
+    fn get_block_with_txs<T: Into<BlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+    ) -> Result<Option<Block<Transaction>>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_block_with_txs(block_hash_or_number).map_err(FromErr::from)
+    }
+
+    fn get_uncle_count<T: Into<BlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+    ) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_uncle_count(block_hash_or_number).map_err(FromErr::from)
+    }
+
+    fn get_uncle<T: Into<BlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+        idx: U64,
+    ) -> Result<Option<Block<H256>>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_uncle(block_hash_or_number, idx).map_err(FromErr::from)
+    }
+
+    fn get_transaction_count<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_transaction_count(from, block).map_err(FromErr::from)
+    }
+
+    fn estimate_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().estimate_gas(tx, block).map_err(FromErr::from)
+    }
+
+    fn call(&self, tx: &TypedTransaction, block: Option<BlockId>) -> Result<Bytes, Self::Error> {
+        self.limiter.acquire();
+        self.inner().call(tx, block).map_err(FromErr::from)
+    }
+
+    fn call_with_overrides(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        overrides: &spoof::State,
+    ) -> Result<Bytes, Self::Error> {
+        self.limiter.acquire();
+        self.inner().call_with_overrides(tx, block, overrides).map_err(FromErr::from)
+    }
+
+    fn call_with_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(Bytes, U256), Self::Error> {
+        self.limiter.acquire();
+        self.inner().call_with_gas(tx, block).map_err(FromErr::from)
+    }
+
+    fn syncing(&self) -> Result<SyncingStatus, Self::Error> {
+        self.limiter.acquire();
+        self.inner().syncing().map_err(FromErr::from)
+    }
+
+    fn get_chainid(&self) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_chainid().map_err(FromErr::from)
+    }
+
+    fn get_net_version(&self) -> Result<String, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_net_version().map_err(FromErr::from)
+    }
+
+    fn net_peer_count(&self) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().net_peer_count().map_err(FromErr::from)
+    }
+
+    fn net_listening(&self) -> Result<bool, Self::Error> {
+        self.limiter.acquire();
+        self.inner().net_listening().map_err(FromErr::from)
+    }
+
+    fn get_coinbase(&self) -> Result<Address, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_coinbase().map_err(FromErr::from)
+    }
+
+    fn get_mining(&self) -> Result<bool, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_mining().map_err(FromErr::from)
+    }
+
+    fn get_hashrate(&self) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_hashrate().map_err(FromErr::from)
+    }
+
+    fn get_balance<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_balance(from, block).map_err(FromErr::from)
+    }
+
+    fn get_balance_history<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        blocks: &[BlockNumber],
+    ) -> Result<Vec<U256>, Self::Error> {
+        // the history is fetched with a single batch request
+        self.limiter.acquire();
+        self.inner().get_balance_history(from, blocks).map_err(FromErr::from)
+    }
+
+    fn get_transaction<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<Transaction>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_transaction(transaction_hash).map_err(FromErr::from)
+    }
+
+    fn get_raw_transaction<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<Bytes>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_raw_transaction(transaction_hash).map_err(FromErr::from)
+    }
+
+    fn get_transaction_receipt<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<TransactionReceipt>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_transaction_receipt(transaction_hash).map_err(FromErr::from)
+    }
+
+    fn get_block_receipts<T: Into<BlockNumber> + Send + Sync>(
+        &self,
+        block: T,
+    ) -> Result<Vec<TransactionReceipt>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_block_receipts(block).map_err(FromErr::from)
+    }
+
+    fn get_gas_price(&self) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_gas_price().map_err(FromErr::from)
+    }
+
+    fn estimate_eip1559_fees(
+        &self,
+        estimator: Option<fn(U256, Vec<Vec<U256>>) -> (U256, U256)>,
+    ) -> Result<(U256, U256), Self::Error> {
+        self.limiter.acquire();
+        self.inner().estimate_eip1559_fees(estimator).map_err(FromErr::from)
+    }
+
+    fn get_accounts(&self) -> Result<Vec<Address>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_accounts().map_err(FromErr::from)
+    }
+
+    fn sign<T: Into<Bytes> + Send + Sync>(
+        &self,
+        data: T,
+        from: &Address,
+    ) -> Result<Signature, Self::Error> {
+        self.limiter.acquire();
+        self.inner().sign(data, from).map_err(FromErr::from)
+    }
+
+    fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+        from: Address,
+    ) -> Result<Signature, Self::Error> {
+        self.limiter.acquire();
+        self.inner().sign_transaction(tx, from).map_err(FromErr::from)
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_logs(filter).map_err(FromErr::from)
+    }
+
+    fn get_logs_for_event<T: Into<BlockNumber> + Send + Sync>(
+        &self,
+        address: Address,
+        event_signature: &str,
+        from: T,
+        to: T,
+    ) -> Result<Vec<Log>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_logs_for_event(address, event_signature, from, to).map_err(FromErr::from)
+    }
+
+    fn new_filter(&self, filter: FilterKind<'_>) -> Result<U256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().new_filter(filter).map_err(FromErr::from)
+    }
+
+    fn uninstall_filter<T: Into<U256> + Send + Sync>(&self, id: T) -> Result<bool, Self::Error> {
+        self.limiter.acquire();
+        self.inner().uninstall_filter(id).map_err(FromErr::from)
+    }
+
+    fn get_filter_changes<T, R>(&self, id: T) -> Result<Vec<R>, Self::Error>
+    where
+        T: Into<U256> + Send + Sync,
+        R: Serialize + DeserializeOwned + Send + Sync + Debug,
+    {
+        self.limiter.acquire();
+        self.inner().get_filter_changes(id).map_err(FromErr::from)
+    }
+
+    fn get_filter_logs<T: Into<U256> + Send + Sync>(&self, id: T) -> Result<Vec<Log>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_filter_logs(id).map_err(FromErr::from)
+    }
+
+    fn get_code<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        at: T,
+        block: Option<BlockId>,
+    ) -> Result<Bytes, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_code(at, block).map_err(FromErr::from)
+    }
+
+    fn get_storage_at<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        location: H256,
+        block: Option<BlockId>,
+    ) -> Result<H256, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_storage_at(from, location, block).map_err(FromErr::from)
+    }
+
+    fn get_proof<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        locations: Vec<H256>,
+        block: Option<BlockId>,
+    ) -> Result<EIP1186ProofResponse, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_proof(from, locations, block).map_err(FromErr::from)
+    }
+
+    fn get_account<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        account: T,
+        block: Option<BlockId>,
+    ) -> Result<Account, Self::Error> {
+        self.limiter.acquire();
+        self.inner().get_account(account, block).map_err(FromErr::from)
+    }
+
+    fn txpool_content(&self) -> Result<TxpoolContent, Self::Error> {
+        self.limiter.acquire();
+        self.inner().txpool_content().map_err(FromErr::from)
+    }
+
+    fn pending_transactions_from(&self, from: Address) -> Result<Vec<Transaction>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().pending_transactions_from(from).map_err(FromErr::from)
+    }
+
+    fn txpool_inspect(&self) -> Result<TxpoolInspect, Self::Error> {
+        self.limiter.acquire();
+        self.inner().txpool_inspect().map_err(FromErr::from)
+    }
+
+    fn txpool_status(&self) -> Result<TxpoolStatus, Self::Error> {
+        self.limiter.acquire();
+        self.inner().txpool_status().map_err(FromErr::from)
+    }
+
+    fn debug_trace_transaction(
+        &self,
+        tx_hash: TxHash,
+        trace_options: GethDebugTracingOptions,
+    ) -> Result<GethTrace, ProviderError> {
+        self.limiter.acquire();
+        self.inner().debug_trace_transaction(tx_hash, trace_options).map_err(FromErr::from)
+    }
+
+    fn debug_trace_block_by_number(
+        &self,
+        block: BlockNumber,
+        trace_options: GethDebugTracingOptions,
+    ) -> Result<Vec<GethTrace>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().debug_trace_block_by_number(block, trace_options).map_err(FromErr::from)
+    }
+
+    fn debug_trace_block_by_hash(
+        &self,
+        block_hash: H256,
+        trace_options: GethDebugTracingOptions,
+    ) -> Result<Vec<GethTrace>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().debug_trace_block_by_hash(block_hash, trace_options).map_err(FromErr::from)
+    }
+
+    fn trace_call<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        req: T,
+        trace_type: Vec<TraceType>,
+        block: Option<BlockNumber>,
+    ) -> Result<BlockTrace, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_call(req, trace_type, block).map_err(FromErr::from)
+    }
+
+    fn trace_call_many<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        req: Vec<(T, Vec<TraceType>)>,
+        block: Option<BlockNumber>,
+    ) -> Result<Vec<BlockTrace>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_call_many(req, block).map_err(FromErr::from)
+    }
+
+    fn trace_raw_transaction(
+        &self,
+        data: Bytes,
+        trace_type: Vec<TraceType>,
+    ) -> Result<BlockTrace, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_raw_transaction(data, trace_type).map_err(FromErr::from)
+    }
+
+    fn trace_replay_transaction(
+        &self,
+        hash: H256,
+        trace_type: Vec<TraceType>,
+    ) -> Result<BlockTrace, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_replay_transaction(hash, trace_type).map_err(FromErr::from)
+    }
+
+    fn trace_replay_block_transactions(
+        &self,
+        block: BlockNumber,
+        trace_type: Vec<TraceType>,
+    ) -> Result<Vec<BlockTrace>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_replay_block_transactions(block, trace_type).map_err(FromErr::from)
+    }
+
+    fn trace_block(&self, block: BlockNumber) -> Result<Vec<Trace>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_block(block).map_err(FromErr::from)
+    }
+
+    fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<Trace>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_filter(filter).map_err(FromErr::from)
+    }
+
+    fn trace_get<T: Into<U64> + Send + Sync>(
+        &self,
+        hash: H256,
+        index: Vec<T>,
+    ) -> Result<Trace, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_get(hash, index).map_err(FromErr::from)
+    }
+
+    fn trace_transaction(&self, hash: H256) -> Result<Vec<Trace>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().trace_transaction(hash).map_err(FromErr::from)
+    }
+
+    fn parity_block_receipts<T: Into<BlockNumber> + Send + Sync>(
+        &self,
+        block: T,
+    ) -> Result<Vec<TransactionReceipt>, Self::Error> {
+        self.limiter.acquire();
+        self.inner().parity_block_receipts(block).map_err(FromErr::from)
+    }
+
+    fn fee_history<T: Into<U256> + serde::Serialize + Send + Sync>(
+        &self,
+        block_count: T,
+        last_block: BlockNumber,
+        reward_percentiles: &[f64],
+    ) -> Result<FeeHistory, Self::Error> {
+        self.limiter.acquire();
+        self.inner().fee_history(block_count, last_block, reward_percentiles).map_err(FromErr::from)
+    }
+
+    fn create_access_list(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<AccessListWithGasUsed, Self::Error> {
+        self.limiter.acquire();
+        self.inner().create_access_list(tx, block).map_err(FromErr::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_providers::Provider;
+
+    #[test]
+    fn caps_throughput() {
+        let (provider, mock) = Provider::mocked();
+        let (rate, burst, calls) = (50, 2, 12);
+        for _ in 0..calls {
+            mock.push(U64::from(1)).unwrap();
+        }
+        let client = RateLimitMiddleware::new(provider, rate, burst);
+
+        let start = Instant::now();
+        for _ in 0..calls {
+            client.get_block_number().unwrap();
+        }
+        let elapsed = start.elapsed();
+
+        // the burst goes through immediately, every further call has to wait for a new token
+        let min = Duration::from_secs_f64((calls - burst) as f64 / rate as f64);
+        assert!(elapsed >= min, "{} calls took {:?}, expected at least {:?}", calls, elapsed, min);
+    }
+
+    #[test]
+    fn clones_share_limiter() {
+        let (provider, _) = Provider::mocked();
+        let client = RateLimitMiddleware::new(provider, 1, 1);
+        let cloned = client.clone();
+        assert!(Arc::ptr_eq(client.limiter(), cloned.limiter()));
+    }
+}
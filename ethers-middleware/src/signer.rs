@@ -2,7 +2,7 @@ use ethers_core::types::{
     transaction::{eip2718::TypedTransaction, eip2930::AccessListWithGasUsed},
     Address, BlockId, Bytes, Signature, U256,
 };
-use ethers_providers::{maybe, FromErr, Middleware};
+use ethers_providers::{call_raw::spoof, maybe, FromErr, Middleware};
 use ethers_signers::Signer;
 
 use async_trait::async_trait;
@@ -13,6 +13,8 @@ pub struct SignerMiddleware<M, S> {
     pub(crate) inner: M,
     pub(crate) signer: S,
     pub(crate) address: Address,
+    pub(crate) gas_buffer: Option<f64>,
+    pub(crate) signing_chain_id: Option<u64>,
 }
 
 impl<M: Middleware, S: Signer> FromErr<M::Error> for SignerMiddlewareError<M, S> {
@@ -47,6 +49,9 @@ pub enum SignerMiddlewareError<M: Middleware, S: Signer> {
     /// Thrown if the signer's chain_id is different than the chain_id of the transaction
     #[error("specified chain_id is different than the signer's chain_id")]
     DifferentChainID,
+    /// Thrown if the gas buffer is not a finite number of at least 1
+    #[error("invalid gas buffer: {0}")]
+    InvalidGasBuffer(f64),
 }
 
 // Helper functions for locally signing transactions
@@ -66,20 +71,21 @@ where
     /// [`Signer`] ethers_signers::Signer
     pub fn new(inner: M, signer: S) -> Self {
         let address = signer.address();
-        SignerMiddleware { inner, signer, address }
+        SignerMiddleware { inner, signer, address, gas_buffer: None, signing_chain_id: None }
     }
 
-    /// Signs and returns the RLP encoding of the signed transaction.
+    /// Signs and returns the RLP encoding of the signed transaction, without filling in any of
+    /// its fields or sending it. This is useful for inspecting or storing the signed payload.
     /// If the transaction does not have a chain id set, it sets it to the signer's chain id.
     /// Returns an error if the transaction's existing chain id does not match the signer's chain
     /// id.
-    async fn sign_transaction(
+    pub async fn sign_transaction_raw(
         &self,
         mut tx: TypedTransaction,
     ) -> Result<Bytes, SignerMiddlewareError<M, S>> {
         // compare chain_id and use signer's chain_id if the tranasaction's chain_id is None,
         // return an error if they are not consistent
-        let chain_id = self.signer.chain_id();
+        let chain_id = self.signing_chain_id();
         match tx.chain_id() {
             Some(id) if id.as_u64() != chain_id => {
                 return Err(SignerMiddlewareError::DifferentChainID)
@@ -94,7 +100,7 @@ where
             self.signer.sign_transaction(&tx).await.map_err(SignerMiddlewareError::SignerError)?;
 
         // Return the raw rlp-encoded signed transaction
-        Ok(tx.rlp_signed(&signature))
+        Ok(tx.rlp_network(&signature))
     }
 
     /// Returns the client's address
@@ -107,6 +113,31 @@ where
         &self.signer
     }
 
+    /// Scales the gas estimate of transactions filled by this middleware by `multiplier`, to
+    /// reduce out-of-gas failures when the node under-reports gas. Transactions with an explicit
+    /// gas limit are left untouched. Multipliers which are not finite or below `1.0` are rejected.
+    pub fn with_gas_buffer(mut self, multiplier: f64) -> Result<Self, SignerMiddlewareError<M, S>> {
+        if !multiplier.is_finite() || multiplier < 1.0 {
+            return Err(SignerMiddlewareError::InvalidGasBuffer(multiplier))
+        }
+        self.gas_buffer = Some(multiplier);
+        Ok(self)
+    }
+
+    /// Signs transactions for `chain_id` instead of the signer's chain id, for chains which report
+    /// a different chain id over RPC than the one they use for signing. Transactions which already
+    /// set a different chain id are still rejected.
+    #[must_use]
+    pub fn with_signing_chain_id(mut self, chain_id: u64) -> Self {
+        self.signing_chain_id = Some(chain_id);
+        self
+    }
+
+    /// Returns the chain id transactions are signed for
+    fn signing_chain_id(&self) -> u64 {
+        self.signing_chain_id.unwrap_or_else(|| self.signer.chain_id())
+    }
+
     /// Builds a SignerMiddleware with the given Signer.
     #[must_use]
     pub fn with_signer(&self, signer: S) -> Self
@@ -135,7 +166,7 @@ where
         let chain_id =
             inner.get_chainid().await.map_err(|e| SignerMiddlewareError::MiddlewareError(e))?;
         let signer = signer.with_chain_id(chain_id.as_u64());
-        Ok(SignerMiddleware { inner, signer, address })
+        Ok(SignerMiddleware { inner, signer, address, gas_buffer: None, signing_chain_id: None })
     }
 
     fn set_tx_from_if_none(&self, tx: &TypedTransaction) -> TypedTransaction {
@@ -177,7 +208,11 @@ where
         tx: &TypedTransaction,
         _: Address,
     ) -> Result<Signature, Self::Error> {
-        Ok(self.signer.sign_transaction(tx).await.map_err(SignerMiddlewareError::SignerError)?)
+        let mut tx = tx.clone();
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.signing_chain_id());
+        }
+        Ok(self.signer.sign_transaction(&tx).await.map_err(SignerMiddlewareError::SignerError)?)
     }
 
     /// Helper for filling a transaction's nonce using the wallet
@@ -195,13 +230,20 @@ where
         tx.set_from(from);
 
         // get the signer's chain_id if the transaction does not set it
-        let chain_id = self.signer.chain_id();
+        let chain_id = self.signing_chain_id();
         if tx.chain_id().is_none() {
             tx.set_chain_id(chain_id);
         }
 
         let nonce = maybe(tx.nonce().cloned(), self.get_transaction_count(from, block)).await?;
         tx.set_nonce(nonce);
+
+        // apply the gas buffer before the inner middleware fills in a plain estimate
+        if let (None, Some(multiplier)) = (tx.gas(), self.gas_buffer) {
+            let gas = self.estimate_gas_buffered(tx, block, multiplier).await?;
+            tx.set_gas(gas);
+        }
+
         self.inner()
             .fill_transaction(tx, block)
             .await
@@ -248,13 +290,25 @@ where
         let tx = self.set_tx_from_if_none(tx);
         self.inner().call(&tx, block).await.map_err(SignerMiddlewareError::MiddlewareError)
     }
+
+    fn call_with_overrides(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        overrides: &spoof::State,
+    ) -> Result<Bytes, Self::Error> {
+        let tx = self.set_tx_from_if_none(tx);
+        self.inner()
+            .call_with_overrides(&tx, block, overrides)
+            .map_err(SignerMiddlewareError::MiddlewareError)
+    }
 }
 
 #[cfg(all(test, not(feature = "celo"), not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
     use ethers_core::{
-        types::TransactionRequest,
+        types::{BlockNumber, TransactionRequest},
         utils::{self, keccak256, Anvil},
     };
     use ethers_providers::Provider;
@@ -289,7 +343,7 @@ mod tests {
             .with_chain_id(chain_id);
         let client = SignerMiddleware::new(provider, key);
 
-        let tx = client.sign_transaction(tx).await.unwrap();
+        let tx = client.sign_transaction_raw(tx).await.unwrap();
 
         assert_eq!(
             keccak256(&tx)[..],
@@ -301,6 +355,73 @@ mod tests {
         assert_eq!(tx, expected_rlp);
     }
 
+    #[tokio::test]
+    async fn signs_tx_raw_without_provider() {
+        // same vector as `signs_tx`, signing must not touch the provider
+        let tx = TransactionRequest {
+            from: None,
+            to: Some("F0109fC8DF283027b6285cc889F5aA624EaC1F55".parse::<Address>().unwrap().into()),
+            value: Some(1_000_000_000.into()),
+            gas: Some(2_000_000.into()),
+            nonce: Some(0.into()),
+            gas_price: Some(21_000_000_000u128.into()),
+            data: None,
+            chain_id: None,
+        }
+        .into();
+
+        let (provider, _mock) = Provider::mocked();
+        let key = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1u64);
+        let client = SignerMiddleware::new(provider, key);
+
+        let tx = client.sign_transaction_raw(tx).await.unwrap();
+
+        let expected_rlp = Bytes::from(hex::decode("f869808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a0c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895a0727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68").unwrap());
+        assert_eq!(tx, expected_rlp);
+    }
+
+    #[tokio::test]
+    async fn signs_tx_with_signing_chain_id() {
+        // same vector as `signs_tx`, signed for mainnet by a signer set up for another chain
+        let tx: TypedTransaction = TransactionRequest {
+            from: None,
+            to: Some("F0109fC8DF283027b6285cc889F5aA624EaC1F55".parse::<Address>().unwrap().into()),
+            value: Some(1_000_000_000.into()),
+            gas: Some(2_000_000.into()),
+            nonce: Some(0.into()),
+            gas_price: Some(21_000_000_000u128.into()),
+            data: None,
+            chain_id: None,
+        }
+        .into();
+
+        let (provider, _mock) = Provider::mocked();
+        let key = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1337u64);
+        let client = SignerMiddleware::new(provider, key).with_signing_chain_id(1);
+
+        let signed = client.sign_transaction_raw(tx.clone()).await.unwrap();
+        let expected_rlp = Bytes::from(hex::decode("f869808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a0c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895a0727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68").unwrap());
+        assert_eq!(signed, expected_rlp);
+
+        // the signature carries the overridden chain id, v = 1 * 2 + 35 + recovery id
+        let signature = client.sign_transaction(&tx, client.address()).await.unwrap();
+        assert_eq!(signature.v, 37);
+
+        // a transaction for the signer's own chain no longer matches
+        let mut tx = tx;
+        tx.set_chain_id(1337u64);
+        assert!(matches!(
+            client.sign_transaction_raw(tx).await,
+            Err(SignerMiddlewareError::DifferentChainID)
+        ));
+    }
+
     #[tokio::test]
     async fn signs_tx_none_chainid() {
         // retrieved test vector from:
@@ -331,7 +452,7 @@ mod tests {
             .with_chain_id(chain_id);
         let client = SignerMiddleware::new(provider, key);
 
-        let tx = client.sign_transaction(tx).await.unwrap();
+        let tx = client.sign_transaction_raw(tx).await.unwrap();
 
         let expected_rlp = Bytes::from(hex::decode("f86b808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca0080820a95a08290324bae25ca0490077e0d1f4098730333088f6a500793fa420243f35c6b23a06aca42876cd28fdf614a4641e64222fee586391bb3f4061ed5dfefac006be850").unwrap());
         assert_eq!(tx, expected_rlp);
@@ -380,4 +501,54 @@ mod tests {
         let signer_chainid = signer.chain_id();
         assert_eq!(chain_id.as_u64(), signer_chainid);
     }
+
+    #[tokio::test]
+    async fn fill_transaction_applies_gas_buffer() {
+        let (provider, mock) = Provider::mocked();
+        let key = LocalWallet::new(&mut rand::thread_rng()).with_chain_id(1u64);
+        let client = SignerMiddleware::new(provider, key).with_gas_buffer(1.5).unwrap();
+
+        let tx = TransactionRequest::new().to(Address::zero()).nonce(0).gas_price(1);
+
+        // the estimate is scaled by the buffer
+        mock.push(U256::from(21_000)).unwrap();
+        let mut buffered: TypedTransaction = tx.clone().into();
+        client.fill_transaction(&mut buffered, None).await.unwrap();
+        assert_eq!(buffered.gas(), Some(&U256::from(31_500)));
+
+        // an explicitly set gas limit is left untouched
+        let mut explicit: TypedTransaction = tx.gas(50_000).into();
+        client.fill_transaction(&mut explicit, None).await.unwrap();
+        assert_eq!(explicit.gas(), Some(&U256::from(50_000)));
+    }
+
+    #[test]
+    fn rejects_invalid_gas_buffer() {
+        for multiplier in [f64::NAN, f64::INFINITY, -1.0, 0.5] {
+            let (provider, _) = Provider::mocked();
+            let key = LocalWallet::new(&mut rand::thread_rng()).with_chain_id(1u64);
+            let err = SignerMiddleware::new(provider, key).with_gas_buffer(multiplier).unwrap_err();
+            assert!(matches!(err, SignerMiddlewareError::InvalidGasBuffer(_)));
+        }
+    }
+
+    #[test]
+    fn call_with_overrides_reaches_provider() {
+        let (provider, mock) = Provider::mocked();
+        let key = LocalWallet::new(&mut rand::thread_rng()).with_chain_id(1u64);
+        let client = SignerMiddleware::new(provider, key);
+
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::zero()).into();
+        let state = spoof::balance(client.address(), 1_000u64.into());
+
+        let returned = Bytes::from(vec![1u8, 2, 3]);
+        mock.push::<Bytes, _>(returned.clone()).unwrap();
+        let res = client.call_with_overrides(&tx, None, &state).unwrap();
+        assert_eq!(res, returned);
+
+        // the signer's address is filled in and the overrides are passed through untouched
+        let mut expected = tx.clone();
+        expected.set_from(client.address());
+        mock.assert_request("eth_call", (expected, BlockNumber::Latest, state)).unwrap();
+    }
 }
@@ -62,7 +62,7 @@ async fn deploy_and_call_contract() {
     let deployer = factory.deploy(()).unwrap().legacy();
     let contract = deployer.block(BlockNumber::Pending).send().await.unwrap();
 
-    let value: U256 = contract.method("value", ()).unwrap().call().await.unwrap();
+    let value: U256 = contract.method("value", ()).unwrap().call().unwrap();
     assert_eq!(value, 0.into());
 
     // make a state mutating transaction
@@ -72,7 +72,7 @@ async fn deploy_and_call_contract() {
     let pending_tx = call.send().await.unwrap();
     let _receipt = pending_tx.await.unwrap();
 
-    let value: U256 = contract.method("value", ()).unwrap().call().await.unwrap();
+    let value: U256 = contract.method("value", ()).unwrap().call().unwrap();
     assert_eq!(value, 1.into());
 }
 
@@ -1,16 +1,17 @@
 //! Overrides for the `eth_call` rpc method
 
-use crate::{JsonRpcClient, PinBoxFut, Provider, ProviderError};
+use crate::{JsonRpcClient, Provider, ProviderError};
 use ethers_core::{
     types::{
-        transaction::eip2718::TypedTransaction, Address, BlockId, BlockNumber, Bytes, H256, U256,
-        U64,
+        transaction::eip2718::TypedTransaction, Address, BlockId, BlockNumber, Bytes, PreStateDiff,
+        H256, U256, U64,
     },
     utils,
 };
 use pin_project::pin_project;
 use serde::{ser::SerializeTuple, Deserialize, Serialize};
 use std::{
+    borrow::Cow,
     fmt,
     pin::Pin,
     task::{Context, Poll},
@@ -25,6 +26,16 @@ pub trait RawCall<'a> {
     /// Sets the [state override set](https://geth.ethereum.org/docs/rpc/ns-eth#3-object---state-override-set).
     /// Note that not all client implementations will support this as a parameter.
     fn state(self, state: &'a spoof::State) -> Self;
+    /// Sets the gas limit of the call, overriding the gas of the transaction.
+    ///
+    /// Nodes execute calls without a gas limit with their configured call gas cap (e.g. geth's
+    /// `--rpc.gascap`), so complex view functions may run out of gas. A larger limit is honored up
+    /// to that cap.
+    fn gas(self, gas: U256) -> Self;
+    /// Sets the [block overrides](https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-eth#eth-call)
+    /// of the block the call is executed in, e.g. to simulate time-dependent logic.
+    /// Note that not all client implementations will support this as a parameter.
+    fn block_overrides(self, overrides: BlockOverrides) -> Self;
 
     /// Maps a closure `f` over the result of `.await`ing this call
     fn map<F>(self, f: F) -> Map<Self, F>
@@ -102,6 +113,14 @@ impl<'a, P> RawCall<'a> for CallBuilder<'a, P> {
     fn state(self, state: &'a spoof::State) -> Self {
         self.map_input(|mut call| call.input.state = Some(state))
     }
+    /// Sets the gas limit of the call, overriding the gas of the transaction
+    fn gas(self, gas: U256) -> Self {
+        self.map_input(|call| call.input.gas = Some(gas))
+    }
+    /// Sets the block overrides of the block the call is executed in
+    fn block_overrides(self, overrides: BlockOverrides) -> Self {
+        self.map_input(|call| call.input.block_overrides = Some(overrides))
+    }
 }
 
 /// Holds the inputs to the `eth_call` rpc method along with the rpc provider.
@@ -121,7 +140,9 @@ impl<'a, P: JsonRpcClient> Caller<'a, P> {
     /// Executes an `eth_call` rpc request with the overriden parameters. Returns a future that
     /// resolves to the result of the request.
     fn execute(&self) -> Result<Bytes, ProviderError> {
-        self.provider.request("eth_call", utils::serialize(&self.input))
+        let mut params = utils::serialize(&self.input);
+        params[0] = self.provider.serialize_tx(&self.input.tx());
+        self.provider.request("eth_call", params)
     }
 }
 
@@ -131,11 +152,25 @@ struct CallInput<'a> {
     tx: &'a TypedTransaction,
     block: Option<BlockId>,
     state: Option<&'a spoof::State>,
+    gas: Option<U256>,
+    block_overrides: Option<BlockOverrides>,
 }
 
 impl<'a> CallInput<'a> {
     fn new(tx: &'a TypedTransaction) -> Self {
-        Self { tx, block: None, state: None }
+        Self { tx, block: None, state: None, gas: None, block_overrides: None }
+    }
+
+    /// Returns the transaction with the gas override applied
+    fn tx(&self) -> Cow<'a, TypedTransaction> {
+        match self.gas {
+            Some(gas) => {
+                let mut tx = self.tx.clone();
+                tx.set_gas(gas);
+                Cow::Owned(tx)
+            }
+            None => Cow::Borrowed(self.tx),
+        }
     }
 }
 
@@ -144,16 +179,26 @@ impl<'a> Serialize for CallInput<'a> {
     where
         S: serde::ser::Serializer,
     {
-        let len = 2 + self.state.is_some() as usize;
+        // the block overrides follow the state overrides, which are sent empty if not set
+        let len = match (self.state, &self.block_overrides) {
+            (_, Some(_)) => 4,
+            (Some(_), None) => 3,
+            (None, None) => 2,
+        };
 
         let mut tup = serializer.serialize_tuple(len)?;
-        tup.serialize_element(self.tx)?;
+        tup.serialize_element(&self.tx())?;
 
         let block = self.block.unwrap_or_else(|| BlockNumber::Latest.into());
         tup.serialize_element(&block)?;
 
         if let Some(state) = self.state {
             tup.serialize_element(state)?;
+        } else if self.block_overrides.is_some() {
+            tup.serialize_element(&spoof::State::default())?;
+        }
+        if let Some(ref overrides) = self.block_overrides {
+            tup.serialize_element(overrides)?;
         }
         tup.end()
     }
@@ -199,6 +244,44 @@ where
     fn state(self, state: &'a spoof::State) -> Self {
         Self { inner: self.inner.state(state), f: self.f }
     }
+
+    /// Sets the gas limit of the call, overriding the gas of the transaction
+    fn gas(self, gas: U256) -> Self {
+        Self { inner: self.inner.gas(gas), f: self.f }
+    }
+
+    /// Sets the block overrides of the block the call is executed in
+    fn block_overrides(self, overrides: BlockOverrides) -> Self {
+        Self { inner: self.inner.block_overrides(overrides), f: self.f }
+    }
+}
+
+/// Overrides for the fields of the block an `eth_call` is executed in. Fields left as `None` keep
+/// the value of the block the call is executed against.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    /// Overrides the block number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<U64>,
+    /// Overrides the block difficulty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<U256>,
+    /// Overrides the block timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<U64>,
+    /// Overrides the block gas limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U64>,
+    /// Overrides the block's fee recipient
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coinbase: Option<Address>,
+    /// Overrides the `PREVRANDAO` value of the block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random: Option<H256>,
+    /// Overrides the base fee of the block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<U256>,
 }
 
 /// Provides types and methods for constructing an `eth_call`
@@ -289,6 +372,35 @@ pub mod spoof {
         pub fn account(&mut self, adr: Address) -> &mut Account {
             self.0.entry(adr).or_default()
         }
+
+        /// Applies the state changes of a transaction, as traced by the `prestateTracer` in
+        /// `diffMode`, so calls made with the overrides see the state after the transaction
+        pub fn apply_diff(&mut self, diff: &PreStateDiff) {
+            for (&address, post) in &diff.post {
+                let account = self.account(address);
+                if let Some(nonce) = post.nonce {
+                    account.nonce(nonce.into());
+                }
+                if let Some(balance) = post.balance {
+                    account.balance(balance);
+                }
+                if let Some(ref code) = post.code {
+                    account.code(code.clone());
+                }
+                for (&key, &val) in post.storage.iter().flatten() {
+                    account.store(key, val);
+                }
+            }
+            // slots which are missing after the transaction were cleared
+            for (&address, pre) in &diff.pre {
+                let post = diff.post.get(&address).and_then(|post| post.storage.as_ref());
+                for &key in pre.storage.iter().flatten().map(|(key, _)| key) {
+                    if post.map_or(true, |post| !post.contains_key(&key)) {
+                        self.account(address).store(key, H256::zero());
+                    }
+                }
+            }
+        }
     }
 
     /// Returns an empty state override set.
@@ -347,7 +459,13 @@ mod tests {
     );
     impl<'a> From<&'a CallInputOwned> for CallInput<'a> {
         fn from(src: &'a CallInputOwned) -> Self {
-            Self { tx: &src.0, block: src.1, state: src.2.as_ref() }
+            Self {
+                tx: &src.0,
+                block: src.1,
+                state: src.2.as_ref(),
+                gas: None,
+                block_overrides: None,
+            }
         }
     }
 
@@ -358,7 +476,7 @@ mod tests {
         let de: CallInputOwned = serde_json::from_str(&ser).unwrap();
         let de = CallInput::from(&de);
 
-        assert_eq!(input.tx, de.tx);
+        assert_eq!(*input.tx(), *de.tx);
         assert_eq!(input.state, de.state);
 
         let block = input.block.or_else(|| Some(BlockNumber::Latest.into()));
@@ -396,6 +514,68 @@ mod tests {
         state.account(adr1);
         let call = provider.call_raw(&tx).state(&state);
         test_encode(call);
+
+        let call = provider.call_raw(&tx).gas(1_000_000.into());
+        test_encode(call);
+    }
+
+    #[test]
+    fn test_gas_override() {
+        let (provider, mock) = Provider::mocked();
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::zero()).gas(21_000).into();
+
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        provider.call_raw(&tx).gas(50_000_000.into()).poll().unwrap();
+
+        let mut expected = tx.clone();
+        expected.set_gas(50_000_000);
+        mock.assert_request("eth_call", (expected, BlockNumber::Latest)).unwrap();
+
+        // the transaction itself is left untouched
+        assert_eq!(tx.gas(), Some(&21_000.into()));
+    }
+
+    #[test]
+    fn test_apply_diff() {
+        let adr = Address::repeat_byte(1);
+        let (k1, k2) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+        let (a, s1, s2) = (format!("{:?}", adr), format!("{:?}", k1), format!("{:?}", k2));
+        let diff: PreStateDiff = serde_json::from_value(serde_json::json!({
+            "pre": { &a: { "nonce": 1, "storage": { &s1: k2, &s2: k1 } } },
+            "post": { &a: { "nonce": 2, "storage": { &s1: k1 } } }
+        }))
+        .unwrap();
+
+        let mut state = spoof::balance(adr, 5.into());
+        state.apply_diff(&diff);
+
+        let mut expected = spoof::balance(adr, 5.into());
+        // the cleared slot is zeroed
+        expected.account(adr).nonce(2.into()).store(k1, k1).store(k2, H256::zero());
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    fn test_block_overrides() {
+        let (provider, mock) = Provider::mocked();
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::zero()).into();
+        let overrides = BlockOverrides {
+            number: Some(100.into()),
+            time: Some(1_700_000_000.into()),
+            ..Default::default()
+        };
+
+        // the state overrides are sent empty to get to the block overrides
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        provider.call_raw(&tx).block_overrides(overrides.clone()).poll().unwrap();
+        let expected = serde_json::json!({ "number": "0x64", "time": "0x6553f100" });
+        mock.assert_request("eth_call", (&tx, BlockNumber::Latest, spoof::state(), &expected))
+            .unwrap();
+
+        let state = spoof::balance(Address::zero(), 1.into());
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        provider.call_raw(&tx).state(&state).block_overrides(overrides).poll().unwrap();
+        mock.assert_request("eth_call", (&tx, BlockNumber::Latest, &state, &expected)).unwrap();
     }
 
     #[test]
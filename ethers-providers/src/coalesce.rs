@@ -0,0 +1,123 @@
+use crate::ProviderError;
+
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Condvar, Mutex, PoisonError},
+};
+
+/// Method, serialized params and maximum response size of a request
+type RequestKey = (String, String, Option<usize>);
+
+/// Shares the response of a request with the identical requests made while it is in flight, so
+/// bursts of callers asking for the same data (e.g. the latest block) result in a single call to
+/// the node.
+#[derive(Debug, Default)]
+pub(crate) struct RequestCoalescer {
+    in_flight: Mutex<HashMap<RequestKey, Arc<InFlight>>>,
+}
+
+#[derive(Debug, Default)]
+struct InFlight {
+    /// `None` while the request is in flight, then the response if the request succeeded
+    response: Mutex<Option<Option<Value>>>,
+    done: Condvar,
+}
+
+impl RequestCoalescer {
+    /// Sends the request with `send`, unless an identical request is in flight, in which case its
+    /// response is awaited instead. Errors can not be shared, so the callers waiting on a request
+    /// which failed send their own.
+    pub(crate) fn coalesce<F>(
+        &self,
+        method: &str,
+        params: String,
+        max_size: Option<usize>,
+        send: F,
+    ) -> Result<Value, ProviderError>
+    where
+        F: FnOnce() -> Result<Value, ProviderError>,
+    {
+        let key = (method.to_string(), params, max_size);
+        let in_flight = {
+            let mut requests = self.in_flight.lock().unwrap();
+            match requests.get(&key) {
+                Some(in_flight) => Some(in_flight.clone()),
+                None => {
+                    requests.insert(key.clone(), Arc::new(InFlight::default()));
+                    None
+                }
+            }
+        };
+
+        if let Some(in_flight) = in_flight {
+            let mut response = in_flight.response.lock().unwrap();
+            while response.is_none() {
+                response = in_flight.done.wait(response).unwrap();
+            }
+            if let Some(Some(ref value)) = *response {
+                return Ok(value.clone())
+            }
+            drop(response);
+            return send()
+        }
+
+        let mut completion = Completion { coalescer: self, key, response: None };
+        let res = send();
+        completion.response = res.as_ref().ok().cloned();
+        res
+    }
+}
+
+/// Completes an in-flight request when dropped, so the callers waiting on it are woken up even if
+/// sending it panicked
+struct Completion<'a> {
+    coalescer: &'a RequestCoalescer,
+    key: RequestKey,
+    /// The response to share, if the request succeeded
+    response: Option<Value>,
+}
+
+impl Drop for Completion<'_> {
+    fn drop(&mut self) {
+        // later requests are sent again, as the response may have changed since. Poisoned locks
+        // are still taken, as panicking again while unwinding would abort.
+        let in_flight = self
+            .coalescer
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.key);
+        if let Some(in_flight) = in_flight {
+            *in_flight.response.lock().unwrap_or_else(PoisonError::into_inner) =
+                Some(self.response.take());
+            in_flight.done.notify_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn wakes_up_waiters_if_the_request_panics() {
+        let coalescer = Arc::new(RequestCoalescer::default());
+        let leader = {
+            let coalescer = coalescer.clone();
+            thread::spawn(move || {
+                coalescer.coalesce("eth_blockNumber", "[]".to_string(), None, || {
+                    thread::sleep(Duration::from_millis(200));
+                    panic!("transport panicked")
+                })
+            })
+        };
+        thread::sleep(Duration::from_millis(50));
+        let res = coalescer
+            .coalesce("eth_blockNumber", "[]".to_string(), None, || Ok(Value::from("0x1")));
+        assert_eq!(res.unwrap(), Value::from("0x1"));
+        assert!(leader.join().is_err());
+        assert!(coalescer.in_flight.lock().unwrap().is_empty());
+    }
+}
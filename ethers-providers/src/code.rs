@@ -0,0 +1,60 @@
+use super::{JsonRpcClient, Middleware, Provider, ProviderError};
+use ethers_core::{
+    types::{Address, Bytes, H256, U64},
+    utils::keccak256,
+};
+use std::thread;
+
+/// Iterator over the changes of the code of an account, see [`Provider::watch_code`].
+///
+/// Every call to `next` polls the block number at the provider's interval, and fetches the code
+/// at every new block until its hash differs from the one of the code at the previous block. The
+/// code at the block the watcher starts at is not yielded, it is what later code is compared to.
+/// The iterator never ends on its own.
+pub struct CodeWatcher<'a, P> {
+    provider: &'a Provider<P>,
+    address: Address,
+    block: Option<U64>,
+    code_hash: Option<H256>,
+}
+
+impl<'a, P> CodeWatcher<'a, P>
+where
+    P: JsonRpcClient,
+{
+    pub fn new(provider: &'a Provider<P>, address: Address) -> Self {
+        Self { provider, address, block: None, code_hash: None }
+    }
+
+    /// Fetches the code if a new block was mined, and returns it if its hash changed
+    fn poll(&mut self) -> Result<Option<(U64, Bytes)>, ProviderError> {
+        let latest = self.provider.get_block_number()?;
+        if self.block.map_or(false, |block| latest <= block) {
+            return Ok(None)
+        }
+        let code = self.provider.get_code(self.address, Some(latest.into()))?;
+        let code_hash = H256(keccak256(&code));
+        self.block = Some(latest);
+        Ok(match self.code_hash.replace(code_hash) {
+            Some(previous) if previous != code_hash => Some((latest, code)),
+            _ => None,
+        })
+    }
+}
+
+impl<'a, P> Iterator for CodeWatcher<'a, P>
+where
+    P: JsonRpcClient,
+{
+    type Item = Result<(U64, Bytes), ProviderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.poll() {
+                Ok(Some(change)) => return Some(Ok(change)),
+                Ok(None) => thread::sleep(self.provider.get_interval()),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
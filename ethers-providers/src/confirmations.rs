@@ -0,0 +1,68 @@
+use super::{JsonRpcClient, Middleware, Provider, ProviderError};
+use ethers_core::types::TxHash;
+use std::thread;
+
+/// Iterator over the confirmation count of a transaction, see [`Provider::watch_transaction`].
+///
+/// Every call to `next` polls the node at the provider's interval until the transaction has been
+/// mined in a block it has not reported yet, and yields its confirmation count. The iterator ends
+/// once the target number of confirmations has been reached.
+pub struct ConfirmationWatcher<'a, P> {
+    provider: &'a Provider<P>,
+    tx_hash: TxHash,
+    target: u64,
+    confirmations: u64,
+}
+
+impl<'a, P> ConfirmationWatcher<'a, P>
+where
+    P: JsonRpcClient,
+{
+    pub fn new(provider: &'a Provider<P>, tx_hash: TxHash, target: u64) -> Self {
+        Self { provider, tx_hash, target, confirmations: 0 }
+    }
+
+    /// Returns the current number of confirmations without polling the node
+    pub fn confirmations(&self) -> u64 {
+        self.confirmations
+    }
+
+    /// Returns the current number of confirmations of the transaction, 0 if it is not mined yet
+    fn poll(&self) -> Result<u64, ProviderError> {
+        let mined_in = match self.provider.get_transaction_receipt(self.tx_hash)? {
+            Some(receipt) => receipt.block_number,
+            None => None,
+        };
+        Ok(match mined_in {
+            Some(mined_in) => {
+                let latest = self.provider.get_block_number()?;
+                (latest + 1).saturating_sub(mined_in).as_u64()
+            }
+            None => 0,
+        })
+    }
+}
+
+impl<'a, P> Iterator for ConfirmationWatcher<'a, P>
+where
+    P: JsonRpcClient,
+{
+    type Item = Result<u64, ProviderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.confirmations >= self.target {
+            return None
+        }
+
+        loop {
+            match self.poll() {
+                Ok(confirmations) if confirmations > self.confirmations => {
+                    self.confirmations = confirmations;
+                    return Some(Ok(confirmations))
+                }
+                Ok(_) => thread::sleep(self.provider.get_interval()),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
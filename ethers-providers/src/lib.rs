@@ -7,21 +7,55 @@ mod transports;
 pub use transports::*;
 
 mod provider;
-pub use provider::{is_local_endpoint, FilterKind, Provider, ProviderError};
+pub use provider::{
+    is_local_endpoint, FilterKind, NodeClient, NodeClientVersion, PreparedTx, Provider,
+    ProviderError, TransactionSerializer, TxStatus,
+};
 
 // ENS support
 pub mod ens;
 
 mod log_query;
-pub use log_query::{LogQuery, LogQueryError};
+pub use log_query::{LogPages, LogQuery, LogQueryError};
+
+mod code;
+pub use code::CodeWatcher;
+
+mod confirmations;
+pub use confirmations::ConfirmationWatcher;
+
+mod pending_logs;
+pub use pending_logs::PendingLogWatcher;
+
+mod storage;
+pub use storage::StorageWatcher;
+
+mod coalesce;
+
+mod rpc_error;
+pub use rpc_error::{DefaultErrorClassifier, ErrorClassifier, RpcErrorKind};
 
 pub mod call_raw;
 pub mod erc;
 
 use auto_impl::auto_impl;
-use ethers_core::types::transaction::{eip2718::TypedTransaction, eip2930::AccessListWithGasUsed};
+use ethers_core::{
+    abi::Abi,
+    types::transaction::{
+        eip2718::TypedTransaction,
+        eip2930::{AccessListReport, AccessListWithGasUsed},
+    },
+};
 use serde::{de::DeserializeOwned, Serialize};
-use std::{error::Error, fmt::Debug, pin::Pin};
+use std::{
+    error::Error,
+    fmt::Debug,
+    ops::RangeInclusive,
+    panic,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
 use url::Url;
 
 // feature-enabled support for dev-rpc methods
@@ -31,12 +65,6 @@ pub use provider::dev_rpc::DevRpcMiddleware;
 /// A simple gas escalation policy
 pub type EscalationPolicy = Box<dyn Fn(U256, usize) -> U256 + Send + Sync>;
 
-// Helper type alias
-#[cfg(target_arch = "wasm32")]
-pub(crate) type PinBoxFut<T> = Pin<T>;
-#[cfg(not(target_arch = "wasm32"))]
-pub(crate) type PinBoxFut<T> = Pin<T>;
-
 #[auto_impl(&, Box, Arc)]
 /// Trait which must be implemented by data transports to be used with the Ethereum
 /// JSON-RPC provider.
@@ -49,6 +77,35 @@ pub trait JsonRpcClient: Debug + Send + Sync {
     where
         T: Debug + Serialize + Send + Sync,
         R: DeserializeOwned;
+
+    /// Sends a request like [`request`](Self::request), rejecting responses larger than
+    /// `max_size` bytes before they are deserialized.
+    ///
+    /// Transports which can not determine the size of a response ignore the limit.
+    fn request_with_max_size<T, R>(
+        &self,
+        method: &str,
+        params: T,
+        max_size: Option<usize>,
+    ) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let _ = max_size;
+        self.request(method, params)
+    }
+
+    /// Sends a request to `method` with each of the `params`, returning the results in the same
+    /// order. Transports which support it send them as a single JSON-RPC batch, the others one
+    /// after the other.
+    fn request_batch<T, R>(&self, method: &str, params: Vec<T>) -> Result<Vec<R>, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        params.into_iter().map(|params| self.request(method, params)).collect()
+    }
 }
 
 use ethers_core::types::*;
@@ -76,6 +133,12 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().provider()
     }
 
+    /// Converts an error raised by the provider to this middleware's error, e.g. to reject invalid
+    /// input before any request is sent
+    fn convert_err(err: ProviderError) -> Self::Error {
+        FromErr::from(Self::Inner::convert_err(err))
+    }
+
     fn default_sender(&self) -> Option<Address> {
         self.inner().default_sender()
     }
@@ -84,6 +147,10 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().client_version().map_err(FromErr::from)
     }
 
+    fn web3_sha3(&self, data: Bytes) -> Result<H256, Self::Error> {
+        self.inner().web3_sha3(data).map_err(FromErr::from)
+    }
+
     /// Fill necessary details of a transaction for dispatch
     ///
     /// This function is defined on providers to behave as follows:
@@ -109,6 +176,31 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().fill_transaction(tx, block).map_err(FromErr::from)
     }
 
+    /// Returns a filled copy of `tx` along with a summary of what sending it entails, e.g. for a
+    /// confirmation screen. The transaction is filled with
+    /// [`fill_transaction`](Self::fill_transaction), and its nonce is set to the sender's pending
+    /// transaction count if missing. Nothing is sent.
+    fn prepare_transaction(&self, tx: &TypedTransaction) -> Result<PreparedTx, Self::Error> {
+        let mut tx = tx.clone();
+        self.fill_transaction(&mut tx, None)?;
+        if let (None, Some(&from)) = (tx.nonce(), tx.from()) {
+            let nonce = self.get_transaction_count(from, Some(BlockNumber::Pending.into()))?;
+            tx.set_nonce(nonce);
+        }
+        Ok(PreparedTx::new(tx))
+    }
+
+    /// Returns the most `tx` can cost its sender, i.e. its gas limit times its gas price (its max
+    /// fee per gas for EIP-1559 transactions) plus its value, e.g. to check whether the sender can
+    /// afford it. Missing gas and fees are filled with
+    /// [`fill_transaction`](Self::fill_transaction).
+    fn estimate_total_cost(&self, tx: &TypedTransaction) -> Result<U256, Self::Error> {
+        let mut tx = tx.clone();
+        self.fill_transaction(&mut tx, None)?;
+        let value = tx.value().copied().unwrap_or_default();
+        Ok(tx.max_cost().unwrap_or_default().saturating_add(value))
+    }
+
     fn get_block_number(&self) -> Result<U64, Self::Error> {
         self.inner().get_block_number().map_err(FromErr::from)
     }
@@ -140,6 +232,30 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_block(block_hash_or_number).map_err(FromErr::from)
     }
 
+    /// Returns the [`BlockSummary`] of a block, e.g. for lightweight polling. The block is
+    /// fetched with the hashes of its transactions only, which are counted.
+    fn get_block_summary<T: Into<BlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+    ) -> Result<Option<BlockSummary>, Self::Error> {
+        Ok(self.get_block(block_hash_or_number)?.map(|block| block.summary()))
+    }
+
+    /// Returns the latest block finalized by the consensus layer, which will not be reorged.
+    /// Returns `None` if the node does not know of a finalized block yet, e.g. before the Merge.
+    fn get_finalized_block(&self) -> Result<Option<Block<TxHash>>, Self::Error> {
+        self.get_block(BlockNumber::Finalized)
+    }
+
+    /// Returns the validator withdrawals included in the given block, or `None` if the block
+    /// does not exist or predates Shanghai
+    fn get_block_withdrawals<T: Into<BlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+    ) -> Result<Option<Vec<Withdrawal>>, Self::Error> {
+        Ok(self.get_block(block_hash_or_number)?.and_then(|block| block.withdrawals))
+    }
+
     fn get_block_with_txs<T: Into<BlockId> + Send + Sync>(
         &self,
         block_hash_or_number: T,
@@ -147,6 +263,30 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_block_with_txs(block_hash_or_number).map_err(FromErr::from)
     }
 
+    /// Returns the blocks in `range` in order, e.g. for backfilling an indexer. Up to
+    /// `max_concurrency` blocks are fetched at a time, and blocks which do not exist yet are
+    /// `None`.
+    fn get_blocks(
+        &self,
+        range: RangeInclusive<u64>,
+        max_concurrency: usize,
+    ) -> Result<Vec<Option<Block<TxHash>>>, Self::Error> {
+        fetch_concurrently(range, max_concurrency, |number| {
+            self.get_block(BlockNumber::Number(number.into()))
+        })
+    }
+
+    /// Returns the blocks in `range` with their transactions, like [`get_blocks`](Self::get_blocks)
+    fn get_blocks_with_txs(
+        &self,
+        range: RangeInclusive<u64>,
+        max_concurrency: usize,
+    ) -> Result<Vec<Option<Block<Transaction>>>, Self::Error> {
+        fetch_concurrently(range, max_concurrency, |number| {
+            self.get_block_with_txs(BlockNumber::Number(number.into()))
+        })
+    }
+
     fn get_uncle_count<T: Into<BlockId> + Send + Sync>(
         &self,
         block_hash_or_number: T,
@@ -170,6 +310,23 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_transaction_count(from, block).map_err(FromErr::from)
     }
 
+    /// Returns the nonce of the next transaction sent by `from`, counting its transactions in the
+    /// mempool
+    fn pending_nonce<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+    ) -> Result<U256, Self::Error> {
+        self.get_transaction_count(from, Some(BlockNumber::Pending.into()))
+    }
+
+    /// Returns the number of transactions sent by `from` which have been mined
+    fn confirmed_nonce<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+    ) -> Result<U256, Self::Error> {
+        self.get_transaction_count(from, Some(BlockNumber::Latest.into()))
+    }
+
     fn estimate_gas(
         &self,
         tx: &TypedTransaction,
@@ -178,10 +335,49 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().estimate_gas(tx, block).map_err(FromErr::from)
     }
 
+    /// Estimates the gas of `tx` and scales the estimate by `multiplier`, rounded up. This leaves
+    /// a safety margin for nodes that under-report the gas a transaction needs, e.g. a multiplier
+    /// of `1.2` adds a 20% buffer. Multipliers which are not finite or below `1.0` are rejected
+    /// with [`ProviderError::InvalidGasMultiplier`].
+    fn estimate_gas_buffered(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        multiplier: f64,
+    ) -> Result<U256, Self::Error> {
+        validate_gas_multiplier(multiplier).map_err(Self::convert_err)?;
+        let gas = self.estimate_gas(tx, block)?;
+        Ok(buffer_gas(gas, multiplier))
+    }
+
     fn call(&self, tx: &TypedTransaction, block: Option<BlockId>) -> Result<Bytes, Self::Error> {
         self.inner().call(tx, block).map_err(FromErr::from)
     }
 
+    /// Executes an `eth_call` like [`call`](crate::Middleware::call), additionally passing the
+    /// given [state override set](https://geth.ethereum.org/docs/rpc/ns-eth#3-object---state-override-set)
+    /// down the middleware stack to the provider.
+    ///
+    /// See the [`call_raw::spoof`] module for functions to construct state override parameters.
+    fn call_with_overrides(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        overrides: &call_raw::spoof::State,
+    ) -> Result<Bytes, Self::Error> {
+        self.inner().call_with_overrides(tx, block, overrides).map_err(FromErr::from)
+    }
+
+    /// Executes the read-only transaction like [`call`](crate::Middleware::call) and returns its
+    /// return data together with the gas it used, e.g. for simulations
+    fn call_with_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(Bytes, U256), Self::Error> {
+        self.inner().call_with_gas(tx, block).map_err(FromErr::from)
+    }
+
     fn syncing(&self) -> Result<SyncingStatus, Self::Error> {
         self.inner().syncing().map_err(FromErr::from)
     }
@@ -194,6 +390,26 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_net_version().map_err(FromErr::from)
     }
 
+    fn net_peer_count(&self) -> Result<U256, Self::Error> {
+        self.inner().net_peer_count().map_err(FromErr::from)
+    }
+
+    fn net_listening(&self) -> Result<bool, Self::Error> {
+        self.inner().net_listening().map_err(FromErr::from)
+    }
+
+    fn get_coinbase(&self) -> Result<Address, Self::Error> {
+        self.inner().get_coinbase().map_err(FromErr::from)
+    }
+
+    fn get_mining(&self) -> Result<bool, Self::Error> {
+        self.inner().get_mining().map_err(FromErr::from)
+    }
+
+    fn get_hashrate(&self) -> Result<U256, Self::Error> {
+        self.inner().get_hashrate().map_err(FromErr::from)
+    }
+
     fn get_balance<T: Into<NameOrAddress> + Send + Sync>(
         &self,
         from: T,
@@ -202,6 +418,17 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_balance(from, block).map_err(FromErr::from)
     }
 
+    /// Returns the balance of `from` at each of the given `blocks`, in the same order, e.g. for
+    /// charting a balance over time. The requests are sent as a single JSON-RPC batch if the
+    /// transport supports it, see [`JsonRpcClient::request_batch`].
+    fn get_balance_history<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        blocks: &[BlockNumber],
+    ) -> Result<Vec<U256>, Self::Error> {
+        self.inner().get_balance_history(from, blocks).map_err(FromErr::from)
+    }
+
     fn get_transaction<T: Send + Sync + Into<TxHash>>(
         &self,
         transaction_hash: T,
@@ -209,6 +436,15 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_transaction(transaction_hash).map_err(FromErr::from)
     }
 
+    /// Returns the signed network encoding of the transaction with `transaction_hash`, e.g. for
+    /// rebroadcasting it
+    fn get_raw_transaction<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<Bytes>, Self::Error> {
+        self.inner().get_raw_transaction(transaction_hash).map_err(FromErr::from)
+    }
+
     fn get_transaction_receipt<T: Send + Sync + Into<TxHash>>(
         &self,
         transaction_hash: T,
@@ -216,6 +452,54 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_transaction_receipt(transaction_hash).map_err(FromErr::from)
     }
 
+    /// Returns the logs emitted by the transaction, taken from its receipt, or an empty list if
+    /// the transaction is not mined yet. This avoids an `eth_getLogs` block range filter for the
+    /// logs of a single transaction.
+    fn get_transaction_logs<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Vec<Log>, Self::Error> {
+        let receipt = self.get_transaction_receipt(transaction_hash)?;
+        Ok(receipt.map(|receipt| receipt.logs).unwrap_or_default())
+    }
+
+    /// Returns whether the transaction is unknown, pending or mined, based on its receipt and, if
+    /// there is none yet, on whether the node knows the transaction
+    fn get_transaction_status<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<TxStatus, Self::Error> {
+        let hash = transaction_hash.into();
+        if let Some(block) = self.get_transaction_receipt(hash)?.and_then(|r| r.block_number) {
+            return Ok(TxStatus::Mined(block))
+        }
+        Ok(match self.get_transaction(hash)? {
+            Some(_) => TxStatus::Pending,
+            None => TxStatus::Unknown,
+        })
+    }
+
+    /// Returns the transaction along with its number of confirmations, i.e. the number of blocks
+    /// from the one including it up to the latest one, or 0 while it is pending. Returns `None`
+    /// if the node does not know the transaction.
+    fn get_transaction_with_confirmations<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<(Transaction, u64)>, Self::Error> {
+        let tx = match self.get_transaction(transaction_hash)? {
+            Some(tx) => tx,
+            None => return Ok(None),
+        };
+        let confirmations = match tx.block_number {
+            Some(mined_in) => {
+                let latest = self.get_block_number()?;
+                (latest + 1).saturating_sub(mined_in).as_u64()
+            }
+            None => 0,
+        };
+        Ok(Some((tx, confirmations)))
+    }
+
     fn get_block_receipts<T: Into<BlockNumber> + Send + Sync>(
         &self,
         block: T,
@@ -234,6 +518,18 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().estimate_eip1559_fees(estimator).map_err(FromErr::from)
     }
 
+    /// Projects the base fee of the block `blocks_ahead` blocks after the latest one from the
+    /// base fees and gas usage of the recent blocks, see [`FeeHistory::predict_base_fee`].
+    /// Returns `None` on chains without EIP-1559.
+    fn predict_base_fee(&self, blocks_ahead: u64) -> Result<Option<U256>, Self::Error> {
+        let history = self.fee_history(
+            ethers_core::utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+            BlockNumber::Latest,
+            &[],
+        )?;
+        Ok(history.predict_base_fee(blocks_ahead))
+    }
+
     fn get_accounts(&self) -> Result<Vec<Address>, Self::Error> {
         self.inner().get_accounts().map_err(FromErr::from)
     }
@@ -268,7 +564,27 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_logs(filter).map_err(FromErr::from)
     }
 
-    /// Returns a stream of logs are loaded in pages of given page size
+    /// Returns all the logs of the block with the given hash. Querying the logs by block hash
+    /// instead of a range of one block ensures they belong to that block even across reorgs.
+    fn get_block_logs(&self, block_hash: H256) -> Result<Vec<Log>, Self::Error> {
+        self.get_logs(&Filter::new().at_block_hash(block_hash))
+    }
+
+    /// Returns the logs emitted by `address` for the event with the given signature between the
+    /// `from` and `to` blocks. The signature is either canonical, e.g.
+    /// `Transfer(address,address,uint256)`, or human-readable, e.g.
+    /// `event Transfer(address indexed from, address indexed to, uint256 value)`.
+    fn get_logs_for_event<T: Into<BlockNumber> + Send + Sync>(
+        &self,
+        address: Address,
+        event_signature: &str,
+        from: T,
+        to: T,
+    ) -> Result<Vec<Log>, Self::Error> {
+        self.inner().get_logs_for_event(address, event_signature, from, to).map_err(FromErr::from)
+    }
+
+    /// Returns an iterator over logs which are loaded in pages of given page size
     fn get_logs_paginated<'a>(
         &'a self,
         filter: &Filter,
@@ -293,6 +609,10 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_filter_changes(id).map_err(FromErr::from)
     }
 
+    fn get_filter_logs<T: Into<U256> + Send + Sync>(&self, id: T) -> Result<Vec<Log>, Self::Error> {
+        self.inner().get_filter_logs(id).map_err(FromErr::from)
+    }
+
     fn get_code<T: Into<NameOrAddress> + Send + Sync>(
         &self,
         at: T,
@@ -301,6 +621,45 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_code(at, block).map_err(FromErr::from)
     }
 
+    /// Polls for the code at `at` at the provider's interval until it is not empty, e.g. to
+    /// confirm that a contract deployment landed at its CREATE address, and returns it.
+    ///
+    /// Returns `Ok(None)` if there is still no code after `timeout`. The node is polled at least
+    /// once.
+    fn wait_for_code(&self, at: Address, timeout: Duration) -> Result<Option<Bytes>, Self::Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let code = self.get_code(at, None)?;
+            if !code.as_ref().is_empty() {
+                return Ok(Some(code))
+            }
+            if Instant::now() >= deadline {
+                return Ok(None)
+            }
+            thread::sleep(self.provider().get_interval());
+        }
+    }
+
+    /// Returns the size in bytes of the code deployed at `at`, which is zero for externally owned
+    /// accounts
+    fn code_size<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        at: T,
+        block: Option<BlockId>,
+    ) -> Result<usize, Self::Error> {
+        self.get_code(at, block).map(|code| code.len())
+    }
+
+    /// Returns `true` if there is code deployed at `at`, i.e. if it is a contract rather than an
+    /// externally owned account
+    fn is_contract<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        at: T,
+        block: Option<BlockId>,
+    ) -> Result<bool, Self::Error> {
+        self.code_size(at, block).map(|size| size > 0)
+    }
+
     fn get_storage_at<T: Into<NameOrAddress> + Send + Sync>(
         &self,
         from: T,
@@ -310,6 +669,23 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_storage_at(from, location, block).map_err(FromErr::from)
     }
 
+    /// Returns the values of the storage `slots` of `from`, in the same order, fetched with a
+    /// single `eth_getProof` request instead of one `eth_getStorageAt` request per slot
+    fn get_storage_at_many<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        slots: Vec<H256>,
+        block: Option<BlockId>,
+    ) -> Result<Vec<H256>, Self::Error> {
+        let proof = self.get_proof(from, slots, block)?;
+        // the storage proofs are returned in the order of the requested keys
+        Ok(proof
+            .storage_proof
+            .into_iter()
+            .map(|slot| H256::from(<[u8; 32]>::from(slot.value)))
+            .collect())
+    }
+
     fn get_proof<T: Into<NameOrAddress> + Send + Sync>(
         &self,
         from: T,
@@ -319,12 +695,43 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().get_proof(from, locations, block).map_err(FromErr::from)
     }
 
+    /// Returns the proof of `from` and its storage `locations` at the block `block_hash`, pinned
+    /// by hash (EIP-1898) so it cannot be served from a block reorged in between, together with
+    /// the block's state root to verify it against. Returns `None` if the block is unknown.
+    fn get_proof_at_hash<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        locations: Vec<H256>,
+        block_hash: H256,
+    ) -> Result<Option<(EIP1186ProofResponse, H256)>, Self::Error> {
+        let state_root = match self.get_block(block_hash)? {
+            Some(block) => block.state_root,
+            None => return Ok(None),
+        };
+        let proof = self.get_proof(from, locations, Some(BlockId::Hash(block_hash)))?;
+        Ok(Some((proof, state_root)))
+    }
+
+    fn get_account<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        account: T,
+        block: Option<BlockId>,
+    ) -> Result<Account, Self::Error> {
+        self.inner().get_account(account, block).map_err(FromErr::from)
+    }
+
     // Mempool inspection for Geth's API
 
     fn txpool_content(&self) -> Result<TxpoolContent, Self::Error> {
         self.inner().txpool_content().map_err(FromErr::from)
     }
 
+    /// Returns the transactions sent by `from` which are in the txpool, both the pending ones and
+    /// the ones queued behind a nonce gap, sorted by nonce
+    fn pending_transactions_from(&self, from: Address) -> Result<Vec<Transaction>, Self::Error> {
+        self.inner().pending_transactions_from(from).map_err(FromErr::from)
+    }
+
     fn txpool_inspect(&self) -> Result<TxpoolInspect, Self::Error> {
         self.inner().txpool_inspect().map_err(FromErr::from)
     }
@@ -344,6 +751,26 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().debug_trace_transaction(tx_hash, trace_options).map_err(FromErr::from)
     }
 
+    /// Replays all transactions of the block with the given number, returning one trace per
+    /// transaction configured with passed options
+    fn debug_trace_block_by_number(
+        &self,
+        block: BlockNumber,
+        trace_options: GethDebugTracingOptions,
+    ) -> Result<Vec<GethTrace>, Self::Error> {
+        self.inner().debug_trace_block_by_number(block, trace_options).map_err(FromErr::from)
+    }
+
+    /// Replays all transactions of the block with the given hash, returning one trace per
+    /// transaction configured with passed options
+    fn debug_trace_block_by_hash(
+        &self,
+        block_hash: H256,
+        trace_options: GethDebugTracingOptions,
+    ) -> Result<Vec<GethTrace>, Self::Error> {
+        self.inner().debug_trace_block_by_hash(block_hash, trace_options).map_err(FromErr::from)
+    }
+
     // Parity `trace` support
 
     /// Executes the given call and returns a number of possible traces for it
@@ -415,6 +842,17 @@ pub trait Middleware: Sync + Send + Debug {
         self.inner().trace_transaction(hash).map_err(FromErr::from)
     }
 
+    /// Returns all traces of a given transaction, with the input of each call decoded into the
+    /// called function and its arguments where it matches a function of `abi`
+    fn trace_transaction_decoded(
+        &self,
+        hash: H256,
+        abi: &Abi,
+    ) -> Result<Vec<DecodedTrace>, Self::Error> {
+        let traces = self.trace_transaction(hash)?;
+        Ok(traces.into_iter().map(|trace| DecodedTrace::new(trace, abi)).collect())
+    }
+
     // Parity namespace
 
     /// Returns all receipts for that block. Must be done on a parity node.
@@ -441,6 +879,95 @@ pub trait Middleware: Sync + Send + Debug {
     ) -> Result<AccessListWithGasUsed, Self::Error> {
         self.inner().create_access_list(tx, block).map_err(FromErr::from)
     }
+
+    /// Creates an access list for `tx` like [`create_access_list`](Self::create_access_list) and
+    /// estimates the gas each of its entries saves. The sender and recipient of `tx` are warm
+    /// anyway, so listing them only adds to the cost.
+    fn create_access_list_report(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<AccessListReport, Self::Error> {
+        let sender = tx.from().copied().or_else(|| self.default_sender());
+        let mut warm: Vec<_> = sender.into_iter().collect();
+        match tx.to() {
+            Some(NameOrAddress::Name(ens_name)) => warm.push(self.resolve_name(ens_name)?),
+            Some(NameOrAddress::Address(addr)) => warm.push(*addr),
+            None => {}
+        }
+        Ok(self.create_access_list(tx, block)?.access_list.report(&warm))
+    }
+}
+
+/// Rejects a `multiplier` which can not scale a gas estimate, see
+/// [`Middleware::estimate_gas_buffered`]
+pub(crate) fn validate_gas_multiplier(multiplier: f64) -> Result<(), ProviderError> {
+    if multiplier.is_finite() && multiplier >= 1.0 {
+        Ok(())
+    } else {
+        Err(ProviderError::InvalidGasMultiplier(multiplier))
+    }
+}
+
+/// Calls `fetch` for the numbers in `range` on up to `max_concurrency` threads, and returns the
+/// results in order. No more numbers are fetched once a call failed.
+pub(crate) fn fetch_concurrently<T, E, F>(
+    range: RangeInclusive<u64>,
+    max_concurrency: usize,
+    fetch: F,
+) -> Result<Vec<T>, E>
+where
+    T: Send,
+    E: Send,
+    F: Fn(u64) -> Result<T, E> + Sync,
+{
+    let numbers: Vec<u64> = range.collect();
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let worker = || {
+        let mut fetched = Vec::new();
+        while !failed.load(Ordering::SeqCst) {
+            let index = next.fetch_add(1, Ordering::SeqCst);
+            let number = match numbers.get(index) {
+                Some(number) => *number,
+                None => break,
+            };
+            match fetch(number) {
+                Ok(item) => fetched.push((index, item)),
+                Err(err) => {
+                    failed.store(true, Ordering::SeqCst);
+                    return Err(err)
+                }
+            }
+        }
+        Ok(fetched)
+    };
+
+    let workers = max_concurrency.clamp(1, numbers.len().max(1));
+    let results: Vec<_> = crossbeam_utils::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers).map(|_| scope.spawn(|_| worker())).collect();
+        handles.into_iter().map(|handle| handle.join()).collect()
+    })
+    .unwrap_or_else(|err| panic::resume_unwind(err));
+    let mut fetched = Vec::with_capacity(numbers.len());
+    for result in results {
+        fetched.extend(result.unwrap_or_else(|err| panic::resume_unwind(err))?);
+    }
+    fetched.sort_by_key(|(index, _)| *index);
+    Ok(fetched.into_iter().map(|(_, item)| item).collect())
+}
+
+/// Scales `gas` by a valid `multiplier`, rounded up
+pub(crate) fn buffer_gas(gas: U256, multiplier: f64) -> U256 {
+    // scale in fixed point to avoid converting the estimate to a float
+    const PRECISION: u64 = 1_000_000;
+    let multiplier = U256::from((multiplier * PRECISION as f64).round() as u64);
+    let (buffered, rem) = (gas * multiplier).div_mod(PRECISION.into());
+    if rem.is_zero() {
+        buffered
+    } else {
+        buffered + 1
+    }
 }
 
 #[cfg(feature = "celo")]
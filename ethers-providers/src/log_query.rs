@@ -1,10 +1,6 @@
-use super::{JsonRpcClient, Middleware, PinBoxFut, Provider, ProviderError};
+use super::{JsonRpcClient, Middleware, Provider, ProviderError};
 use ethers_core::types::{Filter, Log, U64};
-use std::{
-    collections::VecDeque,
-    pin::Pin,
-    task::{Context, Poll},
-};
+use std::collections::VecDeque;
 use thiserror::Error;
 
 pub struct LogQuery<'a, P> {
@@ -15,13 +11,13 @@ pub struct LogQuery<'a, P> {
     current_logs: VecDeque<Log>,
     last_block: Option<U64>,
     state: LogQueryState,
+    on_progress: Option<Box<dyn FnMut(U64, U64) + 'a>>,
 }
 
 enum LogQueryState {
     Initial,
-    LoadLastBlock(PinBoxFut<U64>),
-    LoadLogs(PinBoxFut<Vec<Log>>),
     Consume,
+    Done,
 }
 
 impl<'a, P> LogQuery<'a, P>
@@ -37,6 +33,7 @@ where
             current_logs: VecDeque::new(),
             last_block: None,
             state: LogQueryState::Initial,
+            on_progress: None,
         }
     }
 
@@ -45,14 +42,135 @@ where
         self.page_size = page_size;
         self
     }
+
+    /// Sets a callback which is invoked with the current [`progress`](Self::progress) every time
+    /// a page of logs has been loaded
+    pub fn with_progress_callback<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(U64, U64) + 'a,
+    {
+        self.on_progress = Some(Box::new(f));
+        self
+    }
+
+    /// Returns an iterator yielding the logs page by page instead of one by one, e.g. to
+    /// checkpoint the scanned blocks after each page. Like the logs, each page is only loaded
+    /// once requested.
+    pub fn pages(self) -> LogPages<'a, P> {
+        LogPages(self)
+    }
+
+    /// Returns the number of blocks scanned so far and the total number of blocks to scan.
+    ///
+    /// The total is only known once the query has started and the latest block has been fetched,
+    /// until then (and for filters which can not be paginated) this returns `(0, 0)`.
+    pub fn progress(&self) -> (U64, U64) {
+        match (self.filter.get_from_block(), self.from_block, self.last_block) {
+            (Some(start), Some(next), Some(last)) if last >= start => {
+                let scanned = next.min(last + 1) - start;
+                (scanned, last - start + 1)
+            }
+            _ => (U64::zero(), U64::zero()),
+        }
+    }
+
+    /// Loads the page of logs starting at `from_block` and advances to the next page
+    fn load_page(&mut self) -> Result<Vec<Log>, LogQueryError<ProviderError>> {
+        // this is okay because we only load pages when the filter is paginatable, i.e. the from
+        // block is set
+        let from_block = self.from_block.unwrap();
+        let to_block = from_block + self.page_size;
+        let filter = self.filter.clone().from_block(from_block).to_block(to_block);
+
+        let logs = self.provider.get_logs(&filter).map_err(LogQueryError::LoadLogsError)?;
+        self.from_block = Some(to_block + 1);
+
+        let (scanned, total) = self.progress();
+        if let Some(on_progress) = self.on_progress.as_mut() {
+            on_progress(scanned, total);
+        }
+        Ok(logs)
+    }
+
+    /// Loads the next page of logs, returns `None` once all pages have been loaded
+    fn next_page(&mut self) -> Option<Result<Vec<Log>, LogQueryError<ProviderError>>> {
+        match self.state {
+            LogQueryState::Initial => {
+                if !self.filter.is_paginatable() {
+                    // if not paginatable, load all logs at once
+                    let logs = match self.provider.get_logs(&self.filter) {
+                        Ok(logs) => logs,
+                        Err(err) => return Some(Err(LogQueryError::LoadLogsError(err))),
+                    };
+                    self.state = LogQueryState::Done;
+                    return Some(Ok(logs))
+                }
+                // if paginatable, load the last block before the first page
+                match self.provider.get_block_number() {
+                    Ok(last_block) => self.last_block = Some(last_block),
+                    Err(err) => return Some(Err(LogQueryError::LoadLastBlockError(err))),
+                }
+                self.state = LogQueryState::Consume;
+            }
+            LogQueryState::Consume => {}
+            LogQueryState::Done => return None,
+        }
+
+        // no more pages to load
+        // can safely assume these will always be set in this state
+        if self.from_block.unwrap() > self.last_block.unwrap() {
+            self.state = LogQueryState::Done;
+            return None
+        }
+        Some(self.load_page())
+    }
 }
 
-macro_rules! rewake_with_new_state {
-    ($ctx:ident, $this:ident, $new_state:expr) => {
-        $this.state = $new_state;
-        $ctx.waker().wake_by_ref();
-        return Poll::Pending
-    };
+impl<'a, P> Iterator for LogQuery<'a, P>
+where
+    P: JsonRpcClient,
+{
+    type Item = Result<Log, LogQueryError<ProviderError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(log) = self.current_logs.pop_front() {
+                return Some(Ok(log))
+            }
+            match self.next_page()? {
+                Ok(logs) => self.current_logs = VecDeque::from(logs),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// Iterator over the pages of logs of a [`LogQuery`], see [`LogQuery::pages`]
+pub struct LogPages<'a, P>(LogQuery<'a, P>);
+
+impl<'a, P> LogPages<'a, P>
+where
+    P: JsonRpcClient,
+{
+    /// Returns the progress of the query, see [`LogQuery::progress`]
+    pub fn progress(&self) -> (U64, U64) {
+        self.0.progress()
+    }
+}
+
+impl<'a, P> Iterator for LogPages<'a, P>
+where
+    P: JsonRpcClient,
+{
+    type Item = Result<Vec<Log>, LogQueryError<ProviderError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // the rest of a page already loaded by the query
+        if !self.0.current_logs.is_empty() {
+            return Some(Ok(self.0.current_logs.drain(..).collect()))
+        }
+        self.0.next_page()
+    }
 }
 
 #[derive(Error, Debug)]
@@ -62,3 +180,78 @@ pub enum LogQueryError<E> {
     #[error(transparent)]
     LoadLogsError(E),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::BlockNumber;
+
+    #[test]
+    fn progress_advances_to_completion() {
+        let (provider, mock) = Provider::mocked();
+
+        mock.push::<Vec<Log>, _>(vec![Log::default()]).unwrap();
+        mock.push::<Vec<Log>, _>(vec![]).unwrap();
+        mock.push::<Vec<Log>, _>(vec![Log::default(), Log::default()]).unwrap();
+        mock.push(U64::from(25)).unwrap();
+
+        let filter = Filter::new().from_block(0);
+        let mut reports = Vec::new();
+        let mut query = LogQuery::new(&provider, &filter)
+            .with_page_size(9)
+            .with_progress_callback(|scanned, total| reports.push((scanned, total)));
+        assert_eq!(query.progress(), (0.into(), 0.into()));
+
+        let logs = query.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(query.progress(), (26.into(), 26.into()));
+        drop(query);
+
+        assert_eq!(
+            reports,
+            vec![(10.into(), 26.into()), (20.into(), 26.into()), (26.into(), 26.into())]
+        );
+
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        for (from, to) in [(0u64, 9u64), (10, 19), (20, 29)] {
+            let filter = filter.clone().from_block(from).to_block(BlockNumber::Number(to.into()));
+            mock.assert_request("eth_getLogs", [filter]).unwrap();
+        }
+    }
+    #[test]
+    fn loads_pages_on_demand() {
+        let (provider, mock) = Provider::mocked();
+        let log = |block: u64| Log { block_number: Some(block.into()), ..Default::default() };
+
+        mock.push::<Vec<Log>, _>(vec![log(25)]).unwrap();
+        mock.push::<Vec<Log>, _>(vec![]).unwrap();
+        mock.push::<Vec<Log>, _>(vec![log(1), log(2)]).unwrap();
+        mock.push(U64::from(25)).unwrap();
+
+        let filter = Filter::new().from_block(0);
+        let page = |from: u64, to: u64| {
+            filter.clone().from_block(from).to_block(BlockNumber::Number(to.into()))
+        };
+        let mut query = LogQuery::new(&provider, &filter).with_page_size(9);
+
+        // only the first page is loaded to yield its logs
+        assert_eq!(query.next().unwrap().unwrap(), log(1));
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        mock.assert_request("eth_getLogs", [page(0, 9)]).unwrap();
+        assert!(mock.assert_request("eth_getLogs", [page(10, 19)]).is_err());
+        assert_eq!(query.next().unwrap().unwrap(), log(2));
+        assert!(mock.assert_request("eth_getLogs", [page(10, 19)]).is_err());
+
+        // the remaining pages are yielded as a whole, including the empty one
+        let mut pages = query.pages();
+        assert_eq!(pages.next().unwrap().unwrap(), vec![]);
+        assert_eq!(pages.progress(), (20.into(), 26.into()));
+        mock.assert_request("eth_getLogs", [page(10, 19)]).unwrap();
+        assert!(mock.assert_request("eth_getLogs", [page(20, 29)]).is_err());
+
+        assert_eq!(pages.next().unwrap().unwrap(), vec![log(25)]);
+        mock.assert_request("eth_getLogs", [page(20, 29)]).unwrap();
+        assert!(pages.next().is_none());
+        assert!(mock.assert_request("eth_getLogs", [page(30, 39)]).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use super::{JsonRpcClient, Middleware, Provider, ProviderError};
+use ethers_core::types::{BlockNumber, Filter, Log, H256, U256};
+use std::{collections::VecDeque, thread};
+
+/// Iterator over the logs of the pending block, see [`Provider::watch_pending_logs`].
+///
+/// Every call to `next` polls `eth_getLogs` for the pending block at the provider's interval until
+/// a log shows up which was not returned by the previous poll. Logs are identified by their
+/// transaction hash and log index, so a log which leaves the pending block and reappears later is
+/// yielded again. The iterator never ends on its own.
+pub struct PendingLogWatcher<'a, P> {
+    provider: &'a Provider<P>,
+    filter: Filter,
+    seen: Vec<(Option<H256>, Option<U256>)>,
+    logs: VecDeque<Log>,
+}
+
+impl<'a, P> PendingLogWatcher<'a, P>
+where
+    P: JsonRpcClient,
+{
+    pub fn new(provider: &'a Provider<P>, filter: &Filter) -> Self {
+        let filter = filter.clone().from_block(BlockNumber::Pending).to_block(BlockNumber::Pending);
+        Self { provider, filter, seen: Vec::new(), logs: VecDeque::new() }
+    }
+
+    /// Fetches the logs of the pending block and queues the ones not seen in the previous poll
+    fn poll(&mut self) -> Result<(), ProviderError> {
+        let logs = self.provider.get_logs(&self.filter)?;
+        let seen = logs.iter().map(|log| (log.transaction_hash, log.log_index)).collect();
+        let previous = std::mem::replace(&mut self.seen, seen);
+        self.logs.extend(
+            logs.into_iter()
+                .filter(|log| !previous.contains(&(log.transaction_hash, log.log_index))),
+        );
+        Ok(())
+    }
+}
+
+impl<'a, P> Iterator for PendingLogWatcher<'a, P>
+where
+    P: JsonRpcClient,
+{
+    type Item = Result<Log, ProviderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(log) = self.logs.pop_front() {
+                return Some(Ok(log))
+            }
+            if let Err(err) = self.poll() {
+                return Some(Err(err))
+            }
+            if self.logs.is_empty() {
+                thread::sleep(self.provider.get_interval());
+            }
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use crate::{
-    call_raw::CallBuilder, ens, erc, FromErr, Http as HttpProvider, JsonRpcClient, LogQuery,
-    MockProvider, SyncingStatus,
+    call_raw::{self, CallBuilder, RawCall},
+    coalesce::RequestCoalescer,
+    ens, erc, is_retryable,
+    rpc_error::ClassifiedError,
+    CodeWatcher, ConfirmationWatcher, DefaultErrorClassifier, ErrorClassifier, FromErr,
+    Http as HttpProvider, HttpClientError, JsonRpcClient, JsonRpcError, LogQuery, MockError,
+    MockProvider, PendingLogWatcher, RecordingError, RpcErrorKind, StorageWatcher, SyncingStatus,
 };
 
 #[cfg(feature = "celo")]
@@ -8,28 +13,53 @@ use crate::CeloMiddleware;
 use crate::Middleware;
 
 use ethers_core::{
-    abi::{self, Detokenize, ParamType},
+    abi::{self, Detokenize, Function, ParamType, Token},
     types::{
         transaction::{eip2718::TypedTransaction, eip2930::AccessListWithGasUsed},
-        Address, Block, BlockId, BlockNumber, BlockTrace, Bytes, EIP1186ProofResponse, FeeHistory,
-        Filter, FilterBlockOption, GethDebugTracingOptions, GethTrace, Log, NameOrAddress,
-        Selector, Signature, Trace, TraceFilter, TraceType, Transaction, TransactionReceipt,
-        TransactionRequest, TxHash, TxpoolContent, TxpoolInspect, TxpoolStatus, H256, U256, U64,
+        Account, Address, Block, BlockId, BlockNumber, BlockTrace, Bytes, EIP1186ProofResponse,
+        Eip4844TransactionRequest, FeeHistory, Filter, FilterBlockOption, GethDebugTracingOptions,
+        GethTrace, Log, NameOrAddress, Selector, Signature, Trace, TraceFilter, TraceType,
+        Transaction, TransactionReceipt, TransactionRequest, TxHash, TxpoolContent, TxpoolInspect,
+        TxpoolStatus, ValueOrArray, H256, U256, U64,
     },
     utils,
 };
 use hex::FromHex;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use thiserror::Error;
 use url::{ParseError, Url};
 
 use ethers_core::types::Chain;
 use std::{
-    collections::VecDeque, convert::TryFrom, fmt::Debug, str::FromStr, sync::Arc, time::Duration,
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    convert::TryFrom,
+    fmt::{self, Debug},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
 };
 use tracing::trace;
 
-#[derive(Copy, Clone, Debug)]
+/// The default polling interval for filters and pending transactions
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(7000);
+
+/// The fraction of the chain's block time used as polling interval by
+/// [`Provider::detect_interval`]
+const BLOCK_TIME_POLL_FRACTION: u32 = 4;
+
+/// The number of blocks the block time is averaged over by [`Provider::detect_interval`], as the
+/// timestamps of chains producing several blocks per second only differ every few blocks
+const BLOCK_TIME_SAMPLE_SIZE: u64 = 10;
+
+/// The shortest polling interval set by [`Provider::detect_interval`]
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The delay before the first retry of a failed ENS resolver call, doubled on every retry
+const ENS_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum NodeClient {
     Geth,
     Erigon,
@@ -53,12 +83,84 @@ impl FromStr for NodeClient {
     }
 }
 
+/// The node client family together with its version, platform and implementation language, as
+/// reported by `web3_clientVersion`, e.g. `Geth/v1.13.5-stable/linux-amd64/go1.21`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeClientVersion {
+    /// The node client family
+    pub client: NodeClient,
+    /// The client's version
+    pub version: semver::Version,
+    /// The platform the client runs on, e.g. `linux-amd64`. Empty if not reported
+    pub os: String,
+    /// The language or runtime the client was built with, e.g. `go1.21`. Empty if not reported
+    pub lang: String,
+}
+
+impl FromStr for NodeClientVersion {
+    type Err = ProviderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let client = s.parse::<NodeClient>()?;
+
+        // some clients allow a custom node name before the version, e.g. `Geth/my-node/v1.10.0`,
+        // so the version is the first component that parses as one
+        let mut parts = s.split('/').skip(1);
+        let version = parts
+            .by_ref()
+            .find_map(|part| semver::Version::parse(part.trim_start_matches('v')).ok())
+            .ok_or_else(|| ProviderError::InvalidClientVersion(s.to_string()))?;
+        let os = parts.next().unwrap_or_default().to_string();
+        let lang = parts.next().unwrap_or_default().to_string();
+
+        Ok(NodeClientVersion { client, version, os, lang })
+    }
+}
+
+/// Hook for encoding transactions of chain-specific types, e.g. Optimism deposit transactions,
+/// which the [`TypedTransaction`] encodings do not cover. Registered with
+/// [`Provider::with_tx_serializer`], it is consulted for every transaction the provider sends to
+/// the node, so downstream crates can support new transaction types without forking.
+pub trait TransactionSerializer: Debug + Send + Sync {
+    /// Returns the JSON-RPC representation of `tx` used by calls like `eth_call`,
+    /// `eth_estimateGas` and `trace_call`, or `None` to serialize it as usual
+    fn serialize_tx(&self, tx: &TypedTransaction) -> Option<serde_json::Value>;
+
+    /// Returns the signed network encoding of `tx` for `eth_sendRawTransaction`, or `None` to
+    /// encode it with [`TypedTransaction::rlp_network`]
+    fn rlp_signed(&self, _tx: &TypedTransaction, _signature: &Signature) -> Option<Bytes> {
+        None
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Provider<P> {
     inner: P,
     ens: Option<Address>,
     interval: Option<Duration>,
     from: Option<Address>,
+    /// Maximum size in bytes of `eth_call` and `eth_getLogs` responses
+    max_response_size: Option<usize>,
+    /// Whether `get_block_receipts` may fall back to fetching receipts one by one
+    block_receipts_fallback: bool,
+    /// Whether `fill_transaction` populates empty EIP-2930 access lists
+    fill_access_list: bool,
+    /// Block used by reads which are not given a block, `Latest` if unset
+    default_block: Option<BlockId>,
+    /// Number of times ENS resolver calls are retried on transport errors
+    ens_retries: u32,
+    /// Whether ENS names are rejected instead of resolved
+    ens_disabled: bool,
+    /// How long a fetched block number is reused by `get_block_number`, not cached if unset
+    block_number_ttl: Option<Duration>,
+    /// The last block number fetched, along with when it was fetched
+    block_number_cache: Arc<Mutex<Option<(Instant, U64)>>>,
+    /// Custom encoding of transactions, the `TypedTransaction` encodings if unset
+    tx_serializer: Option<Arc<dyn TransactionSerializer>>,
+    /// Classifier of the node's errors, the `DefaultErrorClassifier` if unset
+    error_classifier: Option<Arc<dyn ErrorClassifier>>,
+    /// Shares responses among identical concurrent requests, disabled if unset
+    coalescer: Option<Arc<RequestCoalescer>>,
     /// Node client hasn't been checked yet = `None`
     /// Unsupported node client = `Some(None)`
     /// Supported node client = `Some(Some(NodeClient))`
@@ -110,8 +212,118 @@ pub enum ProviderError {
     #[error("unsupported node client")]
     UnsupportedNodeClient,
 
+    #[error("invalid client version: {0}")]
+    InvalidClientVersion(String),
+
     #[error("Attempted to sign a transaction with no available signer. Hint: did you mean to use a SignerMiddleware?")]
     SignerUnavailable,
+
+    /// The response exceeded the configured maximum response size
+    #[error("response exceeds the maximum size of {0} bytes")]
+    ResponseTooLarge(usize),
+
+    /// The response could not be deserialized into the expected type
+    #[error("failed to deserialize the response to `{method}`: {err}. Response: {raw}")]
+    Deserialization { method: String, err: serde_json::Error, raw: serde_json::Value },
+
+    /// The operation did not complete before its deadline
+    #[error("timed out")]
+    Timeout,
+
+    /// The call reverted, with the revert data returned by the node
+    #[error("execution reverted: {0}")]
+    Revert(Bytes),
+
+    /// The gas estimate multiplier is not a finite number of at least 1
+    #[error("invalid gas multiplier: {0}")]
+    InvalidGasMultiplier(f64),
+}
+
+impl ProviderError {
+    /// Returns the JSON-RPC error returned by the node, if this error originates from one
+    pub fn as_error_response(&self) -> Option<&JsonRpcError> {
+        let mut err = match self {
+            ProviderError::JsonRpcClientError(err) => err,
+            _ => return None,
+        };
+        if let Some(classified) = err.downcast_ref::<ClassifiedError>() {
+            err = &classified.source;
+        }
+        if let Some(HttpClientError::JsonRpcError(err)) = err.downcast_ref() {
+            return Some(err)
+        }
+        if let Some(MockError::JsonRpcError(err)) = err.downcast_ref() {
+            return Some(err)
+        }
+        if let Some(RecordingError::JsonRpcError(err)) = err.downcast_ref() {
+            return Some(err)
+        }
+        None
+    }
+
+    /// Returns the error as a [`ProviderError::Revert`] if the node returned revert data with it
+    fn into_revert(self) -> Self {
+        match self.as_error_response().and_then(JsonRpcError::as_revert_data) {
+            Some(data) => ProviderError::Revert(data),
+            None => self,
+        }
+    }
+
+    /// Returns the reason of a [`ProviderError::Revert`] raised with `revert("reason")` or
+    /// `require(cond, "reason")`, i.e. revert data encoding `Error(string)`
+    pub fn revert_reason(&self) -> Option<String> {
+        let data = match self {
+            ProviderError::Revert(data) => data,
+            _ => return None,
+        };
+        // `Error(string)`
+        let data = data.strip_prefix(&[0x08, 0xc3, 0x79, 0xa0][..])?;
+        abi::decode(&[ParamType::String], data).ok()?.pop()?.into_string()
+    }
+
+    /// Returns the normalized cause of the error, e.g. to retry a transaction with a new nonce
+    /// regardless of how the node phrases `nonce too low`. The JSON-RPC errors returned by the node
+    /// are classified by the classifier registered with [`Provider::with_error_classifier`], or by
+    /// the [`DefaultErrorClassifier`] if there is none.
+    pub fn kind(&self) -> RpcErrorKind {
+        if let ProviderError::JsonRpcClientError(err) = self {
+            if let Some(classified) = err.downcast_ref::<ClassifiedError>() {
+                return classified.kind
+            }
+        }
+        self.kind_with(&DefaultErrorClassifier)
+    }
+
+    /// Returns the normalized cause of the error, classifying JSON-RPC errors with `classifier`
+    pub fn kind_with(&self, classifier: &dyn ErrorClassifier) -> RpcErrorKind {
+        if let ProviderError::HTTPError(err) = self {
+            if err.status().map(|status| status.as_u16()) == Some(429) {
+                return RpcErrorKind::RateLimited
+            }
+        }
+        self.as_error_response().map(|err| classifier.classify(err)).unwrap_or(RpcErrorKind::Other)
+    }
+
+    /// Returns `true` if the node rejected the request because it does not support the method
+    pub fn is_method_not_found(&self) -> bool {
+        // <https://www.jsonrpc.org/specification#error_object>
+        self.as_error_response().map(|err| err.code == -32601).unwrap_or_default()
+    }
+}
+
+/// A single transaction's trace as returned by the `debug_traceBlock*` RPCs
+#[derive(Debug, Deserialize, Serialize)]
+struct BlockTraceResult {
+    result: GethTrace,
+}
+
+/// The response of `txpool_content`, whose transactions are decoded in full instead of as a
+/// [`TxpoolTransaction`](ethers_core::types::TxpoolTransaction), which omits the signature and
+/// fee fields
+#[derive(Debug, Deserialize, Serialize)]
+struct TxpoolTransactions {
+    pending: BTreeMap<Address, BTreeMap<String, Transaction>>,
+    queued: BTreeMap<Address, BTreeMap<String, Transaction>>,
 }
 
 /// Types of filters supported by the JSON-RPC.
@@ -124,11 +336,124 @@ pub enum FilterKind<'a> {
     NewBlocks,
 }
 
+/// The inclusion status of a transaction, see [`Middleware::get_transaction_status`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxStatus {
+    /// The node does not know the transaction
+    Unknown,
+    /// The transaction is in the mempool, but not yet included in a block
+    Pending,
+    /// The transaction was included in the block with the given number
+    Mined(U64),
+}
+
+/// A filled transaction which has not been sent yet, along with a summary of its fields, see
+/// [`Middleware::prepare_transaction`]. The summary is rendered by its `Display` implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedTx {
+    /// The filled transaction
+    pub tx: TypedTransaction,
+    /// The sender, as supplied by the caller or filled in with the default sender, `None` if
+    /// neither is known
+    pub from: Option<Address>,
+    /// The recipient, `None` for contract deployments
+    pub to: Option<Address>,
+    /// The value sent, as supplied by the caller, zero if unset
+    pub value: U256,
+    /// The nonce, as supplied by the caller or filled in with the sender's pending transaction
+    /// count, `None` if the sender is unknown
+    pub nonce: Option<U256>,
+    /// The gas limit, as supplied by the caller or filled in with the node's gas estimate
+    pub gas: U256,
+    /// The gas price of legacy and EIP-2930 transactions
+    pub gas_price: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// The most the transaction can cost the sender: its value plus its gas at the highest price
+    pub max_total_cost: U256,
+}
+
+impl PreparedTx {
+    pub fn new(tx: TypedTransaction) -> Self {
+        let value = tx.value().copied().unwrap_or_default();
+        let (gas_price, max_fee_per_gas, max_priority_fee_per_gas) = match tx {
+            TypedTransaction::Eip1559(ref inner) => {
+                (None, inner.max_fee_per_gas, inner.max_priority_fee_per_gas)
+            }
+            TypedTransaction::Eip4844(ref inner) => {
+                (None, inner.tx.max_fee_per_gas, inner.tx.max_priority_fee_per_gas)
+            }
+            _ => (tx.gas_price(), None, None),
+        };
+        Self {
+            from: tx.from().copied(),
+            to: tx.to_addr().copied(),
+            value,
+            nonce: tx.nonce().copied(),
+            gas: tx.gas().copied().unwrap_or_default(),
+            gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_total_cost: tx.max_cost().unwrap_or_default().saturating_add(value),
+            tx,
+        }
+    }
+}
+
+impl fmt::Display for PreparedTx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = |amount: U256, unit: &str| {
+            utils::format_units(amount, unit).unwrap_or_else(|_| amount.to_string())
+        };
+        match self.from {
+            Some(from) => writeln!(f, "from:      {:?}", from)?,
+            None => writeln!(f, "from:      unknown")?,
+        }
+        match self.to {
+            Some(to) => writeln!(f, "to:        {:?}", to)?,
+            None => writeln!(f, "to:        contract deployment")?,
+        }
+        writeln!(f, "value:     {} ETH", units(self.value, "ether"))?;
+        match self.nonce {
+            Some(nonce) => writeln!(f, "nonce:     {}", nonce)?,
+            None => writeln!(f, "nonce:     unknown")?,
+        }
+        writeln!(f, "gas limit: {}", self.gas)?;
+        if let Some(gas_price) = self.gas_price {
+            writeln!(f, "gas price: {} gwei", units(gas_price, "gwei"))?;
+        }
+        if let Some(max_fee) = self.max_fee_per_gas {
+            writeln!(f, "max fee:   {} gwei", units(max_fee, "gwei"))?;
+        }
+        if let Some(priority_fee) = self.max_priority_fee_per_gas {
+            writeln!(f, "tip:       {} gwei", units(priority_fee, "gwei"))?;
+        }
+        write!(f, "max cost:  {} ETH", units(self.max_total_cost, "ether"))
+    }
+}
+
 // JSON RPC bindings
 impl<P: JsonRpcClient> Provider<P> {
     /// Instantiate a new provider with a backend.
     pub fn new(provider: P) -> Self {
-        Self { inner: provider, ens: None, interval: None, from: None, _node_client: None }
+        Self {
+            inner: provider,
+            ens: None,
+            interval: None,
+            from: None,
+            max_response_size: None,
+            block_receipts_fallback: false,
+            fill_access_list: false,
+            default_block: None,
+            ens_retries: 0,
+            ens_disabled: false,
+            block_number_ttl: None,
+            block_number_cache: Arc::new(Mutex::new(None)),
+            tx_serializer: None,
+            error_classifier: None,
+            coalescer: None,
+            _node_client: None,
+        }
     }
 
     /// Returns the type of node we're connected to, while also caching the value for use
@@ -149,22 +474,186 @@ impl<P: JsonRpcClient> Provider<P> {
         }
     }
 
+    /// Returns the node client family and version parsed from `web3_clientVersion`, which can be
+    /// used to gate behavior on the node's version.
+    pub fn node_client_version(&self) -> Result<NodeClientVersion, ProviderError> {
+        self.client_version()?.parse()
+    }
+
     #[must_use]
     pub fn with_sender(mut self, address: impl Into<Address>) -> Self {
         self.from = Some(address.into());
         self
     }
 
+    /// Pins reads (e.g. balance, nonce, code, storage and calls) which are not given a block to
+    /// `block`, instead of the latest block. Blocks passed explicitly still take precedence.
+    #[must_use]
+    pub fn with_default_block(mut self, block: impl Into<BlockId>) -> Self {
+        self.default_block = Some(block.into());
+        self
+    }
+
+    /// Retries the `eth_call`s made while resolving ENS names up to `retries` times when they fail
+    /// with a transport error, backing off exponentially between attempts. Logical resolution
+    /// failures, such as a name without a resolver, are not retried.
+    #[must_use]
+    pub fn with_ens_retries(mut self, retries: u32) -> Self {
+        self.ens_retries = retries;
+        self
+    }
+
+    /// Reuses the block number returned by `get_block_number` for `ttl`, e.g. a fraction of the
+    /// chain's block time, to dedupe the calls of services asking for it constantly. Clones of the
+    /// provider share the cached block number.
+    #[must_use]
+    pub fn with_block_number_cache(mut self, ttl: Duration) -> Self {
+        self.block_number_ttl = Some(ttl);
+        self
+    }
+
+    /// Encodes the transactions sent to the node with `serializer`, for chains with transaction
+    /// types unknown to [`TypedTransaction`]
+    #[must_use]
+    pub fn with_tx_serializer(mut self, serializer: impl TransactionSerializer + 'static) -> Self {
+        self.tx_serializer = Some(Arc::new(serializer));
+        self
+    }
+
+    /// Returns the JSON-RPC representation of `tx`, as encoded by the registered
+    /// [`TransactionSerializer`] if it handles it
+    pub fn serialize_tx(&self, tx: &TypedTransaction) -> serde_json::Value {
+        self.tx_serializer
+            .as_ref()
+            .and_then(|serializer| serializer.serialize_tx(tx))
+            .unwrap_or_else(|| utils::serialize(tx))
+    }
+
+    /// Returns the signed network encoding of `tx` to be sent with `eth_sendRawTransaction`, as
+    /// encoded by the registered [`TransactionSerializer`] if it handles it
+    pub fn rlp_signed(&self, tx: &TypedTransaction, signature: &Signature) -> Bytes {
+        self.tx_serializer
+            .as_ref()
+            .and_then(|serializer| serializer.rlp_signed(tx, signature))
+            .unwrap_or_else(|| tx.rlp_network(signature))
+    }
+
+    /// Classifies the JSON-RPC errors of the node with `classifier`, for nodes whose error
+    /// messages the [`DefaultErrorClassifier`] does not know
+    #[must_use]
+    pub fn with_error_classifier(mut self, classifier: impl ErrorClassifier + 'static) -> Self {
+        self.error_classifier = Some(Arc::new(classifier));
+        self
+    }
+
+    /// Returns the kind of `err` as classified by the registered [`ErrorClassifier`], see
+    /// [`ProviderError::kind`]
+    pub fn error_kind(&self, err: &ProviderError) -> RpcErrorKind {
+        match self.error_classifier {
+            Some(ref classifier) => err.kind_with(classifier.as_ref()),
+            None => err.kind(),
+        }
+    }
+
+    /// Sends identical requests (same method and params) made while one of them is in flight only
+    /// once, sharing the response among the callers, to spare the node bursts of callers asking
+    /// for the same data. Clones of the provider share the in-flight requests.
+    #[must_use]
+    pub fn with_request_coalescing(mut self) -> Self {
+        self.coalescer = Some(Arc::new(RequestCoalescer::default()));
+        self
+    }
+
+    /// Returns `block`, falling back to the default block and then to the latest block
+    fn block_or_default(&self, block: Option<BlockId>) -> BlockId {
+        block.or(self.default_block).unwrap_or_else(|| BlockNumber::Latest.into())
+    }
+
     pub fn request<T, R>(&self, method: &str, params: T) -> Result<R, ProviderError>
     where
         T: Debug + Serialize + Send + Sync,
         R: Serialize + DeserializeOwned + Debug,
     {
-        let res = {
-            let res: R = self.inner.request(method, params).map_err(Into::into)?;
-            Ok::<_, ProviderError>(res)
-        }?;
-        Ok(res)
+        let raw = self.send_request(method, params, None)?;
+        deserialize_response(method, raw)
+    }
+
+    /// Like [`request`](Self::request), but rejects responses exceeding the configured
+    /// maximum response size
+    fn request_with_max_size<T, R>(&self, method: &str, params: T) -> Result<R, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: Serialize + DeserializeOwned + Debug,
+    {
+        let raw = self.send_request(method, params, self.max_response_size)?;
+        deserialize_response(method, raw)
+    }
+
+    /// Sends a request to `method` with each of the `params`, as a single JSON-RPC batch if the
+    /// transport supports it, see [`JsonRpcClient::request_batch`]
+    pub fn request_batch<T, R>(&self, method: &str, params: Vec<T>) -> Result<Vec<R>, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: Serialize + DeserializeOwned + Debug,
+    {
+        let raw: Vec<serde_json::Value> =
+            self.inner.request_batch(method, params).map_err(|err| self.classify(err.into()))?;
+        raw.into_iter().map(|raw| deserialize_response(method, raw)).collect()
+    }
+
+    /// Sends the request to the transport. With the `tracing-spans` feature, the request runs in a
+    /// `request` span recording the method, the size of the serialized params and the duration.
+    fn send_request<T>(
+        &self,
+        method: &str,
+        params: T,
+        max_size: Option<usize>,
+    ) -> Result<serde_json::Value, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+    {
+        #[cfg(feature = "tracing-spans")]
+        let span = {
+            let params_size = serde_json::to_vec(&params).map_or(0, |params| params.len());
+            tracing::debug_span!("request", method, params_size)
+        };
+        #[cfg(feature = "tracing-spans")]
+        let _enter = span.enter();
+        #[cfg(feature = "tracing-spans")]
+        let start = Instant::now();
+
+        let res = match self.coalescer {
+            Some(ref coalescer) => {
+                let key = serde_json::to_string(&params)?;
+                coalescer.coalesce(method, key, max_size, || {
+                    self.inner.request_with_max_size(method, params, max_size).map_err(Into::into)
+                })
+            }
+            None => self.inner.request_with_max_size(method, params, max_size).map_err(Into::into),
+        };
+
+        #[cfg(feature = "tracing-spans")]
+        tracing::debug!(duration_ms = start.elapsed().as_millis() as u64, ok = res.is_ok());
+        res.map_err(|err| self.classify(err))
+    }
+
+    /// Attaches the kind of `err` as classified by the registered [`ErrorClassifier`], so
+    /// [`ProviderError::kind`] returns it
+    fn classify(&self, err: ProviderError) -> ProviderError {
+        let classifier = match self.error_classifier {
+            Some(ref classifier) => classifier,
+            None => return err,
+        };
+        let kind = match err.as_error_response() {
+            Some(response) => classifier.classify(response),
+            None => return err,
+        };
+        match err {
+            ProviderError::JsonRpcClientError(source) => {
+                ProviderError::JsonRpcClientError(Box::new(ClassifiedError { kind, source }))
+            }
+            err => err,
+        }
     }
 
     fn get_block_gen<Tx: Default + Serialize + DeserializeOwned + Debug>(
@@ -186,6 +675,52 @@ impl<P: JsonRpcClient> Provider<P> {
         })
     }
 
+    /// Signs EIP-712 typed data using a specific account via the `eth_signTypedData_v4` RPC. This
+    /// account needs to be unlocked.
+    ///
+    /// The `typed_data` is sent as-is, so it must serialize to the JSON representation of the
+    /// typed data (`types`, `primaryType`, `domain` and `message`). This complements signing typed
+    /// data locally with a [`Signer`](https://docs.rs/ethers-signers).
+    pub fn sign_typed_data_rpc<T: Serialize + Send + Sync>(
+        &self,
+        from: Address,
+        typed_data: &T,
+    ) -> Result<Signature, ProviderError> {
+        let from = utils::serialize(&from);
+        let typed_data = utils::serialize(typed_data);
+
+        let sig: String = self.request("eth_signTypedData_v4", [from, typed_data])?;
+        decode_signature(&sig)
+    }
+
+    /// Signs `data` with the node-managed key of `from` via `personal_sign`, which prefixes the
+    /// data with `"\x19Ethereum Signed Message:\n" + len(data)` before hashing it. The account
+    /// needs to be unlocked.
+    pub fn personal_sign<T: Into<Bytes> + Send + Sync>(
+        &self,
+        data: T,
+        from: Address,
+    ) -> Result<Signature, ProviderError> {
+        let data = utils::serialize(&data.into());
+        let from = utils::serialize(&from);
+
+        let sig: String = self.request("personal_sign", [data, from])?;
+        decode_signature(&sig)
+    }
+
+    /// Returns the address which produced `signature` for a `personal_sign` style message via
+    /// `personal_ecRecover`
+    pub fn personal_ec_recover<T: Into<Bytes> + Send + Sync>(
+        &self,
+        data: T,
+        signature: &Signature,
+    ) -> Result<Address, ProviderError> {
+        let data = utils::serialize(&data.into());
+        let signature = utils::serialize(&format!("0x{}", signature));
+
+        self.request("personal_ecRecover", [data, signature])
+    }
+
     /// Analogous to [`Middleware::call`], but returns a [`CallBuilder`] that can either be
     /// ``d or used to override the parameters sent to `eth_call`.
     ///
@@ -223,6 +758,10 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         self
     }
 
+    fn convert_err(err: ProviderError) -> ProviderError {
+        err
+    }
+
     fn default_sender(&self) -> Option<Address> {
         self.from
     }
@@ -236,6 +775,11 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         self.request("web3_clientVersion", ())
     }
 
+    /// Returns the Keccak-256 hash of `data`, as computed by the node
+    fn web3_sha3(&self, data: Bytes) -> Result<H256, Self::Error> {
+        self.request("web3_sha3", [data])
+    }
+
     fn fill_transaction(
         &self,
         tx: &mut TypedTransaction,
@@ -265,7 +809,8 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 
                 tx.set_gas_price(gas_price);
             }
-            TypedTransaction::Eip1559(ref mut inner) => {
+            TypedTransaction::Eip1559(ref mut inner) |
+            TypedTransaction::Eip4844(Eip4844TransactionRequest { tx: ref mut inner, .. }) => {
                 if inner.max_fee_per_gas.is_none() || inner.max_priority_fee_per_gas.is_none() {
                     let (max_fee_per_gas, max_priority_fee_per_gas) =
                         self.estimate_eip1559_fees(None)?;
@@ -275,6 +820,21 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
             }
         }
 
+        // commit to the blobs of the sidecar
+        if let TypedTransaction::Eip4844(ref mut inner) = tx {
+            inner.fill_versioned_hashes();
+        }
+
+        // populate an empty EIP-2930 access list, so that the gas estimate below accounts for it
+        if self.fill_access_list {
+            if let TypedTransaction::Eip2930(ref inner) = tx {
+                if inner.access_list.0.is_empty() {
+                    let access_list = self.create_access_list(tx, block)?.access_list;
+                    tx.set_access_list(access_list);
+                }
+            }
+        }
+
         // Set gas to estimated value only if it was not set by the caller,
         // even if the access list has been populated and saves gas
         if tx.gas().is_none() {
@@ -287,7 +847,21 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 
     /// Gets the latest block number via the `eth_BlockNumber` API
     fn get_block_number(&self) -> Result<U64, ProviderError> {
-        self.request("eth_blockNumber", ())
+        let ttl = match self.block_number_ttl {
+            Some(ttl) => ttl,
+            None => return self.request("eth_blockNumber", ()),
+        };
+
+        // the lock is held while fetching, so concurrent callers wait for the same request
+        let mut cache = self.block_number_cache.lock().unwrap();
+        if let Some((fetched_at, number)) = *cache {
+            if fetched_at.elapsed() < ttl {
+                return Ok(number)
+            }
+        }
+        let number = self.request("eth_blockNumber", ())?;
+        *cache = Some((Instant::now(), number));
+        Ok(number)
     }
 
     /// Gets the block at `block_hash_or_number` (transaction hashes only)
@@ -353,6 +927,22 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         self.request("eth_getTransactionByHash", [hash])
     }
 
+    /// Gets the signed network encoding of the transaction with `transaction_hash`. Nodes without
+    /// `eth_getRawTransactionByHash` are asked for the transaction instead, which is then
+    /// re-encoded with its signature.
+    fn get_raw_transaction<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<Bytes>, ProviderError> {
+        let hash = transaction_hash.into();
+        match self.request("eth_getRawTransactionByHash", [hash]) {
+            Err(err) if err.is_method_not_found() => {
+                Ok(self.get_transaction(hash)?.map(|tx| tx.rlp()))
+            }
+            res => res,
+        }
+    }
+
     /// Gets the transaction receipt with `transaction_hash`
     fn get_transaction_receipt<T: Send + Sync + Into<TxHash>>(
         &self,
@@ -366,11 +956,36 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
     ///
     /// Note that this uses the `eth_getBlockReceipts` RPC, which is
     /// non-standard and currently supported by Erigon.
+    ///
+    /// If the block receipts fallback is enabled (see [`Provider::set_block_receipts_fallback`])
+    /// and the node supports neither `eth_getBlockReceipts` nor `parity_getBlockReceipts`, the
+    /// receipts are fetched one by one for every transaction in the block.
     fn get_block_receipts<T: Into<BlockNumber> + Send + Sync>(
         &self,
         block: T,
     ) -> Result<Vec<TransactionReceipt>, Self::Error> {
-        self.request("eth_getBlockReceipts", [block.into()])
+        let block = block.into();
+        match self.request("eth_getBlockReceipts", [block]) {
+            Err(err) if self.block_receipts_fallback && err.is_method_not_found() => {}
+            res => return res,
+        }
+        match self.request("parity_getBlockReceipts", [block]) {
+            Err(err) if err.is_method_not_found() => {}
+            res => return res,
+        }
+
+        let block = self
+            .get_block(block)?
+            .ok_or_else(|| ProviderError::CustomError(format!("block {} not found", block)))?;
+        block
+            .transactions
+            .into_iter()
+            .map(|hash| {
+                self.get_transaction_receipt(hash)?.ok_or_else(|| {
+                    ProviderError::CustomError(format!("receipt for {:?} not found", hash))
+                })
+            })
+            .collect()
     }
 
     /// Returns all receipts for that block. Must be done on a parity node.
@@ -431,7 +1046,7 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         };
 
         let from = utils::serialize(&from);
-        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        let block = utils::serialize(&self.block_or_default(block));
         self.request("eth_getTransactionCount", [from, block])
     }
 
@@ -447,14 +1062,41 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         };
 
         let from = utils::serialize(&from);
-        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        let block = utils::serialize(&self.block_or_default(block));
         self.request("eth_getBalance", [from, block])
     }
 
+    /// Returns the balance of `from` at each of the given `blocks`, sending the requests as a
+    /// single JSON-RPC batch
+    fn get_balance_history<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        from: T,
+        blocks: &[BlockNumber],
+    ) -> Result<Vec<U256>, ProviderError> {
+        let from = match from.into() {
+            NameOrAddress::Name(ens_name) => self.resolve_name(&ens_name)?,
+            NameOrAddress::Address(addr) => addr,
+        };
+
+        let from = utils::serialize(&from);
+        let params = blocks.iter().map(|block| [from.clone(), utils::serialize(block)]).collect();
+        self.request_batch("eth_getBalance", params)
+    }
+
     /// Returns the currently configured chain id, a value used in replay-protected
     /// transaction signing as introduced by EIP-155.
     fn get_chainid(&self) -> Result<U256, ProviderError> {
-        self.request("eth_chainId", ())
+        let raw: serde_json::Value = self.request("eth_chainId", ())?;
+        // some nodes return the chain id as a decimal string or number instead of a quantity
+        let decimal = match &raw {
+            serde_json::Value::String(id) if !id.starts_with("0x") => U256::from_dec_str(id).ok(),
+            serde_json::Value::Number(id) => id.as_u64().map(U256::from),
+            _ => None,
+        };
+        match decimal {
+            Some(chain_id) => Ok(chain_id),
+            None => deserialize_response("eth_chainId", raw),
+        }
     }
 
     /// Return current client syncing status. If IsFalse sync is over.
@@ -467,6 +1109,31 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         self.request("net_version", ())
     }
 
+    /// Returns the number of peers currently connected to the node
+    fn net_peer_count(&self) -> Result<U256, ProviderError> {
+        self.request("net_peerCount", ())
+    }
+
+    /// Returns whether the node is listening for network connections
+    fn net_listening(&self) -> Result<bool, ProviderError> {
+        self.request("net_listening", ())
+    }
+
+    /// Returns the address mining rewards are credited to
+    fn get_coinbase(&self) -> Result<Address, ProviderError> {
+        self.request("eth_coinbase", ())
+    }
+
+    /// Returns whether the node is actively mining new blocks
+    fn get_mining(&self) -> Result<bool, ProviderError> {
+        self.request("eth_mining", ())
+    }
+
+    /// Returns the number of hashes per second the node is mining with
+    fn get_hashrate(&self) -> Result<U256, ProviderError> {
+        self.request("eth_hashrate", ())
+    }
+
     ////// Contract Execution
     //
     // These are relatively low-level calls. The Contracts API should usually be used instead.
@@ -475,9 +1142,51 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
     /// (as bytes) of executing it. This is free, since it does not change any state on the
     /// blockchain.
     fn call(&self, tx: &TypedTransaction, block: Option<BlockId>) -> Result<Bytes, ProviderError> {
-        let tx = utils::serialize(tx);
-        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
-        self.request("eth_call", [tx, block])
+        let tx = self.resolve_recipient(tx)?;
+        let tx = self.serialize_tx(&tx);
+        let block = utils::serialize(&self.block_or_default(block));
+        self.request_with_max_size("eth_call", [tx, block]).map_err(ProviderError::into_revert)
+    }
+
+    /// Sends the read-only transaction like [`call`](crate::Middleware::call), with the given
+    /// state overrides applied for the duration of the call.
+    fn call_with_overrides(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        overrides: &call_raw::spoof::State,
+    ) -> Result<Bytes, ProviderError> {
+        let tx = self.resolve_recipient(tx)?;
+        let mut call = self.call_raw(&tx).state(overrides);
+        if let Some(block) = block.or(self.default_block) {
+            call = call.block(block);
+        }
+        call.poll().map_err(ProviderError::into_revert)
+    }
+
+    /// Executes the call with `debug_traceCall`, which returns the return data and the gas used in
+    /// a single round trip. Nodes without the `debug` namespace are sent an `eth_call` and an
+    /// `eth_estimateGas` instead, in which case the gas is an estimate.
+    fn call_with_gas(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(Bytes, U256), ProviderError> {
+        let resolved = self.resolve_recipient(tx)?;
+        let params = [
+            self.serialize_tx(&resolved),
+            utils::serialize(&self.block_or_default(block)),
+            // the struct logs are not needed, only the result of the call
+            serde_json::json!({ "disableStorage": true, "disableStack": true, "limit": 1 }),
+        ];
+        match self.request::<_, GethTrace>("debug_traceCall", params) {
+            Ok(trace) if trace.failed => Err(ProviderError::Revert(trace.return_value)),
+            Ok(trace) => Ok((trace.return_value, trace.gas.into())),
+            Err(err) if err.is_method_not_found() => {
+                Ok((self.call(tx, block)?, self.estimate_gas(tx, block)?))
+            }
+            Err(err) => Err(err),
+        }
     }
 
     /// Sends a transaction to a single Ethereum node and return the estimated amount of gas
@@ -489,7 +1198,8 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         tx: &TypedTransaction,
         block: Option<BlockId>,
     ) -> Result<U256, ProviderError> {
-        let tx = utils::serialize(tx);
+        let tx = self.resolve_recipient(tx)?;
+        let tx = self.serialize_tx(&tx);
         // Some nodes (e.g. old Optimism clients) don't support a block ID being passed as a param,
         // so refrain from defaulting to BlockNumber::Latest.
         let params = if let Some(block_id) = block {
@@ -497,7 +1207,7 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         } else {
             vec![tx]
         };
-        self.request("eth_estimateGas", params)
+        self.request("eth_estimateGas", params).map_err(ProviderError::into_revert)
     }
 
     fn create_access_list(
@@ -505,8 +1215,9 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         tx: &TypedTransaction,
         block: Option<BlockId>,
     ) -> Result<AccessListWithGasUsed, ProviderError> {
-        let tx = utils::serialize(tx);
-        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        let tx = self.resolve_recipient(tx)?;
+        let tx = self.serialize_tx(&tx);
+        let block = utils::serialize(&self.block_or_default(block));
         self.request("eth_createAccessList", [tx, block])
     }
     /// The JSON-RPC provider is at the bottom-most position in the middleware stack. Here we check
@@ -527,14 +1238,9 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         let data = utils::serialize(&data.into());
         let from = utils::serialize(from);
 
-        // get the response from `eth_sign` call and trim the 0x-prefix if present.
+        // get the response from `eth_sign` call and decode the signature.
         let sig: String = self.request("eth_sign", [from, data])?;
-        let sig = sig.strip_prefix("0x").unwrap_or(&sig);
-
-        // decode the signature.
-        let sig = hex::decode(sig)?;
-        Ok(Signature::try_from(sig.as_slice())
-            .map_err(|e| ProviderError::CustomError(e.to_string()))?)
+        decode_signature(&sig)
     }
 
     /// Sign a transaction via RPC call
@@ -550,7 +1256,22 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 
     /// Returns an array (possibly empty) of logs that match the filter
     fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, ProviderError> {
-        self.request("eth_getLogs", [filter])
+        self.request_with_max_size("eth_getLogs", [filter])
+    }
+
+    fn get_logs_for_event<T: Into<BlockNumber> + Send + Sync>(
+        &self,
+        address: Address,
+        event_signature: &str,
+        from: T,
+        to: T,
+    ) -> Result<Vec<Log>, ProviderError> {
+        let filter = Filter::new()
+            .address(address)
+            .topic0(event_topic(event_signature)?)
+            .from_block(from)
+            .to_block(to);
+        self.get_logs(&filter)
     }
 
     fn get_logs_paginated<'a>(&'a self, filter: &Filter, page_size: u64) -> LogQuery<'a, P> {
@@ -596,6 +1317,17 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         self.request("eth_getFilterChanges", [id])
     }
 
+    /// Returns all logs matching the installed log filter with the given id, unlike
+    /// [`get_filter_changes`](Self::get_filter_changes) which only returns the logs since the last
+    /// poll. This is useful to re-fetch the full set of logs, e.g. after reconnecting.
+    fn get_filter_logs<T: Into<U256> + Send + Sync>(
+        &self,
+        id: T,
+    ) -> Result<Vec<Log>, ProviderError> {
+        let id = utils::serialize(&id.into());
+        self.request_with_max_size("eth_getFilterLogs", [id])
+    }
+
     /// Get the storage of an address for a particular slot location
     fn get_storage_at<T: Into<NameOrAddress> + Send + Sync>(
         &self,
@@ -613,7 +1345,7 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         let position = U256::from_big_endian(location.as_bytes());
         let position = utils::serialize(&position);
         let from = utils::serialize(&from);
-        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        let block = utils::serialize(&self.block_or_default(block));
 
         // get the hex encoded value.
         let value: String = self.request("eth_getStorageAt", [from, position, block])?;
@@ -634,7 +1366,7 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         };
 
         let at = utils::serialize(&at);
-        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        let block = utils::serialize(&self.block_or_default(block));
         self.request("eth_getCode", [at, block])
     }
 
@@ -653,11 +1385,34 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 
         let from = utils::serialize(&from);
         let locations = locations.iter().map(|location| utils::serialize(&location)).collect();
-        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        let block = utils::serialize(&self.block_or_default(block));
 
         self.request("eth_getProof", [from, locations, block])
     }
 
+    /// Returns the balance, nonce, code hash and storage root of an account in a single request.
+    ///
+    /// Uses `eth_getAccount` and falls back to `eth_getProof` without storage slots on nodes
+    /// which do not support it, since it returns the same fields.
+    fn get_account<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        account: T,
+        block: Option<BlockId>,
+    ) -> Result<Account, ProviderError> {
+        let account = match account.into() {
+            NameOrAddress::Name(ens_name) => self.resolve_name(&ens_name)?,
+            NameOrAddress::Address(addr) => addr,
+        };
+
+        let block = self.block_or_default(block);
+        match self.request("eth_getAccount", [utils::serialize(&account), utils::serialize(&block)])
+        {
+            Err(err) if err.is_method_not_found() => {}
+            res => return res,
+        }
+        self.get_proof(account, vec![], Some(block)).map(Into::into)
+    }
+
     ////// Ethereum Naming Service
     // The Ethereum Naming Service (ENS) allows easy to remember and use names to
     // be assigned to Ethereum addresses. Any provider operation which takes an address
@@ -813,6 +1568,19 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         self.request("txpool_content", ())
     }
 
+    fn pending_transactions_from(&self, from: Address) -> Result<Vec<Transaction>, ProviderError> {
+        let mut content: TxpoolTransactions = self.request("txpool_content", ())?;
+        let mut txs: Vec<_> = content
+            .pending
+            .remove(&from)
+            .into_iter()
+            .chain(content.queued.remove(&from))
+            .flat_map(|txs| txs.into_values())
+            .collect();
+        txs.sort_by_key(|tx| tx.nonce);
+        Ok(txs)
+    }
+
     /// Returns a summary of all the transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     /// Ref: [Here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_inspect)
@@ -838,6 +1606,34 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         self.request("debug_traceTransaction", [tx_hash, trace_options])
     }
 
+    /// Replays all transactions of the block with the given number, returning one trace per
+    /// transaction
+    fn debug_trace_block_by_number(
+        &self,
+        block: BlockNumber,
+        trace_options: GethDebugTracingOptions,
+    ) -> Result<Vec<GethTrace>, ProviderError> {
+        let block = utils::serialize(&block);
+        let trace_options = utils::serialize(&trace_options);
+        let traces: Vec<BlockTraceResult> =
+            self.request("debug_traceBlockByNumber", [block, trace_options])?;
+        Ok(traces.into_iter().map(|trace| trace.result).collect())
+    }
+
+    /// Replays all transactions of the block with the given hash, returning one trace per
+    /// transaction
+    fn debug_trace_block_by_hash(
+        &self,
+        block_hash: H256,
+        trace_options: GethDebugTracingOptions,
+    ) -> Result<Vec<GethTrace>, ProviderError> {
+        let block_hash = utils::serialize(&block_hash);
+        let trace_options = utils::serialize(&trace_options);
+        let traces: Vec<BlockTraceResult> =
+            self.request("debug_traceBlockByHash", [block_hash, trace_options])?;
+        Ok(traces.into_iter().map(|trace| trace.result).collect())
+    }
+
     /// Executes the given call and returns a number of possible traces for it
     fn trace_call<T: Into<TypedTransaction> + Send + Sync>(
         &self,
@@ -846,7 +1642,8 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         block: Option<BlockNumber>,
     ) -> Result<BlockTrace, ProviderError> {
         let req = req.into();
-        let req = utils::serialize(&req);
+        let req = self.resolve_recipient(&req)?;
+        let req = self.serialize_tx(&req);
         let block = utils::serialize(&block.unwrap_or(BlockNumber::Latest));
         let trace_type = utils::serialize(&trace_type);
         self.request("trace_call", [req, trace_type, block])
@@ -858,8 +1655,14 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
         req: Vec<(T, Vec<TraceType>)>,
         block: Option<BlockNumber>,
     ) -> Result<Vec<BlockTrace>, ProviderError> {
-        let req: Vec<(TypedTransaction, Vec<TraceType>)> =
-            req.into_iter().map(|(tx, trace_type)| (tx.into(), trace_type)).collect();
+        let req = req
+            .into_iter()
+            .map(|(tx, trace_type)| {
+                let tx = tx.into();
+                let tx = self.resolve_recipient(&tx)?;
+                Ok((self.serialize_tx(&tx), trace_type))
+            })
+            .collect::<Result<Vec<(serde_json::Value, Vec<TraceType>)>, ProviderError>>()?;
         let req = utils::serialize(&req);
         let block = utils::serialize(&block.unwrap_or(BlockNumber::Latest));
         self.request("trace_callMany", [req, block])
@@ -980,12 +1783,16 @@ impl<P: JsonRpcClient> Provider<P> {
         selector: Selector,
         parameters: Option<&[u8]>,
     ) -> Result<T, ProviderError> {
+        if self.ens_disabled {
+            return Err(ProviderError::EnsError("ENS disabled".to_string()))
+        }
+
         // Get the ENS address, prioritize the local override variable
         let ens_addr = self.ens.unwrap_or(ens::ENS_ADDRESS);
 
         // first get the resolver responsible for this name
         // the call will return a Bytes array which we convert to an address
-        let data = self.call(&ens::get_resolver(ens_addr, ens_name).into(), None)?;
+        let data = self.ens_call(ens::get_resolver(ens_addr, ens_name).into())?;
 
         // otherwise, decode_bytes panics
         if data.0.is_empty() {
@@ -1003,20 +1810,55 @@ impl<P: JsonRpcClient> Provider<P> {
         }
 
         // resolve
-        let data = self
-            .call(&ens::resolve(resolver_address, selector, ens_name, parameters).into(), None)?;
+        let data =
+            self.ens_call(ens::resolve(resolver_address, selector, ens_name, parameters).into())?;
 
         Ok(decode_bytes(param, data))
     }
 
-    /// Validates that the resolver supports `selector`.
-    fn validate_resolver(
+    /// Performs an `eth_call` to the ENS registry or a resolver, retrying transport failures and
+    /// rate limited calls with exponential backoff up to the configured number of ENS retries
+    fn ens_call(&self, tx: TypedTransaction) -> Result<Bytes, ProviderError> {
+        let classifier = self.error_classifier.as_deref().unwrap_or(&DefaultErrorClassifier);
+        let mut retries = self.ens_retries;
+        let mut backoff = ENS_RETRY_BACKOFF;
+        loop {
+            match self.call(&tx, None) {
+                Err(err) if retries > 0 && is_retryable(&err, classifier) => {
+                    retries -= 1;
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Returns `tx` with the ENS name in its `to` field, if any, resolved to an address, so that it
+    /// can be serialized.
+    fn resolve_recipient<'a>(
+        &self,
+        tx: &'a TypedTransaction,
+    ) -> Result<Cow<'a, TypedTransaction>, ProviderError> {
+        match tx.to() {
+            Some(NameOrAddress::Name(ens_name)) => {
+                let addr = self.resolve_name(ens_name)?;
+                let mut tx = tx.clone();
+                tx.set_to(addr);
+                Ok(Cow::Owned(tx))
+            }
+            _ => Ok(Cow::Borrowed(tx)),
+        }
+    }
+
+    /// Validates that the resolver supports `selector`.
+    fn validate_resolver(
         &self,
         resolver_address: Address,
         selector: Selector,
         ens_name: &str,
     ) -> Result<(), ProviderError> {
-        let data = self.call(&ens::supports_interface(resolver_address, selector).into(), None)?;
+        let data = self.ens_call(ens::supports_interface(resolver_address, selector).into())?;
 
         if data.is_empty() {
             return Err(ProviderError::EnsError(format!(
@@ -1057,6 +1899,313 @@ impl<P: JsonRpcClient> Provider<P> {
         self
     }
 
+    /// Disables ENS, so that ENS names, e.g. in the `to` field of a transaction, are rejected with
+    /// [`ProviderError::EnsError`] instead of being resolved. Avoids slow resolver calls on chains
+    /// without an ENS registry.
+    #[must_use]
+    pub fn without_ens(mut self) -> Self {
+        self.ens_disabled = true;
+        self
+    }
+
+    /// Sets the maximum size in bytes of `eth_call` and `eth_getLogs` responses. Larger responses
+    /// are rejected with [`ProviderError::ResponseTooLarge`] before being deserialized.
+    /// (default: unlimited)
+    pub fn set_max_response_size(&mut self, max_size: usize) -> &mut Self {
+        self.max_response_size = Some(max_size);
+        self
+    }
+
+    /// Sets the maximum size in bytes of `eth_call` and `eth_getLogs` responses. Larger responses
+    /// are rejected with [`ProviderError::ResponseTooLarge`] before being deserialized.
+    /// (default: unlimited)
+    #[must_use]
+    pub fn max_response_size(mut self, max_size: usize) -> Self {
+        self.set_max_response_size(max_size);
+        self
+    }
+
+    /// Enables fetching the receipts of every transaction individually in `get_block_receipts`
+    /// when the node supports no block receipts RPC. This issues one request per transaction.
+    /// (default: disabled)
+    pub fn set_block_receipts_fallback(&mut self, enabled: bool) -> &mut Self {
+        self.block_receipts_fallback = enabled;
+        self
+    }
+
+    /// Enables fetching the receipts of every transaction individually in `get_block_receipts`
+    /// when the node supports no block receipts RPC. This issues one request per transaction.
+    /// (default: disabled)
+    #[must_use]
+    pub fn block_receipts_fallback(mut self, enabled: bool) -> Self {
+        self.set_block_receipts_fallback(enabled);
+        self
+    }
+
+    /// Enables populating empty access lists of EIP-2930 transactions in `fill_transaction` via
+    /// `eth_createAccessList`. (default: disabled)
+    pub fn set_fill_access_list(&mut self, enabled: bool) -> &mut Self {
+        self.fill_access_list = enabled;
+        self
+    }
+
+    /// Enables populating empty access lists of EIP-2930 transactions in `fill_transaction` via
+    /// `eth_createAccessList`. (default: disabled)
+    #[must_use]
+    pub fn fill_access_list(mut self, enabled: bool) -> Self {
+        self.set_fill_access_list(enabled);
+        self
+    }
+
+    /// Returns the polling interval for event filters and pending transactions
+    pub fn get_interval(&self) -> Duration {
+        self.interval.unwrap_or(DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Returns an iterator yielding the number of confirmations of the transaction every time it
+    /// increases, polling the node at the provider's [interval](Self::get_interval). The iterator
+    /// ends once the transaction has `confirmations` confirmations.
+    pub fn watch_transaction(
+        &self,
+        tx_hash: TxHash,
+        confirmations: u64,
+    ) -> ConfirmationWatcher<'_, P> {
+        ConfirmationWatcher::new(self, tx_hash, confirmations)
+    }
+
+    /// Polls for the receipt of the transaction at the provider's [interval](Self::get_interval)
+    /// until it has `confirmations` confirmations, and returns it.
+    ///
+    /// Returns `Ok(None)` if the transaction was dropped, i.e. it is no longer known to the node
+    /// after having been pending or mined, and [`ProviderError::Timeout`] if the confirmations are
+    /// not reached within `timeout`. The node is polled at least once.
+    pub fn wait_for_receipt(
+        &self,
+        tx_hash: TxHash,
+        timeout: Duration,
+        confirmations: u64,
+    ) -> Result<Option<TransactionReceipt>, ProviderError> {
+        let deadline = Instant::now() + timeout;
+        let mut seen = false;
+        loop {
+            match self.get_transaction_receipt(tx_hash)? {
+                Some(receipt) => {
+                    seen = true;
+                    // some nodes return receipts of pending transactions, without a block number
+                    if let Some(mined_in) = receipt.block_number {
+                        let latest = self.get_block_number()?;
+                        if (latest + 1).saturating_sub(mined_in).as_u64() >= confirmations {
+                            return Ok(Some(receipt))
+                        }
+                    }
+                }
+                None => match self.get_transaction(tx_hash)? {
+                    Some(_) => seen = true,
+                    // the transaction disappeared, e.g. it was replaced or evicted from the mempool
+                    None if seen => return Ok(None),
+                    None => {}
+                },
+            }
+
+            if Instant::now() >= deadline {
+                return Err(ProviderError::Timeout)
+            }
+            thread::sleep(self.get_interval());
+        }
+    }
+
+    /// Returns an iterator yielding the logs matching `filter` in the pending block, polling the
+    /// node at the provider's [interval](Self::get_interval). The filter's block range is replaced
+    /// with the pending block, and logs already returned by the previous poll are skipped.
+    pub fn watch_pending_logs(&self, filter: &Filter) -> PendingLogWatcher<'_, P> {
+        PendingLogWatcher::new(self, filter)
+    }
+
+    /// Returns an iterator yielding the block number and new value of the storage slot of
+    /// `address` every time it changes, e.g. to follow a price oracle. The slot is read at every
+    /// new block, which is polled for at the provider's [interval](Self::get_interval).
+    pub fn watch_storage(&self, address: Address, slot: H256) -> StorageWatcher<'_, P> {
+        StorageWatcher::new(self, address, slot)
+    }
+
+    /// Returns an iterator yielding the block number and new code of `address` every time its code
+    /// hash changes, e.g. to detect the upgrade of a proxy with an immutable implementation or the
+    /// self-destruct of a contract, whose code becomes empty. The code is fetched at every new
+    /// block, which is polled for at the provider's [interval](Self::get_interval).
+    pub fn watch_code(&self, address: Address) -> CodeWatcher<'_, P> {
+        CodeWatcher::new(self, address)
+    }
+
+    /// Sets the polling interval to a fraction of the chain's block time, averaged over the
+    /// timestamps of the latest blocks, so fast chains are polled more often than slow ones. The
+    /// interval is at least 100ms, including on chains producing several blocks with the same
+    /// timestamp. An interval set with [`set_interval`](Self::set_interval) is kept as is.
+    ///
+    /// Returns the polling interval in use.
+    pub fn detect_interval(&mut self) -> Result<Duration, ProviderError> {
+        if let Some(interval) = self.interval {
+            return Ok(interval)
+        }
+
+        let latest = self.get_block(BlockNumber::Latest)?;
+        let number = latest.as_ref().and_then(|block| block.number).unwrap_or_default();
+        // the genesis block has no ancestor to compare with
+        let sample_size = number.low_u64().min(BLOCK_TIME_SAMPLE_SIZE);
+        if sample_size == 0 {
+            return Ok(self.get_interval())
+        }
+        let past = self.get_block(number - sample_size)?;
+        if let (Some(latest), Some(past)) = (latest, past) {
+            let elapsed = latest.timestamp.saturating_sub(past.timestamp).low_u64();
+            let block_time = Duration::from_secs(elapsed) / sample_size as u32;
+            let interval = block_time / BLOCK_TIME_POLL_FRACTION;
+            self.interval = Some(interval.max(MIN_POLL_INTERVAL));
+        }
+        Ok(self.get_interval())
+    }
+
+    /// Executes the transactions one after the other on top of `block`, each seeing the state
+    /// changes of the previous ones, e.g. to simulate a swap after its approval. Nothing is sent
+    /// to the network.
+    ///
+    /// Returns the return data of every transaction, or [`ProviderError::Revert`] for the ones
+    /// which reverted, which leave the state unchanged. Every transaction is executed with an
+    /// `eth_call`, and all but the last are also traced with `debug_traceCall`'s `prestateTracer`
+    /// to carry their state changes over to the next ones as state overrides, so the node has to
+    /// support the `debug` namespace.
+    pub fn simulate_bundle(
+        &self,
+        txs: &[TypedTransaction],
+        block: Option<BlockId>,
+    ) -> Result<Vec<Result<Bytes, ProviderError>>, ProviderError> {
+        let block = self.block_or_default(block);
+        let mut state = call_raw::spoof::state();
+        let mut results = Vec::with_capacity(txs.len());
+        for (i, tx) in txs.iter().enumerate() {
+            let tx = self.resolve_recipient(tx)?;
+            let output = match self.call_raw(&tx).block(block).state(&state).poll() {
+                Err(err) => match err.into_revert() {
+                    err @ ProviderError::Revert(_) => Err(err),
+                    err => return Err(err),
+                },
+                output => output,
+            };
+            if output.is_ok() && i + 1 < txs.len() {
+                let options = serde_json::json!({
+                    "tracer": "prestateTracer",
+                    "tracerConfig": { "diffMode": true },
+                    "stateOverrides": state,
+                });
+                let params = [self.serialize_tx(&tx), utils::serialize(&block), options];
+                let trace: GethTrace = self.request("debug_traceCall", params)?;
+                state.apply_diff(&trace.into_prestate_diff()?);
+            }
+            results.push(output);
+        }
+        Ok(results)
+    }
+
+    /// Traces the calls with `trace_callMany` like
+    /// [`trace_call_many`](crate::Middleware::trace_call_many), each on top of the state changes
+    /// of the previous ones, and decodes their return data with the paired function's outputs.
+    ///
+    /// Returns the decoded outputs of every call, or [`ProviderError::Revert`] for the ones which
+    /// reverted, whose [`revert_reason`](ProviderError::revert_reason) is then readable. Calls
+    /// failing otherwise, e.g. running out of gas, return the node's error message.
+    pub fn trace_call_many_decoded<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        calls: Vec<(T, &Function)>,
+        block: Option<BlockNumber>,
+    ) -> Result<Vec<Result<Vec<Token>, ProviderError>>, ProviderError> {
+        let (req, functions): (Vec<_>, Vec<_>) = calls
+            .into_iter()
+            .map(|(tx, function)| ((tx, vec![TraceType::Trace]), function))
+            .unzip();
+        let traces = self.trace_call_many(req, block)?;
+        if traces.len() != functions.len() {
+            return Err(ProviderError::CustomError(format!(
+                "trace_callMany returned {} traces for {} calls",
+                traces.len(),
+                functions.len()
+            )))
+        }
+
+        Ok(traces
+            .into_iter()
+            .zip(functions)
+            .map(|(trace, function)| {
+                // the error of the call is the one of its top level trace
+                let error = trace
+                    .trace
+                    .iter()
+                    .flatten()
+                    .find(|trace| trace.trace_address.is_empty())
+                    .and_then(|trace| trace.error.clone());
+                match error {
+                    Some(error) if error == "Reverted" => Err(ProviderError::Revert(trace.output)),
+                    Some(error) => Err(ProviderError::CustomError(error)),
+                    None => function.decode_output(&trace.output).map_err(|err| {
+                        ProviderError::CustomError(format!(
+                            "failed to decode the output of `{}`: {}",
+                            function.name, err
+                        ))
+                    }),
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the logs matching `filter` like [`get_logs`](crate::Middleware::get_logs), splitting
+    /// the query whenever the node rejects it for returning too many logs. The block range is
+    /// halved first, and once it is down to a single block the addresses of the filter are split
+    /// as well. The logs are returned in chain order.
+    ///
+    /// Filters without a numeric `fromBlock`, e.g. filters on a block hash, are not split. An
+    /// unset or non-numeric `toBlock` is replaced with the latest block number.
+    pub fn get_logs_split(&self, filter: &Filter) -> Result<Vec<Log>, ProviderError> {
+        let from = match filter.get_from_block() {
+            Some(from) => from,
+            None => return self.get_logs(filter),
+        };
+        let to = match filter.get_to_block() {
+            Some(to) => to,
+            None => self.get_block_number()?,
+        };
+        self.get_logs_split_range(filter, from, to)
+    }
+
+    fn get_logs_split_range(
+        &self,
+        filter: &Filter,
+        from: U64,
+        to: U64,
+    ) -> Result<Vec<Log>, ProviderError> {
+        let filter = filter.clone().from_block(from).to_block(to);
+        let err = match self.get_logs(&filter) {
+            Err(err) if is_log_limit_error(&err) => err,
+            res => return res,
+        };
+
+        if from < to {
+            let mid = from + (to - from) / 2;
+            let mut logs = self.get_logs_split_range(&filter, from, mid)?;
+            logs.extend(self.get_logs_split_range(&filter, mid + 1, to)?);
+            return Ok(logs)
+        }
+
+        let addresses = match filter.address {
+            Some(ValueOrArray::Array(ref addresses)) if addresses.len() > 1 => addresses.clone(),
+            _ => return Err(err),
+        };
+        let (left, right) = addresses.split_at(addresses.len() / 2);
+        let mut logs =
+            self.get_logs_split_range(&filter.clone().address(left.to_vec()), from, to)?;
+        logs.extend(self.get_logs_split_range(&filter.address(right.to_vec()), from, to)?);
+        // the logs of both halves are from the same block
+        logs.sort_by_key(|log| log.log_index);
+        Ok(logs)
+    }
+
     /// Sets the default polling interval for event filters and pending transactions
     /// (default: 7 seconds)
     pub fn set_interval<T: Into<Duration>>(&mut self, interval: T) -> &mut Self {
@@ -1125,6 +2274,59 @@ fn decode_bytes<T: Detokenize>(param: ParamType, bytes: Bytes) -> T {
     T::from_tokens(tokens).expect("could not parse tokens as address")
 }
 
+/// Deserializes the raw response to `method`, keeping the method and the payload on failure
+fn deserialize_response<R: DeserializeOwned>(
+    method: &str,
+    raw: serde_json::Value,
+) -> Result<R, ProviderError> {
+    R::deserialize(&raw).map_err(|err| ProviderError::Deserialization {
+        method: method.to_string(),
+        err,
+        raw,
+    })
+}
+
+/// Decodes a hex encoded signature as returned by the `eth_sign` family of RPCs, trimming the
+/// 0x-prefix if present
+fn decode_signature(sig: &str) -> Result<Signature, ProviderError> {
+    let sig = hex::decode(sig.strip_prefix("0x").unwrap_or(sig))?;
+    Signature::try_from(sig.as_slice()).map_err(|e| ProviderError::CustomError(e.to_string()))
+}
+
+/// Returns `true` if the node rejected an `eth_getLogs` query for matching too many logs, e.g.
+/// `query returned more than 10000 results`, `log response size exceeded` or `block range is too
+/// wide`. Rate limited queries are not, as smaller queries would only be throttled as well.
+fn is_log_limit_error(err: &ProviderError) -> bool {
+    if let ProviderError::ResponseTooLarge(_) = err {
+        return true
+    }
+    if err.kind() == RpcErrorKind::RateLimited {
+        return false
+    }
+    err.as_error_response()
+        .map(|err| {
+            let message = err.message.to_lowercase();
+            message.contains("query returned more than") ||
+                message.contains("log response size exceeded") ||
+                message.contains("block range")
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the topic0 of the event with the given canonical or human-readable signature
+fn event_topic(signature: &str) -> Result<H256, ProviderError> {
+    let signature = signature.trim();
+    let declaration = if signature.starts_with("event ") {
+        signature.to_string()
+    } else {
+        format!("event {}", signature)
+    };
+    let event = abi::AbiParser::default()
+        .parse_event(&declaration)
+        .map_err(|e| ProviderError::CustomError(e.to_string()))?;
+    Ok(event.signature())
+}
+
 impl TryFrom<&str> for Provider<HttpProvider> {
     type Error = ParseError;
 
@@ -1314,10 +2516,16 @@ mod tests {
     use super::*;
     use crate::Http;
     use ethers_core::{
+        abi::Token,
         types::{
-            transaction::eip2930::AccessList, Eip1559TransactionRequest, TransactionRequest, H256,
+            transaction::{
+                eip2930::{AccessList, AccessListItem},
+                eip4844::BYTES_PER_BLOB,
+            },
+            BlobSidecar, BlockSummary, Eip1559TransactionRequest, StorageProof, TransactionRequest,
+            Withdrawal, H256,
         },
-        utils::Anvil,
+        utils::{id, keccak256, Anvil},
     };
 
     #[test]
@@ -1427,6 +2635,92 @@ mod tests {
         assert!(!receipts.is_empty());
     }
 
+    #[test]
+    fn deserialization_error_has_context() {
+        let (provider, mock) = Provider::mocked();
+        mock.push::<String, _>("not a number".to_string()).unwrap();
+
+        let err = provider.get_block_number().unwrap_err();
+        match &err {
+            ProviderError::Deserialization { method, raw, .. } => {
+                assert_eq!(method, "eth_blockNumber");
+                assert_eq!(raw, &serde_json::json!("not a number"));
+            }
+            err => panic!("expected a deserialization error, got {:?}", err),
+        }
+        let msg = err.to_string();
+        assert!(msg.contains("eth_blockNumber") && msg.contains("not a number"), "{}", msg);
+    }
+
+    #[test]
+    fn get_filter_logs() {
+        let (provider, mock) = Provider::mocked();
+        let log = Log { address: Address::repeat_byte(1), ..Default::default() };
+
+        mock.push::<Vec<Log>, _>(vec![log.clone()]).unwrap();
+        assert_eq!(provider.get_filter_logs(0x2a).unwrap(), vec![log]);
+        mock.assert_request("eth_getFilterLogs", ["0x2a"]).unwrap();
+    }
+
+    #[test]
+    fn estimate_gas_buffered() {
+        let (provider, mock) = Provider::mocked();
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::zero()).into();
+
+        mock.push(U256::from(21_000)).unwrap();
+        assert_eq!(provider.estimate_gas_buffered(&tx, None, 1.2).unwrap(), 25_200.into());
+        mock.assert_request("eth_estimateGas", [&tx]).unwrap();
+
+        // 21_001 * 1.1 = 23_101.1 is rounded up
+        mock.push(U256::from(21_001)).unwrap();
+        assert_eq!(provider.estimate_gas_buffered(&tx, None, 1.1).unwrap(), 23_102.into());
+
+        mock.push(U256::from(21_000)).unwrap();
+        assert_eq!(provider.estimate_gas_buffered(&tx, None, 1.0).unwrap(), 21_000.into());
+
+        // rejected before estimating the gas
+        for multiplier in [f64::NAN, f64::INFINITY, -1.0, 0.9] {
+            let err = provider.estimate_gas_buffered(&tx, None, multiplier).unwrap_err();
+            assert!(matches!(err, ProviderError::InvalidGasMultiplier(_)));
+        }
+        mock.assert_request("eth_estimateGas", [&tx]).unwrap();
+        mock.assert_request("eth_estimateGas", [&tx]).unwrap();
+        assert!(mock.assert_request("eth_estimateGas", [&tx]).is_err());
+    }
+
+    #[test]
+    fn block_receipts_fallback() {
+        let (provider, mock) = Provider::mocked();
+        let hashes = vec![H256::repeat_byte(1), H256::repeat_byte(2)];
+        let receipts: Vec<TransactionReceipt> = hashes
+            .iter()
+            .map(|hash| TransactionReceipt { transaction_hash: *hash, ..Default::default() })
+            .collect();
+        let block = Block { transactions: hashes.clone(), ..Default::default() };
+        let method_not_found =
+            || JsonRpcError { code: -32601, message: "method not found".to_string(), data: None };
+
+        // without the fallback, the error is returned as-is
+        mock.push_error(method_not_found());
+        assert!(provider.get_block_receipts(1u64).unwrap_err().is_method_not_found());
+        mock.assert_request("eth_getBlockReceipts", [BlockNumber::from(1u64)]).unwrap();
+
+        let provider = provider.block_receipts_fallback(true);
+        mock.push::<TransactionReceipt, _>(&receipts[1]).unwrap();
+        mock.push::<TransactionReceipt, _>(&receipts[0]).unwrap();
+        mock.push::<Block<H256>, _>(&block).unwrap();
+        mock.push_error(method_not_found());
+        mock.push_error(method_not_found());
+
+        assert_eq!(provider.get_block_receipts(1u64).unwrap(), receipts);
+        mock.assert_request("eth_getBlockReceipts", [BlockNumber::from(1u64)]).unwrap();
+        mock.assert_request("parity_getBlockReceipts", [BlockNumber::from(1u64)]).unwrap();
+        mock.assert_request("eth_getBlockByNumber", (BlockNumber::from(1u64), false)).unwrap();
+        for hash in hashes {
+            mock.assert_request("eth_getTransactionReceipt", [hash]).unwrap();
+        }
+    }
+
     #[test]
     #[cfg_attr(feature = "celo", ignore)]
     fn fee_history() {
@@ -1509,7 +2803,35 @@ mod tests {
 
         let res = provider.fill_transaction(&mut tx, None);
 
-        assert!(matches!(res, Err(ProviderError::JsonRpcClientError(_))));
+        assert!(matches!(res, Err(ProviderError::Deserialization { .. })));
+    }
+
+    #[test]
+    fn test_fill_transaction_eip4844() {
+        let (provider, _) = Provider::mocked();
+        let sidecar = BlobSidecar {
+            blobs: vec![vec![0u8; BYTES_PER_BLOB].into()],
+            commitments: vec![vec![0xc0; 48].into()],
+            proofs: vec![vec![0xc0; 48].into()],
+        };
+        let inner = Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(1))
+            .gas(21_000)
+            .max_fee_per_gas(10)
+            .max_priority_fee_per_gas(1);
+        let mut tx: TypedTransaction =
+            Eip4844TransactionRequest::new(inner, 1).blob_sidecar(sidecar.clone()).into();
+
+        // --- commits to the blobs of the sidecar
+        provider.fill_transaction(&mut tx, None).unwrap();
+        let inner = tx.as_eip4844_ref().unwrap();
+        assert_eq!(inner.blob_versioned_hashes, sidecar.versioned_hashes());
+
+        // --- sends the sidecar along with the signed transaction
+        let signature = Signature { r: 1.into(), s: 2.into(), v: 0 };
+        let raw = provider.rlp_signed(&tx, &signature);
+        assert_eq!(raw, inner.rlp_network(&signature));
+        assert!(raw.len() > BYTES_PER_BLOB);
     }
 
     #[test]
@@ -1546,6 +2868,1695 @@ mod tests {
         assert!(tx.access_list().is_none());
     }
 
+    #[test]
+    fn test_fill_transaction_2930() {
+        let (mut provider, mock) = Provider::mocked();
+        provider.from = Some("0x6fC21092DA55B392b045eD78F4732bff3C580e2c".parse().unwrap());
+
+        let gas = U256::from(21000_usize);
+        let gas_price = U256::from(50_usize);
+        let to: Address = "0x0000000000000000000000000000000000000002".parse().unwrap();
+        let access_list: AccessList =
+            vec![AccessListItem { address: to, storage_keys: vec![H256::repeat_byte(1)] }].into();
+
+        // --- leaves the access list empty unless enabled
+        let mut tx: TypedTransaction =
+            TransactionRequest::new().to(to).with_access_list(AccessList::default()).into();
+        mock.push(gas).unwrap();
+        mock.push(gas_price).unwrap();
+        provider.fill_transaction(&mut tx, None).unwrap();
+
+        assert_eq!(tx.gas_price(), Some(gas_price));
+        assert_eq!(tx.access_list(), Some(&Default::default()));
+
+        // --- fills both the gas price and the access list
+        let provider = provider.fill_access_list(true);
+        let mut tx: TypedTransaction =
+            TransactionRequest::new().to(to).with_access_list(AccessList::default()).into();
+        mock.push(gas).unwrap();
+        mock.push(AccessListWithGasUsed { access_list: access_list.clone(), gas_used: gas })
+            .unwrap();
+        mock.push(gas_price).unwrap();
+        provider.fill_transaction(&mut tx, None).unwrap();
+
+        assert_eq!(tx.gas(), Some(&gas));
+        assert_eq!(tx.gas_price(), Some(gas_price));
+        assert_eq!(tx.access_list(), Some(&access_list));
+    }
+
+    #[test]
+    fn get_transaction_status() {
+        let (provider, mock) = Provider::mocked();
+        let hash = H256::repeat_byte(1);
+        let tx = Transaction { hash, ..Default::default() };
+
+        // unknown: neither a receipt nor a transaction
+        mock.push(serde_json::Value::Null).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        assert_eq!(provider.get_transaction_status(hash).unwrap(), TxStatus::Unknown);
+        mock.assert_request("eth_getTransactionReceipt", [hash]).unwrap();
+        mock.assert_request("eth_getTransactionByHash", [hash]).unwrap();
+
+        // pending: a transaction without a receipt
+        mock.push::<Transaction, _>(&tx).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        assert_eq!(provider.get_transaction_status(hash).unwrap(), TxStatus::Pending);
+
+        // mined: the receipt carries the block number
+        let receipt = TransactionReceipt {
+            transaction_hash: hash,
+            block_number: Some(7u64.into()),
+            ..Default::default()
+        };
+        mock.push::<TransactionReceipt, _>(&receipt).unwrap();
+        assert_eq!(provider.get_transaction_status(hash).unwrap(), TxStatus::Mined(7u64.into()));
+    }
+
+    #[test]
+    fn debug_trace_block() {
+        let (provider, mock) = Provider::mocked();
+        let trace = GethTrace { gas: 21_000, ..Default::default() };
+        let response = serde_json::json!([{ "result": trace }, { "result": trace }]);
+        let opts = GethDebugTracingOptions::default();
+
+        mock.push(response.clone()).unwrap();
+        let traces =
+            provider.debug_trace_block_by_number(BlockNumber::Latest, opts.clone()).unwrap();
+        assert_eq!(traces, vec![trace.clone(), trace.clone()]);
+        mock.assert_request("debug_traceBlockByNumber", (BlockNumber::Latest, &opts)).unwrap();
+
+        let hash = H256::repeat_byte(1);
+        mock.push(response).unwrap();
+        let traces = provider.debug_trace_block_by_hash(hash, opts.clone()).unwrap();
+        assert_eq!(traces, vec![trace.clone(), trace]);
+        mock.assert_request("debug_traceBlockByHash", (hash, &opts)).unwrap();
+    }
+
+    #[test]
+    fn default_block() {
+        let (provider, mock) = Provider::mocked();
+        let pinned = BlockNumber::Number(100.into());
+        let provider = provider.with_default_block(pinned);
+        let addr = Address::repeat_byte(1);
+        let tx: TypedTransaction = TransactionRequest::new().to(addr).into();
+
+        mock.push(U256::from(1)).unwrap();
+        provider.get_balance(addr, None).unwrap();
+        mock.assert_request("eth_getBalance", (addr, pinned)).unwrap();
+
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        provider.get_code(addr, None).unwrap();
+        mock.assert_request("eth_getCode", (addr, pinned)).unwrap();
+
+        mock.push(H256::zero()).unwrap();
+        provider.get_storage_at(addr, H256::zero(), None).unwrap();
+        mock.assert_request("eth_getStorageAt", (addr, U256::zero(), pinned)).unwrap();
+
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        provider.call(&tx, None).unwrap();
+        mock.assert_request("eth_call", (&tx, pinned)).unwrap();
+
+        // an explicit block overrides the pinned one
+        mock.push(U256::from(1)).unwrap();
+        provider.get_balance(addr, Some(BlockNumber::Latest.into())).unwrap();
+        mock.assert_request("eth_getBalance", (addr, BlockNumber::Latest)).unwrap();
+    }
+
+    #[test]
+    fn get_account() {
+        let (provider, mock) = Provider::mocked();
+        let addr = Address::repeat_byte(1);
+        let account = Account {
+            balance: 100.into(),
+            nonce: 2.into(),
+            code_hash: H256::repeat_byte(3),
+            storage_root: H256::repeat_byte(4),
+        };
+
+        // natively supported
+        mock.push(account).unwrap();
+        assert_eq!(provider.get_account(addr, None).unwrap(), account);
+        mock.assert_request("eth_getAccount", (addr, BlockNumber::Latest)).unwrap();
+
+        // falls back to `eth_getProof`
+        let proof = EIP1186ProofResponse {
+            address: addr,
+            balance: account.balance,
+            code_hash: account.code_hash,
+            nonce: 2u64.into(),
+            storage_hash: account.storage_root,
+            ..Default::default()
+        };
+        mock.push(proof).unwrap();
+        mock.push_error(JsonRpcError {
+            code: -32601,
+            message: "the method eth_getAccount does not exist/is not available".to_string(),
+            data: None,
+        });
+        assert_eq!(provider.get_account(addr, None).unwrap(), account);
+        mock.assert_request("eth_getAccount", (addr, BlockNumber::Latest)).unwrap();
+        let no_slots: Vec<H256> = vec![];
+        mock.assert_request("eth_getProof", (addr, no_slots, BlockNumber::Latest)).unwrap();
+    }
+
+    #[test]
+    fn watch_transaction() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.interval(Duration::from_millis(1));
+        let hash = H256::repeat_byte(1);
+        let receipt = TransactionReceipt {
+            transaction_hash: hash,
+            block_number: Some(10u64.into()),
+            ..Default::default()
+        };
+
+        // the transaction is pending for one poll, then mined in block 10 while the chain
+        // advances, stalling at block 11 for one poll
+        for latest in [12u64, 11, 11, 10] {
+            mock.push(U64::from(latest)).unwrap();
+            mock.push::<TransactionReceipt, _>(&receipt).unwrap();
+        }
+        mock.push(serde_json::Value::Null).unwrap();
+
+        let confirmations =
+            provider.watch_transaction(hash, 3).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(confirmations, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn is_contract() {
+        let (provider, mock) = Provider::mocked();
+        let addr: Address = "0x0000000000000000000000000000000000000001".parse().unwrap();
+
+        // externally owned account
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        assert!(!provider.is_contract(addr, None).unwrap());
+        mock.assert_request("eth_getCode", (addr, BlockNumber::Latest)).unwrap();
+
+        // contract
+        let code: Bytes = "0x6080604052".parse().unwrap();
+        mock.push::<Bytes, _>(code).unwrap();
+        assert!(provider.is_contract(addr, None).unwrap());
+
+        mock.push::<Bytes, _>("0x6080604052".parse::<Bytes>().unwrap()).unwrap();
+        let block = Some(BlockNumber::Number(5.into()).into());
+        assert_eq!(provider.code_size(addr, block).unwrap(), 5);
+    }
+
+    #[test]
+    fn sign_typed_data_rpc() {
+        let (provider, mock) = Provider::mocked();
+        let from: Address = "0x0000000000000000000000000000000000000001".parse().unwrap();
+        let typed_data = serde_json::json!({
+            "types": {
+                "EIP712Domain": [{ "name": "name", "type": "string" }],
+                "Mail": [{ "name": "contents", "type": "string" }]
+            },
+            "primaryType": "Mail",
+            "domain": { "name": "Ether Mail" },
+            "message": { "contents": "Hello, Bob!" }
+        });
+
+        let sig = Signature { r: 1.into(), s: 2.into(), v: 27 };
+        mock.push::<String, _>(format!("0x{}", sig)).unwrap();
+
+        assert_eq!(provider.sign_typed_data_rpc(from, &typed_data).unwrap(), sig);
+        mock.assert_request("eth_signTypedData_v4", (from, typed_data)).unwrap();
+    }
+
+    #[test]
+    fn personal_sign() {
+        let (provider, mock) = Provider::mocked();
+        let from: Address = "0x0000000000000000000000000000000000000001".parse().unwrap();
+        let data = Bytes::from(b"hello".to_vec());
+
+        let sig = Signature { r: 1.into(), s: 2.into(), v: 27 };
+        mock.push::<String, _>(format!("0x{}", sig)).unwrap();
+        assert_eq!(provider.personal_sign(data.clone(), from).unwrap(), sig);
+        mock.assert_request("personal_sign", (&data, from)).unwrap();
+
+        mock.push(from).unwrap();
+        assert_eq!(provider.personal_ec_recover(data.clone(), &sig).unwrap(), from);
+        mock.assert_request("personal_ecRecover", (&data, format!("0x{}", sig))).unwrap();
+    }
+
+    #[test]
+    fn parses_node_client_versions() {
+        let geth = "Geth/v1.13.5-stable/linux-amd64/go1.21".parse::<NodeClientVersion>().unwrap();
+        assert_eq!(
+            geth,
+            NodeClientVersion {
+                client: NodeClient::Geth,
+                version: "1.13.5-stable".parse().unwrap(),
+                os: "linux-amd64".to_string(),
+                lang: "go1.21".to_string(),
+            }
+        );
+
+        let named = "Geth/my-node/v1.10.26-stable-e5eb32ac/linux-amd64/go1.18.5"
+            .parse::<NodeClientVersion>()
+            .unwrap();
+        assert_eq!(named.version, "1.10.26-stable-e5eb32ac".parse().unwrap());
+        assert_eq!(named.os, "linux-amd64");
+
+        let erigon = "erigon/2.48.1/linux-amd64/go1.20.5".parse::<NodeClientVersion>().unwrap();
+        assert_eq!(erigon.client, NodeClient::Erigon);
+        assert_eq!(erigon.version, semver::Version::new(2, 48, 1));
+        assert_eq!(erigon.lang, "go1.20.5");
+
+        let nethermind = "Nethermind/v1.21.0+bb9b72c0/linux-x64/dotnet7.0.11"
+            .parse::<NodeClientVersion>()
+            .unwrap();
+        assert_eq!(nethermind.client, NodeClient::Nethermind);
+        assert_eq!(nethermind.version, "1.21.0+bb9b72c0".parse().unwrap());
+        assert_eq!(nethermind.os, "linux-x64");
+        assert_eq!(nethermind.lang, "dotnet7.0.11");
+
+        let besu =
+            "besu/v23.4.1/linux-x86_64/openjdk-java-17".parse::<NodeClientVersion>().unwrap();
+        assert_eq!(besu.client, NodeClient::Besu);
+        assert_eq!(besu.version, semver::Version::new(23, 4, 1));
+        assert_eq!(besu.os, "linux-x86_64");
+        assert_eq!(besu.lang, "openjdk-java-17");
+
+        assert!(matches!(
+            "Geth/unknown".parse::<NodeClientVersion>(),
+            Err(ProviderError::InvalidClientVersion(_))
+        ));
+        assert!(matches!(
+            "reth/v0.1.0/linux".parse::<NodeClientVersion>(),
+            Err(ProviderError::UnsupportedNodeClient)
+        ));
+    }
+
+    #[test]
+    fn get_block_withdrawals() {
+        let (provider, mock) = Provider::mocked();
+
+        // a post-Shanghai block, trimmed down to a single withdrawal
+        let block = serde_json::json!({
+            "hash": "0xe22c56f211f03baadcc91e4eb9a24344e6848c5df4473988f893b58223f5216c",
+            "parentHash": "0xe2c0d1b4a3b9eb6f5ac3b6f4b8f2c6e1d7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2",
+            "number": "0x103f576",
+            "timestamp": "0x6437b1ab",
+            "baseFeePerGas": "0x5a9e8f4c1",
+            "transactions": [],
+            "withdrawalsRoot": "0x7a4ecf19774d15cf9c15adf0dd8e8a250c128b26c9e2ab2a08d6c9c8ffbd104f",
+            "withdrawals": [{
+                "index": "0x0",
+                "validatorIndex": "0x3e1bd",
+                "address": "0x8cf6a4d3b4b38e5b5b1f1a5e5b6f0e1f4c2a8b11",
+                "amount": "0xdb42e"
+            }]
+        });
+        mock.push(block).unwrap();
+
+        let withdrawals = provider.get_block_withdrawals(17034870u64).unwrap().unwrap();
+        assert_eq!(
+            withdrawals,
+            vec![Withdrawal {
+                index: 0u64.into(),
+                validator_index: 254397u64.into(),
+                address: "0x8cf6a4d3b4b38e5b5b1f1a5e5b6f0e1f4c2a8b11".parse().unwrap(),
+                amount: 898094u64.into(),
+            }]
+        );
+        mock.assert_request("eth_getBlockByNumber", (BlockNumber::from(17034870u64), false))
+            .unwrap();
+
+        // pre-Shanghai blocks have no withdrawals
+        mock.push(serde_json::json!({ "number": "0x1", "transactions": [] })).unwrap();
+        assert_eq!(provider.get_block_withdrawals(1u64).unwrap(), None);
+    }
+
+    #[test]
+    fn mining_info() {
+        let (provider, mock) = Provider::mocked();
+        let coinbase = Address::repeat_byte(1);
+
+        mock.push(U256::from(0x38a)).unwrap();
+        mock.push(true).unwrap();
+        mock.push(coinbase).unwrap();
+
+        assert_eq!(provider.get_coinbase().unwrap(), coinbase);
+        mock.assert_request("eth_coinbase", ()).unwrap();
+        assert!(provider.get_mining().unwrap());
+        mock.assert_request("eth_mining", ()).unwrap();
+        assert_eq!(provider.get_hashrate().unwrap(), 906.into());
+        mock.assert_request("eth_hashrate", ()).unwrap();
+    }
+
+    #[test]
+    fn web3_sha3() {
+        let (provider, mock) = Provider::mocked();
+        let data = Bytes::from(b"hello world".to_vec());
+        let hash = H256::from(keccak256(&data));
+
+        mock.push::<String, _>(format!("{:?}", hash)).unwrap();
+        assert_eq!(provider.web3_sha3(data.clone()).unwrap(), hash);
+        mock.assert_request("web3_sha3", [data]).unwrap();
+    }
+
+    #[test]
+    fn net_status() {
+        let (provider, mock) = Provider::mocked();
+
+        mock.push(false).unwrap();
+        mock.push::<String, _>("0x19".to_string()).unwrap();
+
+        assert_eq!(provider.net_peer_count().unwrap(), 25.into());
+        mock.assert_request("net_peerCount", ()).unwrap();
+        assert!(!provider.net_listening().unwrap());
+        mock.assert_request("net_listening", ()).unwrap();
+    }
+
+    #[test]
+    fn without_ens() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.without_ens();
+
+        let err = provider.resolve_name("vitalik.eth").unwrap_err();
+        assert!(matches!(err, ProviderError::EnsError(ref msg) if msg == "ENS disabled"));
+        let tx: TypedTransaction = TransactionRequest::new().to("vitalik.eth").into();
+        assert!(matches!(provider.call(&tx, None), Err(ProviderError::EnsError(_))));
+        assert!(matches!(
+            provider.lookup_address(Address::repeat_byte(1)),
+            Err(ProviderError::EnsError(_))
+        ));
+        // no request reached the node
+        assert!(mock.assert_request("eth_call", ()).is_err());
+
+        // addresses are unaffected
+        mock.push::<Bytes, Bytes>(vec![1].into()).unwrap();
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::repeat_byte(1)).into();
+        assert_eq!(provider.call(&tx, None).unwrap(), Bytes::from(vec![1]));
+    }
+
+    #[test]
+    fn ens_retries() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.with_ens_retries(2);
+
+        let name = "vitalik.eth";
+        let resolver = Address::repeat_byte(0x10);
+        let addr = Address::repeat_byte(0x20);
+        // the registry call fails once before the resolution goes through
+        mock.push::<Bytes, Bytes>(abi::encode(&[Token::Address(addr)]).into()).unwrap();
+        mock.push::<Bytes, Bytes>(abi::encode(&[Token::Bool(true)]).into()).unwrap();
+        mock.push::<Bytes, Bytes>(abi::encode(&[Token::Address(resolver)]).into()).unwrap();
+        mock.push_error(JsonRpcError {
+            code: -32005,
+            message: "rate limited".to_string(),
+            data: None,
+        });
+
+        assert_eq!(provider.resolve_name(name).unwrap(), addr);
+        let get_resolver: TypedTransaction = ens::get_resolver(ens::ENS_ADDRESS, name).into();
+        mock.assert_request("eth_call", (&get_resolver, BlockNumber::Latest)).unwrap();
+        mock.assert_request("eth_call", (&get_resolver, BlockNumber::Latest)).unwrap();
+
+        // JSON-RPC errors other than rate limiting are returned without retrying
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.with_ens_retries(2);
+        mock.push_error(JsonRpcError {
+            code: -32602,
+            message: "invalid argument".to_string(),
+            data: None,
+        });
+        let err = provider.resolve_name(name).unwrap_err();
+        assert!(matches!(err, ProviderError::JsonRpcClientError(_)), "{:?}", err);
+        mock.assert_request("eth_call", (&get_resolver, BlockNumber::Latest)).unwrap();
+        assert!(mock.assert_request("eth_call", ()).is_err());
+
+        // logical failures are returned without retrying
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.with_ens_retries(2);
+        let no_resolver = abi::encode(&[Token::Address(Address::zero())]);
+        mock.push::<Bytes, Bytes>(no_resolver.into()).unwrap();
+        assert!(matches!(provider.resolve_name(name), Err(ProviderError::EnsError(_))));
+        mock.assert_request("eth_call", (&get_resolver, BlockNumber::Latest)).unwrap();
+        assert!(mock.assert_request("eth_call", ()).is_err());
+    }
+
+    #[test]
+    fn call_revert_data() {
+        let (provider, mock) = Provider::mocked();
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::repeat_byte(1)).into();
+
+        // `Error(string)` with the reason "nope"
+        let revert =
+            [&id("Error(string)")[..], &abi::encode(&[Token::String("nope".into())])].concat();
+        mock.push_error(JsonRpcError {
+            code: 3,
+            message: "execution reverted: nope".to_string(),
+            data: Some(serde_json::json!(Bytes::from(revert.clone()))),
+        });
+        match provider.call(&tx, None) {
+            Err(ProviderError::Revert(data)) => assert_eq!(data.as_ref(), revert.as_slice()),
+            res => panic!("expected a revert, got {:?}", res),
+        }
+
+        // errors without revert data are returned as-is
+        mock.push_error(JsonRpcError {
+            code: -32000,
+            message: "out of gas".to_string(),
+            data: None,
+        });
+        let err = provider.call(&tx, None).unwrap_err();
+        assert_eq!(err.as_error_response().unwrap().message, "out of gas");
+    }
+
+    #[test]
+    fn estimate_gas_revert_reason() {
+        let (provider, mock) = Provider::mocked();
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::repeat_byte(1)).into();
+
+        let revert =
+            [&id("Error(string)")[..], &abi::encode(&[Token::String("too low".into())])].concat();
+        mock.push_error(JsonRpcError {
+            code: 3,
+            message: "execution reverted: too low".to_string(),
+            data: Some(serde_json::json!(Bytes::from(revert.clone()))),
+        });
+        let err = provider.estimate_gas(&tx, None).unwrap_err();
+        assert!(matches!(err, ProviderError::Revert(ref data) if data.as_ref() == revert));
+        assert_eq!(err.revert_reason().as_deref(), Some("too low"));
+
+        // custom errors carry no reason string
+        mock.push_error(JsonRpcError {
+            code: 3,
+            message: "execution reverted".to_string(),
+            data: Some(serde_json::json!(Bytes::from(id("Unauthorized()").to_vec()))),
+        });
+        let err = provider.estimate_gas(&tx, None).unwrap_err();
+        assert!(matches!(err, ProviderError::Revert(_)));
+        assert_eq!(err.revert_reason(), None);
+    }
+
+    #[test]
+    fn get_block_logs() {
+        let (provider, mock) = Provider::mocked();
+        let hash = H256::repeat_byte(1);
+        let log = Log { block_hash: Some(hash), ..Default::default() };
+
+        mock.push::<Vec<Log>, _>(vec![log.clone()]).unwrap();
+        assert_eq!(provider.get_block_logs(hash).unwrap(), vec![log]);
+
+        let filter = Filter::new().at_block_hash(hash);
+        let request = serde_json::to_value(&filter).unwrap();
+        assert_eq!(request["blockHash"], serde_json::json!(hash));
+        assert!(request.get("fromBlock").is_none());
+        assert!(request.get("toBlock").is_none());
+        mock.assert_request("eth_getLogs", [request]).unwrap();
+    }
+
+    #[test]
+    fn watch_pending_logs() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.interval(Duration::from_millis(1));
+        let filter = Filter::new().address(Address::repeat_byte(1));
+
+        let log = |index: u64| Log {
+            address: Address::repeat_byte(1),
+            transaction_hash: Some(H256::repeat_byte(2)),
+            log_index: Some(index.into()),
+            ..Default::default()
+        };
+
+        // the first log is returned by both polls but only yielded once
+        mock.push::<Vec<Log>, _>(vec![log(0), log(1)]).unwrap();
+        mock.push::<Vec<Log>, _>(vec![log(0)]).unwrap();
+
+        let logs: Vec<_> =
+            provider.watch_pending_logs(&filter).take(2).collect::<Result<_, _>>().unwrap();
+        assert_eq!(logs, vec![log(0), log(1)]);
+
+        let pending = filter.from_block(BlockNumber::Pending).to_block(BlockNumber::Pending);
+        let request = serde_json::to_value(&pending).unwrap();
+        assert_eq!(request["fromBlock"], "pending");
+        assert_eq!(request["toBlock"], "pending");
+        mock.assert_request("eth_getLogs", [&pending]).unwrap();
+        mock.assert_request("eth_getLogs", [&pending]).unwrap();
+    }
+
+    #[test]
+    fn watch_storage() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.interval(Duration::from_millis(1));
+        let oracle = Address::repeat_byte(1);
+        let slot = H256::from_low_u64_be(3);
+        let price = H256::from_low_u64_be;
+
+        // the slot is read at every new block, and only read again once a block was mined
+        let polls = [(10u64, Some(5)), (11, Some(5)), (11, None), (12, Some(7)), (13, Some(9))];
+        for (block, value) in polls.iter().rev() {
+            if let Some(value) = value {
+                mock.push(price(*value)).unwrap();
+            }
+            mock.push(U64::from(*block)).unwrap();
+        }
+
+        // the initial value and the unchanged value at block 11 are not yielded
+        let changes: Vec<_> =
+            provider.watch_storage(oracle, slot).take(2).collect::<Result<_, _>>().unwrap();
+        assert_eq!(changes, vec![(12.into(), price(7)), (13.into(), price(9))]);
+
+        for (block, value) in polls {
+            mock.assert_request("eth_blockNumber", ()).unwrap();
+            if value.is_some() {
+                let block = format!("{:#x}", block);
+                mock.assert_request("eth_getStorageAt", (oracle, U256::from(3), block)).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn watch_code() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.interval(Duration::from_millis(1));
+        let address = Address::repeat_byte(1);
+        let v1 = Bytes::from(vec![0x60, 0x01]);
+        let v2 = Bytes::from(vec![0x60, 0x02]);
+        let destroyed = Bytes::default();
+
+        // the code is replaced at block 12, and the contract self-destructs at block 14
+        let polls = [
+            (10u64, v1.clone()),
+            (11, v1),
+            (12, v2.clone()),
+            (13, v2.clone()),
+            (14, destroyed.clone()),
+        ];
+        for (block, code) in polls.iter().rev() {
+            mock.push::<Bytes, _>(code.clone()).unwrap();
+            mock.push(U64::from(*block)).unwrap();
+        }
+
+        let changes: Vec<_> =
+            provider.watch_code(address).take(2).collect::<Result<_, _>>().unwrap();
+        assert_eq!(changes, vec![(12.into(), v2), (14.into(), destroyed)]);
+
+        for (block, _) in polls {
+            mock.assert_request("eth_blockNumber", ()).unwrap();
+            mock.assert_request("eth_getCode", (address, format!("{:#x}", block))).unwrap();
+        }
+    }
+
+    #[test]
+    fn get_uncle() {
+        let (provider, mock) = Provider::mocked();
+
+        // uncles are returned with their full header, but without transactions
+        let uncle = serde_json::json!({
+            "difficulty": "0xb9e274f7969f5",
+            "extraData": "0x476574682f76312e302e302f6c696e75782f676f312e342e32",
+            "gasLimit": "0x2fefd8",
+            "gasUsed": "0x0",
+            "hash": "0x2225c92c2b5a1daeb8cb5dea2f7bec6c02cfa2c8c33d3c11e2d6d81e6c6a1b3c",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "miner": "0xea674fdde714fd979de3edf0f56aa9716b898ec8",
+            "mixHash": "0x2a9ed8be42b0a2f4e58e3de7a4c1dbc7c7a6d1a7e5b5c08d3f3cd0f9a7e0c1b2",
+            "nonce": "0x17a6cb3e5e5a4bf8",
+            "number": "0xf4629",
+            "parentHash": "0x77ee1c1e6c9ef1d8b4d1d6a0d7e3b0a09b3b7d5ef7a9c0e3e1b6d0c8a5f9e2d1",
+            "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "size": "0x21b",
+            "stateRoot": "0x0f1c1b0d3c5e4a0f2c9b8e7d6c5b4a39281706f5e4d3c2b1a09f8e7d6c5b4a39",
+            "timestamp": "0x56bfb41a",
+            "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "uncles": []
+        });
+        mock.push(uncle).unwrap();
+
+        let hash = H256::repeat_byte(1);
+        let uncle = provider.get_uncle(hash, 0.into()).unwrap().unwrap();
+        assert_eq!(uncle.number, Some(1_001_001.into()));
+        let miner: Address = "0xea674fdde714fd979de3edf0f56aa9716b898ec8".parse().unwrap();
+        assert_eq!(uncle.author, Some(miner));
+        assert_eq!(uncle.difficulty, U256::from(0xb9e274f7969f5u64));
+        assert_eq!(uncle.gas_limit, U256::from(0x2fefd8));
+        assert_eq!(uncle.extra_data.as_ref(), b"Geth/v1.0.0/linux/go1.4.2");
+        assert!(uncle.transactions.is_empty());
+        assert!(uncle.other.is_empty());
+        mock.assert_request("eth_getUncleByBlockHashAndIndex", (hash, U64::zero())).unwrap();
+    }
+
+    #[test]
+    fn get_pending_and_earliest_block() {
+        let (provider, mock) = Provider::mocked();
+
+        // the pending block is not sealed yet, so it has no hash, nonce or miner
+        let pending = serde_json::json!({
+            "difficulty": "0x0",
+            "extraData": "0x",
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x5208",
+            "hash": null,
+            "logsBloom": null,
+            "miner": null,
+            "mixHash": null,
+            "nonce": null,
+            "number": "0x10",
+            "parentHash": "0x77ee1c1e6c9ef1d8b4d1d6a0d7e3b0a09b3b7d5ef7a9c0e3e1b6d0c8a5f9e2d1",
+            "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "size": "0x21b",
+            "stateRoot": "0x0f1c1b0d3c5e4a0f2c9b8e7d6c5b4a39281706f5e4d3c2b1a09f8e7d6c5b4a39",
+            "timestamp": "0x56bfb41a",
+            "totalDifficulty": null,
+            "transactions": [],
+            "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "uncles": []
+        });
+        let mut genesis = pending.clone();
+        genesis["hash"] = serde_json::json!(H256::repeat_byte(1));
+        genesis["number"] = serde_json::json!("0x0");
+        genesis["nonce"] = serde_json::json!("0x0000000000000042");
+        genesis["miner"] = serde_json::json!(Address::zero());
+        mock.push(genesis).unwrap();
+        mock.push(pending).unwrap();
+
+        let block = provider.get_block(BlockNumber::Pending).unwrap().unwrap();
+        assert_eq!(block.hash, None);
+        assert_eq!(block.nonce, None);
+        assert_eq!(block.author, None);
+        assert_eq!(block.number, Some(16.into()));
+        assert_eq!(block.gas_used, 21_000.into());
+
+        let block = provider.get_block_with_txs(BlockNumber::Earliest).unwrap().unwrap();
+        assert_eq!(block.hash, Some(H256::repeat_byte(1)));
+        assert_eq!(block.number, Some(0.into()));
+        assert_eq!(block.author, Some(Address::zero()));
+
+        mock.assert_request("eth_getBlockByNumber", ("pending", false)).unwrap();
+        mock.assert_request("eth_getBlockByNumber", ("earliest", true)).unwrap();
+    }
+
+    #[test]
+    fn finalized_and_safe_block_tags() {
+        let (provider, mock) = Provider::mocked();
+        let addr = Address::repeat_byte(1);
+
+        mock.push(U256::from(3)).unwrap();
+        mock.push::<Option<Block<H256>>, _>(None).unwrap();
+        let block = Block::<H256> { number: Some(12.into()), ..Default::default() };
+        mock.push(Some(block)).unwrap();
+
+        let block = provider.get_finalized_block().unwrap().unwrap();
+        assert_eq!(block.number, Some(12.into()));
+        assert!(provider.get_block(BlockNumber::Safe).unwrap().is_none());
+        let nonce = provider.get_transaction_count(addr, Some(BlockNumber::Safe.into())).unwrap();
+        assert_eq!(nonce, 3.into());
+
+        mock.assert_request("eth_getBlockByNumber", ("finalized", false)).unwrap();
+        mock.assert_request("eth_getBlockByNumber", ("safe", false)).unwrap();
+        mock.assert_request("eth_getTransactionCount", (addr, "safe")).unwrap();
+    }
+
+    #[test]
+    fn trace_transaction_decoded() {
+        let (provider, mock) = Provider::mocked();
+        let abi = abi::parse_abi(&["function transfer(address to, uint256 amount)"]).unwrap();
+        let (to, amount) = (Address::repeat_byte(2), U256::from(100));
+        let args = [Token::Address(to), Token::Uint(amount)];
+        let input = abi.function("transfer").unwrap().encode_input(&args).unwrap();
+
+        let trace = |input: Bytes| {
+            serde_json::json!({
+                "action": {
+                    "callType": "call",
+                    "from": Address::repeat_byte(1),
+                    "gas": "0x5208",
+                    "input": input,
+                    "to": Address::repeat_byte(3),
+                    "value": "0x0"
+                },
+                "blockHash": H256::repeat_byte(4),
+                "blockNumber": 10,
+                "result": { "gasUsed": "0x5208", "output": "0x" },
+                "subtraces": 0,
+                "traceAddress": [],
+                "transactionHash": H256::repeat_byte(5),
+                "transactionPosition": 0,
+                "type": "call"
+            })
+        };
+        let unknown = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        mock.push(serde_json::json!([trace(input.into()), trace(unknown)])).unwrap();
+
+        let traces = provider.trace_transaction_decoded(H256::repeat_byte(5), &abi).unwrap();
+        assert_eq!(traces.len(), 2);
+        let call = traces[0].call.as_ref().unwrap();
+        assert_eq!(call.name, "transfer");
+        assert_eq!(call.args, args);
+        assert_eq!(traces[1].call, None);
+        mock.assert_request("trace_transaction", [H256::repeat_byte(5)]).unwrap();
+    }
+
+    #[test]
+    fn custom_tx_serializer() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // encodes every transaction as an Optimism deposit transaction
+        #[derive(Debug, Default)]
+        struct DepositSerializer(Arc<AtomicUsize>);
+
+        impl TransactionSerializer for DepositSerializer {
+            fn serialize_tx(&self, tx: &TypedTransaction) -> Option<serde_json::Value> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                let mut json = utils::serialize(tx);
+                json["type"] = "0x7e".into();
+                json["sourceHash"] = serde_json::json!(H256::repeat_byte(7));
+                Some(json)
+            }
+
+            fn rlp_signed(&self, tx: &TypedTransaction, _: &Signature) -> Option<Bytes> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Some([&[0x7e][..], &tx.rlp()].concat().into())
+            }
+        }
+
+        let serializer = DepositSerializer::default();
+        let calls = serializer.0.clone();
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.with_tx_serializer(serializer);
+        let tx: TypedTransaction = TransactionRequest::new().to(Address::zero()).into();
+
+        mock.push::<Bytes, Bytes>(vec![1].into()).unwrap();
+        mock.push(U256::from(21_000)).unwrap();
+        mock.push::<Bytes, Bytes>(vec![2].into()).unwrap();
+        provider.call(&tx, None).unwrap();
+        provider.estimate_gas(&tx, None).unwrap();
+        provider.call_raw(&tx).poll().unwrap();
+
+        let mut expected = utils::serialize(&tx);
+        expected["type"] = "0x7e".into();
+        expected["sourceHash"] = serde_json::json!(H256::repeat_byte(7));
+        mock.assert_request("eth_call", (&expected, "latest")).unwrap();
+        mock.assert_request("eth_estimateGas", [&expected]).unwrap();
+        mock.assert_request("eth_call", (&expected, "latest")).unwrap();
+
+        let signature = Signature { r: 1.into(), s: 2.into(), v: 27 };
+        assert_eq!(provider.rlp_signed(&tx, &signature)[0], 0x7e);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn pending_transactions_from() {
+        let (provider, mock) = Provider::mocked();
+        let (sender, other) = (Address::repeat_byte(1), Address::repeat_byte(2));
+
+        let tx = |from: Address, nonce: u64| {
+            serde_json::json!({
+                "blockHash": null,
+                "blockNumber": null,
+                "from": from,
+                "gas": "0x5208",
+                "gasPrice": "0x3b9aca00",
+                "hash": H256::from_low_u64_be(nonce),
+                "input": "0x",
+                "nonce": U256::from(nonce),
+                "to": Address::zero(),
+                "transactionIndex": null,
+                "value": "0x0",
+                "type": "0x2",
+                "maxFeePerGas": "0x3b9aca00",
+                "maxPriorityFeePerGas": "0x1",
+                "v": "0x1",
+                "r": "0x1",
+                "s": "0x2"
+            })
+        };
+        let (sender_key, other_key) = (format!("{:?}", sender), format!("{:?}", other));
+        mock.push(serde_json::json!({
+            "pending": {
+                sender_key.clone(): { "10": tx(sender, 10), "8": tx(sender, 8), "9": tx(sender, 9) },
+                other_key: { "8": tx(other, 8) }
+            },
+            "queued": {
+                sender_key: { "12": tx(sender, 12) }
+            }
+        }))
+        .unwrap();
+
+        let txs = provider.pending_transactions_from(sender).unwrap();
+        let nonces: Vec<_> = txs.iter().map(|tx| tx.nonce.as_u64()).collect();
+        assert_eq!(nonces, vec![8, 9, 10, 12]);
+        assert!(txs.iter().all(|tx| tx.from == sender && tx.max_fee_per_gas.is_some()));
+        mock.assert_request("txpool_content", ()).unwrap();
+    }
+
+    #[test]
+    fn get_transaction_with_confirmations() {
+        let (provider, mock) = Provider::mocked();
+        let hash = H256::repeat_byte(1);
+        let tx = |block_number: Option<u64>| Transaction {
+            hash,
+            block_number: block_number.map(Into::into),
+            ..Default::default()
+        };
+
+        mock.push(U64::from(105)).unwrap();
+        mock.push(tx(Some(100))).unwrap();
+        let (fetched, confirmations) =
+            provider.get_transaction_with_confirmations(hash).unwrap().unwrap();
+        assert_eq!(fetched, tx(Some(100)));
+        assert_eq!(confirmations, 6);
+        mock.assert_request("eth_getTransactionByHash", [hash]).unwrap();
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+
+        // a pending transaction has no confirmations, without asking for the tip
+        mock.push(tx(None)).unwrap();
+        let (_, confirmations) =
+            provider.get_transaction_with_confirmations(hash).unwrap().unwrap();
+        assert_eq!(confirmations, 0);
+
+        mock.push(serde_json::Value::Null).unwrap();
+        assert_eq!(provider.get_transaction_with_confirmations(hash).unwrap(), None);
+    }
+
+    #[test]
+    fn predict_base_fee() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(serde_json::json!({
+            "oldestBlock": "0x64",
+            "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00", "0x3b9aca00"],
+            "gasUsedRatio": [1.0, 1.0],
+        }))
+        .unwrap();
+        let base_fee = provider.predict_base_fee(2).unwrap();
+        assert_eq!(base_fee, Some(U256::from(1_125_000_000u64)));
+        let params = (U256::from(10), "latest", Vec::<f64>::new());
+        mock.assert_request("eth_feeHistory", params).unwrap();
+    }
+
+    #[test]
+    fn simulate_bundle() {
+        let (provider, mock) = Provider::mocked();
+        let token = Address::repeat_byte(1);
+        let approve: TypedTransaction = TransactionRequest::new().to(token).data(vec![1]).into();
+        let transfer: TypedTransaction = TransactionRequest::new().to(token).data(vec![2]).into();
+        let slot = H256::from_low_u64_be(7);
+        let allowance = H256::from_low_u64_be(100);
+
+        mock.push::<Bytes, Bytes>(vec![1].into()).unwrap();
+        mock.push(serde_json::json!({
+            "pre": { format!("{:?}", token): { "balance": "0x0" } },
+            "post": { format!("{:?}", token): { "storage": { format!("{:?}", slot): allowance } } }
+        }))
+        .unwrap();
+        mock.push::<Bytes, Bytes>(vec![].into()).unwrap();
+
+        let results = provider.simulate_bundle(&[approve.clone(), transfer.clone()], None).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &Bytes::from(vec![]));
+        assert_eq!(results[1].as_ref().unwrap(), &Bytes::from(vec![1]));
+
+        // the transfer is executed on top of the approval's state changes
+        let unchanged = call_raw::spoof::state();
+        let approved = call_raw::spoof::storage(token, slot, allowance);
+        mock.assert_request("eth_call", (&approve, "latest", &unchanged)).unwrap();
+        let options = serde_json::json!({
+            "tracer": "prestateTracer",
+            "tracerConfig": { "diffMode": true },
+            "stateOverrides": {},
+        });
+        mock.assert_request("debug_traceCall", (&approve, "latest", options)).unwrap();
+        mock.assert_request("eth_call", (&transfer, "latest", &approved)).unwrap();
+
+        // a reverted transaction leaves the state unchanged and is not traced
+        mock.push::<Bytes, Bytes>(vec![1].into()).unwrap();
+        mock.push_error(JsonRpcError {
+            code: 3,
+            message: "execution reverted".to_string(),
+            data: Some("0xdead".into()),
+        });
+        let results = provider.simulate_bundle(&[approve.clone(), transfer.clone()], None).unwrap();
+        assert!(matches!(results[0], Err(ProviderError::Revert(_))));
+        assert_eq!(results[1].as_ref().unwrap(), &Bytes::from(vec![1]));
+        mock.assert_request("eth_call", (&approve, "latest", &unchanged)).unwrap();
+        mock.assert_request("eth_call", (&transfer, "latest", &unchanged)).unwrap();
+    }
+
+    #[test]
+    fn trace_call_many_decoded() {
+        let (provider, mock) = Provider::mocked();
+        let token = Address::repeat_byte(1);
+        let owner = Address::repeat_byte(2);
+        let parser = || abi::AbiParser::default();
+        let balance_of =
+            parser().parse_function("function balanceOf(address) returns (uint256)").unwrap();
+        let transfer =
+            parser().parse_function("function transfer(address,uint256) returns (bool)").unwrap();
+
+        let balance_of_tx: TypedTransaction = TransactionRequest::new()
+            .to(token)
+            .data(balance_of.encode_input(&[Token::Address(owner)]).unwrap())
+            .into();
+        let transfer_args = [Token::Address(owner), Token::Uint(2000.into())];
+        let transfer_tx: TypedTransaction = TransactionRequest::new()
+            .to(token)
+            .data(transfer.encode_input(&transfer_args).unwrap())
+            .into();
+
+        // `Error(string)` with the reason "insufficient balance"
+        let revert = [
+            &id("Error(string)")[..],
+            &abi::encode(&[Token::String("insufficient balance".into())]),
+        ]
+        .concat();
+        let call_trace = |output: Option<Vec<u8>>| {
+            let mut trace = serde_json::json!({
+                "action": {
+                    "callType": "call",
+                    "from": Address::zero(),
+                    "gas": "0x1c9c380",
+                    "input": "0x",
+                    "to": token,
+                    "value": "0x0"
+                },
+                "subtraces": 0,
+                "traceAddress": [],
+                "type": "call"
+            });
+            match output {
+                Some(output) => {
+                    trace["result"] =
+                        serde_json::json!({ "gasUsed": "0x9c4", "output": Bytes::from(output) })
+                }
+                None => trace["error"] = "Reverted".into(),
+            }
+            trace
+        };
+        let balance = abi::encode(&[Token::Uint(1000.into())]);
+        mock.push(serde_json::json!([
+            {
+                "output": Bytes::from(balance.clone()),
+                "stateDiff": null,
+                "trace": [call_trace(Some(balance))],
+                "vmTrace": null
+            },
+            {
+                "output": Bytes::from(revert.clone()),
+                "stateDiff": null,
+                "trace": [call_trace(None)],
+                "vmTrace": null
+            }
+        ]))
+        .unwrap();
+
+        let results = provider
+            .trace_call_many_decoded(
+                vec![(balance_of_tx.clone(), &balance_of), (transfer_tx.clone(), &transfer)],
+                None,
+            )
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &vec![Token::Uint(1000.into())]);
+        let err = results[1].as_ref().unwrap_err();
+        assert_eq!(err.revert_reason(), Some("insufficient balance".to_string()));
+
+        let calls = serde_json::json!([[balance_of_tx, ["trace"]], [transfer_tx, ["trace"]]]);
+        mock.assert_request("trace_callMany", (calls, "latest")).unwrap();
+    }
+
+    #[test]
+    fn prepare_transaction() {
+        let (provider, mock) = Provider::mocked();
+        let from = Address::repeat_byte(1);
+        let to = Address::repeat_byte(2);
+        let tx: TypedTransaction =
+            TransactionRequest::new().from(from).to(to).value(utils::WEI_IN_ETHER).into();
+
+        mock.push(U256::from(3)).unwrap();
+        mock.push(U256::from(21_000)).unwrap();
+        mock.push(U256::from(2_000_000_000u64)).unwrap();
+
+        let prepared = provider.prepare_transaction(&tx).unwrap();
+        mock.assert_request("eth_gasPrice", ()).unwrap();
+        let mut estimated = tx.clone();
+        estimated.set_gas_price(2_000_000_000u64);
+        mock.assert_request("eth_estimateGas", [&estimated]).unwrap();
+        mock.assert_request("eth_getTransactionCount", (from, "pending")).unwrap();
+
+        assert_eq!(prepared.tx.nonce(), Some(&U256::from(3)));
+        assert_eq!(prepared.tx.gas(), Some(&U256::from(21_000)));
+        assert_eq!(prepared.from, Some(from));
+        assert_eq!(prepared.to, Some(to));
+        assert_eq!(prepared.value, utils::WEI_IN_ETHER);
+        assert_eq!(prepared.nonce, Some(U256::from(3)));
+        assert_eq!(prepared.gas, U256::from(21_000));
+        assert_eq!(prepared.gas_price, Some(U256::from(2_000_000_000u64)));
+        assert_eq!(prepared.max_fee_per_gas, None);
+        assert_eq!(
+            prepared.max_total_cost,
+            utils::WEI_IN_ETHER + U256::from(21_000u64 * 2_000_000_000)
+        );
+
+        let summary = prepared.to_string();
+        assert!(summary.contains("value:     1.000000000000000000 ETH"), "{}", summary);
+        assert!(summary.contains("nonce:     3"), "{}", summary);
+        assert!(summary.contains("gas price: 2.000000000 gwei"), "{}", summary);
+        assert!(summary.contains("max cost:  1.000042000000000000 ETH"), "{}", summary);
+    }
+
+    #[test]
+    fn estimate_total_cost() {
+        let (provider, mock) = Provider::mocked();
+        let to = Address::repeat_byte(2);
+        let gwei = |n: u64| U256::from(n) * U256::exp10(9);
+
+        // the max fee is paid at most, whatever the priority fee
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(to)
+            .value(1000)
+            .gas(50_000)
+            .max_fee_per_gas(gwei(30))
+            .max_priority_fee_per_gas(gwei(2))
+            .into();
+        let cost = provider.estimate_total_cost(&tx).unwrap();
+        assert_eq!(cost, U256::from(50_000) * gwei(30) + 1000);
+        assert!(mock.assert_request("eth_estimateGas", [&tx]).is_err());
+
+        // the gas and gas price are filled if missing
+        let tx: TypedTransaction = TransactionRequest::new().to(to).value(1000).into();
+        mock.push(U256::from(21_000)).unwrap();
+        mock.push(gwei(20)).unwrap();
+        let cost = provider.estimate_total_cost(&tx).unwrap();
+        assert_eq!(cost, U256::from(21_000) * gwei(20) + 1000);
+        mock.assert_request("eth_gasPrice", ()).unwrap();
+    }
+
+    #[test]
+    fn get_logs_split() {
+        let (provider, mock) = Provider::mocked();
+        let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let log = |address: Address, block: u64, log_index: u64| Log {
+            address,
+            block_number: Some(block.into()),
+            log_index: Some(log_index.into()),
+            ..Default::default()
+        };
+        let too_many = || JsonRpcError {
+            code: -32005,
+            message: "query returned more than 10000 results".to_string(),
+            data: None,
+        };
+
+        mock.push::<Vec<Log>, _>(vec![log(a, 11, 0)]).unwrap();
+        mock.push::<Vec<Log>, _>(vec![log(b, 10, 1)]).unwrap();
+        mock.push::<Vec<Log>, _>(vec![log(a, 10, 0), log(a, 10, 2)]).unwrap();
+        mock.push_error(too_many());
+        mock.push_error(too_many());
+
+        let filter = Filter::new().address(vec![a, b]).from_block(10u64).to_block(11u64);
+        let logs = provider.get_logs_split(&filter).unwrap();
+        let expected = vec![log(a, 10, 0), log(b, 10, 1), log(a, 10, 2), log(a, 11, 0)];
+        assert_eq!(logs, expected);
+
+        mock.assert_request("eth_getLogs", [&filter]).unwrap();
+        let block_10 = filter.clone().from_block(10u64).to_block(10u64);
+        mock.assert_request("eth_getLogs", [&block_10]).unwrap();
+        mock.assert_request("eth_getLogs", [block_10.clone().address(vec![a])]).unwrap();
+        mock.assert_request("eth_getLogs", [block_10.address(vec![b])]).unwrap();
+        mock.assert_request("eth_getLogs", [filter.from_block(11u64).to_block(11u64)]).unwrap();
+
+        // a single address in a single block can not be split further
+        mock.push_error(too_many());
+        let filter = Filter::new().address(a).from_block(10u64).to_block(10u64);
+        assert!(is_log_limit_error(&provider.get_logs_split(&filter).unwrap_err()));
+        mock.assert_request("eth_getLogs", [&filter]).unwrap();
+
+        // rate limited queries are returned as is instead of being split
+        mock.push_error(JsonRpcError {
+            code: -32005,
+            message: "daily request count exceeded, request rate limited".to_string(),
+            data: None,
+        });
+        let filter = Filter::new().address(vec![a, b]).from_block(10u64).to_block(11u64);
+        let err = provider.get_logs_split(&filter).unwrap_err();
+        assert_eq!(err.kind(), RpcErrorKind::RateLimited);
+        mock.assert_request("eth_getLogs", [&filter]).unwrap();
+        mock.assert_request("eth_getLogs", [&filter]).unwrap_err();
+    }
+
+    #[test]
+    fn error_kind() {
+        #[derive(Debug)]
+        struct CustomClassifier;
+
+        impl ErrorClassifier for CustomClassifier {
+            fn classify(&self, err: &JsonRpcError) -> RpcErrorKind {
+                if err.code == -39001 {
+                    return RpcErrorKind::RateLimited
+                }
+                DefaultErrorClassifier.classify(err)
+            }
+        }
+
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.with_error_classifier(CustomClassifier);
+        mock.push_error(JsonRpcError {
+            code: -39001,
+            message: "slow down".to_string(),
+            data: None,
+        });
+        mock.push_error(JsonRpcError {
+            code: -32000,
+            message: "nonce too low".to_string(),
+            data: None,
+        });
+
+        let err = provider.get_gas_price().unwrap_err();
+        assert_eq!(err.kind(), RpcErrorKind::NonceTooLow);
+        assert_eq!(provider.error_kind(&err), RpcErrorKind::NonceTooLow);
+
+        let err = provider.get_gas_price().unwrap_err();
+        assert_eq!(err.kind(), RpcErrorKind::RateLimited);
+        assert_eq!(err.kind_with(&DefaultErrorClassifier), RpcErrorKind::Other);
+        assert_eq!(provider.error_kind(&err), RpcErrorKind::RateLimited);
+        assert_eq!(err.as_error_response().unwrap().message, "slow down");
+
+        assert_eq!(ProviderError::Timeout.kind(), RpcErrorKind::Other);
+    }
+
+    #[test]
+    fn call_with_gas() {
+        let (provider, mock) = Provider::mocked();
+        let tx: TypedTransaction =
+            TransactionRequest::new().to(Address::repeat_byte(1)).data(vec![1, 2]).into();
+        let trace = |failed: bool, return_value: &str| {
+            serde_json::json!({
+                "failed": failed,
+                "gas": 21_234,
+                "returnValue": return_value,
+                "structLogs": []
+            })
+        };
+
+        mock.push(trace(false, "0000000000000000000000000000000000000000000000000000000000000007"))
+            .unwrap();
+        let (data, gas) = provider.call_with_gas(&tx, None).unwrap();
+        assert_eq!(U256::from_big_endian(&data), U256::from(7));
+        assert_eq!(gas, U256::from(21_234));
+        let options =
+            serde_json::json!({ "disableStorage": true, "disableStack": true, "limit": 1 });
+        mock.assert_request("debug_traceCall", (&tx, "latest", &options)).unwrap();
+
+        // the revert data of a failed call is surfaced like by `call`
+        mock.push(trace(true, "deadbeef")).unwrap();
+        let err = provider.call_with_gas(&tx, None).unwrap_err();
+        assert!(
+            matches!(err, ProviderError::Revert(ref data) if data[..] == [0xde, 0xad, 0xbe, 0xef])
+        );
+        mock.assert_request("debug_traceCall", (&tx, "latest", &options)).unwrap();
+
+        // nodes without the debug namespace are sent a call and a gas estimate
+        mock.push(U256::from(30_000)).unwrap();
+        mock.push::<Bytes, Bytes>(vec![7].into()).unwrap();
+        mock.push_error(JsonRpcError {
+            code: -32601,
+            message: "the method debug_traceCall does not exist/is not available".to_string(),
+            data: None,
+        });
+        let (data, gas) = provider.call_with_gas(&tx, None).unwrap();
+        assert_eq!(data, Bytes::from(vec![7]));
+        assert_eq!(gas, U256::from(30_000));
+        mock.assert_request("debug_traceCall", (&tx, "latest", &options)).unwrap();
+        mock.assert_request("eth_call", (&tx, "latest")).unwrap();
+        mock.assert_request("eth_estimateGas", [&tx]).unwrap();
+    }
+
+    #[test]
+    fn get_chainid_decimal_or_quantity() {
+        let (provider, mock) = Provider::mocked();
+        for id in [serde_json::json!("0x1"), serde_json::json!("1"), serde_json::json!(1)] {
+            mock.push(id).unwrap();
+            assert_eq!(provider.get_chainid().unwrap(), U256::one());
+        }
+        mock.push(serde_json::json!("0x89")).unwrap();
+        assert_eq!(provider.get_chainid().unwrap(), U256::from(137));
+        mock.push(serde_json::json!("137")).unwrap();
+        assert_eq!(provider.get_chainid().unwrap(), U256::from(137));
+
+        mock.push(serde_json::json!("mainnet")).unwrap();
+        let err = provider.get_chainid().unwrap_err();
+        assert!(matches!(err, ProviderError::Deserialization { .. }), "{:?}", err);
+    }
+
+    #[test]
+    fn get_logs_for_event() {
+        let transfer: H256 =
+            "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef".parse().unwrap();
+        assert_eq!(event_topic("Transfer(address,address,uint256)").unwrap(), transfer);
+        assert_eq!(
+            event_topic("event Transfer(address indexed from, address indexed to, uint256 value)")
+                .unwrap(),
+            transfer
+        );
+        assert!(event_topic("Transfer(address,").is_err());
+
+        let (provider, mock) = Provider::mocked();
+        let address = Address::repeat_byte(1);
+        mock.push::<Vec<Log>, _>(vec![]).unwrap();
+        let logs = provider
+            .get_logs_for_event(address, "Transfer(address,address,uint256)", 100u64, 200u64)
+            .unwrap();
+        assert!(logs.is_empty());
+        let filter =
+            Filter::new().address(address).topic0(transfer).from_block(100u64).to_block(200u64);
+        mock.assert_request("eth_getLogs", [filter]).unwrap();
+    }
+
+    #[test]
+    fn detect_interval() {
+        let (mut provider, mock) = Provider::mocked();
+        let block = |number: u64, timestamp: u64| Block::<TxHash> {
+            number: Some(number.into()),
+            timestamp: timestamp.into(),
+            ..Default::default()
+        };
+        // 2 second blocks
+        mock.push(block(90, 1_000)).unwrap();
+        mock.push(block(100, 1_020)).unwrap();
+
+        assert_eq!(provider.detect_interval().unwrap(), Duration::from_millis(500));
+        assert_eq!(provider.get_interval(), Duration::from_millis(500));
+        mock.assert_request("eth_getBlockByNumber", ("latest", false)).unwrap();
+        mock.assert_request("eth_getBlockByNumber", (BlockNumber::from(90u64), false)).unwrap();
+
+        // several blocks per second, with the same timestamp
+        let (mut provider, mock) = Provider::mocked();
+        mock.push(block(90, 1_000)).unwrap();
+        mock.push(block(100, 1_000)).unwrap();
+        assert_eq!(provider.detect_interval().unwrap(), Duration::from_millis(100));
+
+        // young chains are sampled since genesis
+        let (mut provider, mock) = Provider::mocked();
+        mock.push(block(0, 1_000)).unwrap();
+        mock.push(block(4, 1_048)).unwrap();
+        assert_eq!(provider.detect_interval().unwrap(), Duration::from_secs(3));
+        mock.assert_request("eth_getBlockByNumber", ("latest", false)).unwrap();
+        mock.assert_request("eth_getBlockByNumber", (BlockNumber::from(0u64), false)).unwrap();
+
+        // an explicitly set interval is not replaced
+        let (provider, _mock) = Provider::mocked();
+        let mut provider = provider.interval(Duration::from_secs(1));
+        assert_eq!(provider.detect_interval().unwrap(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn get_raw_transaction() {
+        let (provider, mock) = Provider::mocked();
+        let hash: H256 =
+            "0x929ff27a5c7833953df23103c4eb55ebdfb698678139d751c51932163877fada".parse().unwrap();
+        let raw = Bytes::from_str("0xf8aa808512ec276caf83010e2b94dac17f958d2ee523a2206206994597c13d831ec780b844a9059cbb000000000000000000000000fdae129ecc2c27d166a3131098bc05d143fa258e0000000000000000000000000000000000000000000000000000000002faf08025a0c81e70f9e49e0d3b854720143e86d172fecc9e76ef8a8666f2fdc017017c5141a01dd3410180f6a6ca3e25ad3058789cd0df3321ed76b5b4dbe0a2bb2dc28ae274").unwrap();
+
+        mock.push(Some(raw.clone())).unwrap();
+        assert_eq!(provider.get_raw_transaction(hash).unwrap(), Some(raw.clone()));
+        mock.assert_request("eth_getRawTransactionByHash", [hash]).unwrap();
+
+        // nodes without the method get the transaction re-encoded
+        mock.push(serde_json::json!({
+            "blockHash": null,
+            "blockNumber": null,
+            "from": "0xc26ad91f4e7a0cad84c4b9315f420ca9217e315d",
+            "gas": "0x10e2b",
+            "gasPrice": "0x12ec276caf",
+            "hash": hash,
+            "input": "0xa9059cbb000000000000000000000000fdae129ecc2c27d166a3131098bc05d143fa258e0000000000000000000000000000000000000000000000000000000002faf080",
+            "nonce": "0x0",
+            "to": "0xdac17f958d2ee523a2206206994597c13d831ec7",
+            "transactionIndex": null,
+            "value": "0x0",
+            "type": "0x0",
+            "v": "0x25",
+            "r": "0xc81e70f9e49e0d3b854720143e86d172fecc9e76ef8a8666f2fdc017017c5141",
+            "s": "0x1dd3410180f6a6ca3e25ad3058789cd0df3321ed76b5b4dbe0a2bb2dc28ae274",
+            "chainId": "0x1"
+        }))
+        .unwrap();
+        mock.push_error(JsonRpcError {
+            code: -32601,
+            message: "the method eth_getRawTransactionByHash does not exist".to_string(),
+            data: None,
+        });
+        let reconstructed = provider.get_raw_transaction(hash).unwrap().unwrap();
+        assert_eq!(reconstructed, raw);
+        assert_eq!(H256::from(keccak256(&reconstructed)), hash);
+        mock.assert_request("eth_getRawTransactionByHash", [hash]).unwrap();
+        mock.assert_request("eth_getTransactionByHash", [hash]).unwrap();
+    }
+
+    #[test]
+    fn block_number_cache() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.with_block_number_cache(Duration::from_millis(100));
+
+        mock.push(U64::from(11)).unwrap();
+        mock.push(U64::from(10)).unwrap();
+        for _ in 0..3 {
+            assert_eq!(provider.get_block_number().unwrap(), 10.into());
+        }
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        assert!(mock.assert_request("eth_blockNumber", ()).is_err());
+
+        // the block number is fetched again once it expired
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(provider.get_block_number().unwrap(), 11.into());
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+    }
+
+    #[test]
+    fn request_coalescing() {
+        // holds the requests long enough for the identical ones to pile up behind them
+        #[derive(Clone, Debug)]
+        struct SlowClient(MockProvider);
+
+        impl JsonRpcClient for SlowClient {
+            type Error = MockError;
+
+            fn request<T, R>(&self, method: &str, params: T) -> Result<R, MockError>
+            where
+                T: Debug + Serialize + Send + Sync,
+                R: DeserializeOwned,
+            {
+                thread::sleep(Duration::from_millis(200));
+                self.0.request(method, params)
+            }
+        }
+
+        let mock = MockProvider::new();
+        let provider = Provider::new(SlowClient(mock.clone())).with_request_coalescing();
+        let block = Block::<H256> { number: Some(10.into()), ..Default::default() };
+        mock.push(Some(block.clone())).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let provider = provider.clone();
+                thread::spawn(move || provider.get_block(10).unwrap())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), Some(block.clone()));
+        }
+        mock.assert_request("eth_getBlockByNumber", ("0xa", false)).unwrap();
+        assert!(mock.assert_request("eth_getBlockByNumber", ("0xa", false)).is_err());
+
+        // requests made after the response arrived are sent again
+        mock.push(Some(block.clone())).unwrap();
+        assert_eq!(provider.get_block(10).unwrap(), Some(block));
+        mock.assert_request("eth_getBlockByNumber", ("0xa", false)).unwrap();
+    }
+
+    #[test]
+    fn get_block_summary() {
+        let (provider, mock) = Provider::mocked();
+        let block = Block::<H256> {
+            number: Some(10.into()),
+            hash: Some(H256::repeat_byte(1)),
+            timestamp: 1_700_000_000.into(),
+            transactions: vec![H256::repeat_byte(2), H256::repeat_byte(3)],
+            gas_used: 42_000.into(),
+            base_fee_per_gas: Some(7.into()),
+            ..Default::default()
+        };
+        mock.push(Some(block)).unwrap();
+
+        let summary = provider.get_block_summary(10).unwrap().unwrap();
+        assert_eq!(
+            summary,
+            BlockSummary {
+                number: Some(10.into()),
+                hash: Some(H256::repeat_byte(1)),
+                timestamp: 1_700_000_000.into(),
+                tx_count: 2,
+                gas_used: 42_000.into(),
+                base_fee: Some(7.into()),
+            }
+        );
+        // the transactions are not requested in full
+        mock.assert_request("eth_getBlockByNumber", ("0xa", false)).unwrap();
+
+        mock.push::<Option<Block<H256>>, _>(None).unwrap();
+        assert_eq!(provider.get_block_summary(11).unwrap(), None);
+    }
+
+    #[test]
+    fn get_blocks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // answers with the blocks up to 15, tracking how many requests are in flight
+        #[derive(Debug, Default)]
+        struct BlockClient {
+            in_flight: AtomicUsize,
+            max_in_flight: AtomicUsize,
+        }
+
+        impl JsonRpcClient for BlockClient {
+            type Error = MockError;
+
+            fn request<T, R>(&self, method: &str, params: T) -> Result<R, MockError>
+            where
+                T: Debug + Serialize + Send + Sync,
+                R: DeserializeOwned,
+            {
+                assert_eq!(method, "eth_getBlockByNumber");
+                let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(50));
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                let (number, _): (U64, bool) =
+                    serde_json::from_value(serde_json::to_value(params)?)?;
+                let block = (number <= 15.into())
+                    .then(|| Block::<H256> { number: Some(number), ..Default::default() });
+                Ok(serde_json::from_value(serde_json::to_value(block)?)?)
+            }
+        }
+
+        let client = Arc::new(BlockClient::default());
+        let provider = Provider::new(client.clone());
+
+        let blocks = provider.get_blocks(10..=17, 3).unwrap();
+        let numbers: Vec<_> =
+            blocks.iter().map(|block| block.as_ref().and_then(|block| block.number)).collect();
+        let mut expected: Vec<_> = (10u64..=15).map(|number| Some(number.into())).collect();
+        expected.extend([None, None]);
+        assert_eq!(numbers, expected);
+
+        // the blocks are fetched concurrently, but no more than 3 at a time
+        let max_in_flight = client.max_in_flight.load(Ordering::SeqCst);
+        assert!(max_in_flight > 1 && max_in_flight <= 3, "{}", max_in_flight);
+    }
+
+    #[test]
+    fn pending_and_confirmed_nonce() {
+        let (provider, mock) = Provider::mocked();
+        let addr = Address::repeat_byte(1);
+
+        mock.push(U256::from(5)).unwrap();
+        mock.push(U256::from(7)).unwrap();
+        assert_eq!(provider.pending_nonce(addr).unwrap(), 7.into());
+        assert_eq!(provider.confirmed_nonce(addr).unwrap(), 5.into());
+        mock.assert_request("eth_getTransactionCount", (addr, "pending")).unwrap();
+        mock.assert_request("eth_getTransactionCount", (addr, "latest")).unwrap();
+    }
+
+    #[test]
+    fn create_access_list_report() {
+        let (provider, mock) = Provider::mocked();
+        let from = Address::repeat_byte(1);
+        let to = Address::repeat_byte(2);
+        let token = Address::repeat_byte(3);
+        let tx: TypedTransaction = TransactionRequest::new().from(from).to(to).into();
+
+        let access_list = AccessList(vec![
+            AccessListItem { address: to, storage_keys: vec![H256::zero()] },
+            AccessListItem {
+                address: token,
+                storage_keys: vec![H256::zero(), H256::repeat_byte(1)],
+            },
+        ]);
+        mock.push(AccessListWithGasUsed { access_list, gas_used: 50_000.into() }).unwrap();
+
+        let report = provider.create_access_list_report(&tx, None).unwrap();
+        assert!(!report.entries[0].cold);
+        assert_eq!(report.entries[0].gas_saved, -2300);
+        assert!(report.entries[1].cold);
+        assert_eq!(report.entries[1].gas_saved, 300);
+        assert_eq!(report.gas_saved, -2000);
+        mock.assert_request("eth_createAccessList", (&tx, BlockNumber::Latest)).unwrap();
+    }
+
+    #[test]
+    fn wait_for_code() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.interval(Duration::from_millis(1));
+        let addr = Address::repeat_byte(1);
+
+        // the deployment lands on the second poll
+        mock.push::<Bytes, _>(Bytes::from(vec![0xfe])).unwrap();
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        let code = provider.wait_for_code(addr, Duration::from_secs(1)).unwrap();
+        assert_eq!(code, Some(Bytes::from(vec![0xfe])));
+        mock.assert_request("eth_getCode", (addr, BlockNumber::Latest)).unwrap();
+        mock.assert_request("eth_getCode", (addr, BlockNumber::Latest)).unwrap();
+
+        mock.push::<Bytes, _>(Bytes::default()).unwrap();
+        assert_eq!(provider.wait_for_code(addr, Duration::ZERO).unwrap(), None);
+    }
+
+    #[test]
+    fn get_storage_at_many() {
+        let (provider, mock) = Provider::mocked();
+        let addr = Address::repeat_byte(1);
+        let slots = vec![H256::from_low_u64_be(2), H256::zero(), H256::from_low_u64_be(1)];
+
+        let storage_proof = slots
+            .iter()
+            .zip(10u64..)
+            .map(|(slot, value)| StorageProof { key: *slot, proof: vec![], value: value.into() })
+            .collect();
+        let proof = EIP1186ProofResponse { address: addr, storage_proof, ..Default::default() };
+        mock.push(proof).unwrap();
+
+        let values = provider.get_storage_at_many(addr, slots.clone(), None).unwrap();
+        assert_eq!(
+            values,
+            vec![H256::from_low_u64_be(10), H256::from_low_u64_be(11), H256::from_low_u64_be(12)]
+        );
+        mock.assert_request("eth_getProof", (addr, slots, BlockNumber::Latest)).unwrap();
+        assert!(mock.assert_request("eth_getStorageAt", ()).is_err());
+    }
+
+    #[test]
+    fn get_proof_at_hash() {
+        let (provider, mock) = Provider::mocked();
+        let addr = Address::repeat_byte(1);
+        let slots = vec![H256::zero()];
+        let hash = H256::repeat_byte(2);
+        let state_root = H256::repeat_byte(3);
+
+        let proof = EIP1186ProofResponse { address: addr, ..Default::default() };
+        mock.push(proof.clone()).unwrap();
+        mock.push(Block::<H256> { hash: Some(hash), state_root, ..Default::default() }).unwrap();
+
+        let res = provider.get_proof_at_hash(addr, slots.clone(), hash).unwrap();
+        assert_eq!(res, Some((proof, state_root)));
+        mock.assert_request("eth_getBlockByHash", (hash, false)).unwrap();
+        let pinned = serde_json::json!({ "blockHash": format!("{:?}", hash) });
+        mock.assert_request("eth_getProof", (addr, slots.clone(), pinned)).unwrap();
+
+        // no proof is requested for an unknown block
+        mock.push::<Option<Block<H256>>, _>(None).unwrap();
+        assert_eq!(provider.get_proof_at_hash(addr, slots, hash).unwrap(), None);
+        mock.assert_request("eth_getBlockByHash", (hash, false)).unwrap();
+        assert!(mock.assert_request("eth_getProof", ()).is_err());
+    }
+
+    #[test]
+    fn get_balance_history() {
+        let (provider, mock) = Provider::mocked();
+        let addr = Address::repeat_byte(1);
+        let blocks = [10u64.into(), 20u64.into(), BlockNumber::Latest];
+
+        mock.push::<[U256; 3], _>([U256::from(100), U256::from(200), U256::from(300)]).unwrap();
+
+        let balances = provider.get_balance_history(addr, &blocks).unwrap();
+        assert_eq!(balances, vec![100.into(), 200.into(), 300.into()]);
+        // a single batch of requests was sent
+        let params: Vec<_> = blocks.iter().map(|block| (addr, block)).collect();
+        mock.assert_request("eth_getBalance", params).unwrap();
+        mock.assert_request("eth_getBalance", ()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg(feature = "tracing-spans")]
+    #[tracing_test::traced_test]
+    fn request_spans() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U64::from(1)).unwrap();
+        mock.push(U256::from(1)).unwrap();
+
+        provider.get_chainid().unwrap();
+        provider.get_block_number().unwrap();
+
+        assert!(logs_contain(r#"request{method="eth_chainId" params_size=4}"#));
+        assert!(logs_contain(r#"request{method="eth_blockNumber" params_size=4}"#));
+        assert!(logs_contain("duration_ms="));
+    }
+
+    #[test]
+    fn wait_for_receipt() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.interval(Duration::from_millis(1));
+        let tx_hash = H256::repeat_byte(1);
+        let tx = Transaction { hash: tx_hash, ..Default::default() };
+        let receipt = TransactionReceipt {
+            transaction_hash: tx_hash,
+            block_number: Some(10u64.into()),
+            ..Default::default()
+        };
+        let timeout = Duration::from_secs(60);
+
+        // pending first, then mined with two confirmations
+        mock.push(U64::from(11)).unwrap();
+        mock.push::<TransactionReceipt, _>(&receipt).unwrap();
+        mock.push::<Transaction, _>(&tx).unwrap();
+        mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+        assert_eq!(provider.wait_for_receipt(tx_hash, timeout, 2).unwrap(), Some(receipt.clone()));
+
+        // still pending at the deadline
+        mock.push::<Transaction, _>(&tx).unwrap();
+        mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+        let err = provider.wait_for_receipt(tx_hash, Duration::ZERO, 1).unwrap_err();
+        assert!(matches!(err, ProviderError::Timeout));
+
+        // dropped from the mempool after being pending
+        mock.push::<Option<Transaction>, _>(None).unwrap();
+        mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+        mock.push::<Transaction, _>(&tx).unwrap();
+        mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+        assert_eq!(provider.wait_for_receipt(tx_hash, timeout, 1).unwrap(), None);
+
+        // a receipt without a block number is pending, not mined in block 0
+        let pending = TransactionReceipt { block_number: None, ..receipt.clone() };
+        mock.push(U64::from(10)).unwrap();
+        mock.push::<TransactionReceipt, _>(&receipt).unwrap();
+        mock.push::<TransactionReceipt, _>(&pending).unwrap();
+        assert_eq!(provider.wait_for_receipt(tx_hash, timeout, 1).unwrap(), Some(receipt));
+    }
+
+    #[test]
+    fn get_transaction_logs() {
+        let (provider, mock) = Provider::mocked();
+        let tx_hash = H256::repeat_byte(1);
+        let logs: Vec<Log> = (0u64..2)
+            .map(|i| Log {
+                address: Address::repeat_byte(2),
+                topics: vec![H256::from_low_u64_be(i)],
+                transaction_hash: Some(tx_hash),
+                log_index: Some(i.into()),
+                ..Default::default()
+            })
+            .collect();
+        let receipt = TransactionReceipt {
+            transaction_hash: tx_hash,
+            logs: logs.clone(),
+            ..Default::default()
+        };
+
+        mock.push::<TransactionReceipt, _>(&receipt).unwrap();
+        assert_eq!(provider.get_transaction_logs(tx_hash).unwrap(), logs);
+        mock.assert_request("eth_getTransactionReceipt", [tx_hash]).unwrap();
+
+        // not mined yet
+        mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+        assert!(provider.get_transaction_logs(tx_hash).unwrap().is_empty());
+    }
+
     #[test]
     fn mainnet_lookup_address_invalid_resolver() {
         let provider = crate::MAINNET.provider();
@@ -0,0 +1,153 @@
+use crate::JsonRpcError;
+use std::{error::Error, fmt};
+
+/// Normalized cause of a JSON-RPC error, see [`ProviderError::kind`](crate::ProviderError::kind)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RpcErrorKind {
+    /// The transaction's nonce was already used by a mined transaction of the sender
+    NonceTooLow,
+    /// The sender cannot pay for the transaction's value and gas
+    InsufficientFunds,
+    /// The transaction's gas limit is below its intrinsic gas
+    GasTooLow,
+    /// The node or its operator throttled the request
+    RateLimited,
+    /// The transaction is already in the node's mempool
+    AlreadyKnown,
+    /// Any other error
+    Other,
+}
+
+/// Maps the JSON-RPC errors of a node to an [`RpcErrorKind`]. Nodes phrase the same condition
+/// differently, e.g. Geth's `nonce too low` is Nethermind's `OldNonce`, so providers returning
+/// other messages can be supported by registering a classifier with
+/// [`Provider::with_error_classifier`](crate::Provider::with_error_classifier).
+pub trait ErrorClassifier: fmt::Debug + Send + Sync {
+    fn classify(&self, err: &JsonRpcError) -> RpcErrorKind;
+}
+
+/// Classifies the errors of Geth, Erigon, Nethermind, Besu and OpenEthereum, as well as the
+/// EIP-1474 `limit exceeded` error used by hosted providers for rate limiting
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultErrorClassifier;
+
+impl ErrorClassifier for DefaultErrorClassifier {
+    fn classify(&self, err: &JsonRpcError) -> RpcErrorKind {
+        // hosted providers mirror the HTTP status in the code. The EIP-1474 `-32005` code is also
+        // used for limits unrelated to throttling, e.g. Infura's `query returned more than 10000
+        // results`, so such errors are classified by their message instead.
+        if err.code == 429 {
+            return RpcErrorKind::RateLimited
+        }
+
+        // ignore case and separators, so `OldNonce` matches `old nonce`
+        let message: String = err
+            .message
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        let matches = |patterns: &[&str]| patterns.iter().any(|p| message.contains(p));
+
+        if matches(&["noncetoolow", "nonceistoolow", "oldnonce"]) {
+            RpcErrorKind::NonceTooLow
+        } else if matches(&["insufficientfunds"]) {
+            RpcErrorKind::InsufficientFunds
+        } else if matches(&["gastoolow", "gasistoolow", "intrinsicgas"]) {
+            RpcErrorKind::GasTooLow
+        } else if matches(&["alreadyknown", "knowntransaction", "alreadyimported"]) {
+            RpcErrorKind::AlreadyKnown
+        } else if matches(&[
+            "ratelimit",
+            "rateexceeded",
+            "toomanyrequests",
+            "requestcountexceeded",
+            "limitexceeded",
+        ]) {
+            RpcErrorKind::RateLimited
+        } else {
+            RpcErrorKind::Other
+        }
+    }
+}
+
+/// Error of a transport along with its kind as classified by the provider's [`ErrorClassifier`],
+/// which [`ProviderError::kind`](crate::ProviderError::kind) returns instead of classifying the
+/// error again with the [`DefaultErrorClassifier`]
+#[derive(Debug)]
+pub(crate) struct ClassifiedError {
+    pub(crate) kind: RpcErrorKind,
+    pub(crate) source: Box<dyn Error + Send + Sync>,
+}
+
+impl fmt::Display for ClassifiedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl Error for ClassifiedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.source()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(code: i64, message: &str) -> RpcErrorKind {
+        let err = JsonRpcError { code, message: message.to_string(), data: None };
+        DefaultErrorClassifier.classify(&err)
+    }
+
+    #[test]
+    fn classifies_node_phrasings() {
+        let cases = [
+            // Geth and Erigon
+            (-32000, "nonce too low", RpcErrorKind::NonceTooLow),
+            (-32000, "nonce too low: address 0x01, tx: 2 state: 3", RpcErrorKind::NonceTooLow),
+            (
+                -32000,
+                "insufficient funds for gas * price + value: balance 0, tx cost 1",
+                RpcErrorKind::InsufficientFunds,
+            ),
+            (-32000, "intrinsic gas too low", RpcErrorKind::GasTooLow),
+            (-32000, "already known", RpcErrorKind::AlreadyKnown),
+            (-32000, "known transaction: 0xabcd", RpcErrorKind::AlreadyKnown),
+            // Nethermind
+            (-32010, "OldNonce", RpcErrorKind::NonceTooLow),
+            (-32010, "InsufficientFunds, Balance is zero", RpcErrorKind::InsufficientFunds),
+            (-32010, "GasLimitBelowIntrinsicGas", RpcErrorKind::GasTooLow),
+            (-32010, "AlreadyKnown", RpcErrorKind::AlreadyKnown),
+            // OpenEthereum and Besu
+            (
+                -32010,
+                "Transaction nonce is too low. Try incrementing the nonce.",
+                RpcErrorKind::NonceTooLow,
+            ),
+            (
+                -32010,
+                "Transaction with the same hash was already imported.",
+                RpcErrorKind::AlreadyKnown,
+            ),
+            (-32004, "Transaction gas is too low.", RpcErrorKind::GasTooLow),
+            // hosted providers
+            (-32005, "daily request count exceeded", RpcErrorKind::RateLimited),
+            (-32005, "project ID request rate exceeded", RpcErrorKind::RateLimited),
+            (-32005, "limit exceeded", RpcErrorKind::RateLimited),
+            (-32005, "query returned more than 10000 results", RpcErrorKind::Other),
+            (429, "Too Many Requests", RpcErrorKind::RateLimited),
+            (
+                -32000,
+                "your app has exceeded its compute units per second capacity, rate limit",
+                RpcErrorKind::RateLimited,
+            ),
+            (-32000, "execution reverted", RpcErrorKind::Other),
+            (-32601, "the method eth_foo does not exist", RpcErrorKind::Other),
+        ];
+        for (code, message, kind) in cases {
+            assert_eq!(classify(code, message), kind, "{}", message);
+        }
+    }
+}
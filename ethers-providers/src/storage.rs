@@ -0,0 +1,57 @@
+use super::{JsonRpcClient, Middleware, Provider, ProviderError};
+use ethers_core::types::{Address, H256, U64};
+use std::thread;
+
+/// Iterator over the changes of a storage slot, see [`Provider::watch_storage`].
+///
+/// Every call to `next` polls the block number at the provider's interval, and reads the slot at
+/// every new block until its value differs from the one read at the previous block. The value at
+/// the block the watcher starts at is not yielded, it is what later values are compared to. The
+/// iterator never ends on its own.
+pub struct StorageWatcher<'a, P> {
+    provider: &'a Provider<P>,
+    address: Address,
+    slot: H256,
+    block: Option<U64>,
+    value: Option<H256>,
+}
+
+impl<'a, P> StorageWatcher<'a, P>
+where
+    P: JsonRpcClient,
+{
+    pub fn new(provider: &'a Provider<P>, address: Address, slot: H256) -> Self {
+        Self { provider, address, slot, block: None, value: None }
+    }
+
+    /// Reads the slot if a new block was mined, and returns its value if it changed
+    fn poll(&mut self) -> Result<Option<(U64, H256)>, ProviderError> {
+        let latest = self.provider.get_block_number()?;
+        if self.block.map_or(false, |block| latest <= block) {
+            return Ok(None)
+        }
+        let value = self.provider.get_storage_at(self.address, self.slot, Some(latest.into()))?;
+        self.block = Some(latest);
+        Ok(match self.value.replace(value) {
+            Some(previous) if previous != value => Some((latest, value)),
+            _ => None,
+        })
+    }
+}
+
+impl<'a, P> Iterator for StorageWatcher<'a, P>
+where
+    P: JsonRpcClient,
+{
+    type Item = Result<(U64, H256), ProviderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.poll() {
+                Ok(Some(change)) => return Some(Ok(change)),
+                Ok(None) => thread::sleep(self.provider.get_interval()),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
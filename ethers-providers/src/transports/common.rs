@@ -8,9 +8,9 @@ use serde::{
 use serde_json::{value::RawValue, Value};
 use thiserror::Error;
 
-use ethers_core::types::U256;
+use ethers_core::types::{Bytes, U256};
 
-#[derive(Deserialize, Debug, Clone, Error)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Error)]
 /// A JSON-RPC 2.0 error
 pub struct JsonRpcError {
     /// The error code
@@ -21,6 +21,14 @@ pub struct JsonRpcError {
     pub data: Option<Value>,
 }
 
+impl JsonRpcError {
+    /// Returns the revert data of a failed `eth_call` or `eth_estimateGas`, if the node included
+    /// it as a hex string in the error's `data` field
+    pub fn as_revert_data(&self) -> Option<Bytes> {
+        self.data.as_ref()?.as_str()?.parse().ok()
+    }
+}
+
 impl fmt::Display for JsonRpcError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "(code: {}, message: {}, data: {:?})", self.code, self.message, self.data)
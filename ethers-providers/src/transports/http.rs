@@ -1,9 +1,15 @@
 // Code adapted from: https://github.com/althea-net/guac_rs/tree/master/web3/src/jsonrpc
 use crate::{provider::ProviderError, JsonRpcClient};
 
-use reqwest::{blocking::Client, header::HeaderValue, Error as ReqwestError};
+use reqwest::{
+    blocking::Client,
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Error as ReqwestError, StatusCode,
+};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
+    fmt::Debug,
+    io::Read,
     str::FromStr,
     sync::atomic::{AtomicU64, Ordering},
 };
@@ -17,6 +23,8 @@ pub struct Provider {
     id: AtomicU64,
     client: Client,
     url: Url,
+    /// Headers sent with every request on top of the client's default headers
+    headers: HeaderMap,
 }
 
 #[derive(Error, Debug)]
@@ -32,12 +40,21 @@ pub enum ClientError {
     #[error("Deserialization Error: {err}. Response: {text}")]
     /// Serde JSON Error
     SerdeJson { err: serde_json::Error, text: String },
+
+    #[error("response exceeds the maximum size of {0} bytes")]
+    /// Thrown if the response body is larger than the requested maximum size
+    ResponseTooLarge(usize),
+
+    #[error(transparent)]
+    /// Thrown if the response body could not be read
+    BodyError(#[from] std::io::Error),
 }
 
 impl From<ClientError> for ProviderError {
     fn from(src: ClientError) -> Self {
         match src {
             ClientError::ReqwestError(err) => ProviderError::HTTPError(err),
+            ClientError::ResponseTooLarge(max_size) => ProviderError::ResponseTooLarge(max_size),
             _ => ProviderError::JsonRpcClientError(Box::new(src)),
         }
     }
@@ -48,28 +65,39 @@ impl JsonRpcClient for Provider {
 
     /// Sends a POST request with the provided method and the params serialized as JSON
     /// over HTTP
-    fn request<T: Serialize + Send + Sync, R: DeserializeOwned>(
+    fn request<T: Debug + Serialize + Send + Sync, R: DeserializeOwned>(
         &self,
         method: &str,
         params: T,
+    ) -> Result<R, ClientError> {
+        self.request_with_max_size(method, params, None)
+    }
+
+    /// Sends a POST request like [`request`](Self::request), rejecting responses whose body is
+    /// larger than `max_size` bytes before deserializing them
+    fn request_with_max_size<T: Debug + Serialize + Send + Sync, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: T,
+        max_size: Option<usize>,
     ) -> Result<R, ClientError> {
         let next_id = self.id.fetch_add(1, Ordering::SeqCst);
         let payload = Request::new(next_id, method, params);
-
-        let res = self.client.post(self.url.as_ref()).json(&payload).send()?;
-        let body = res.bytes()?;
+        let (body, status_err) = self.post(&payload, max_size)?;
 
         let raw = match serde_json::from_slice(&body) {
             Ok(Response::Success { result, .. }) => result.to_owned(),
             Ok(Response::Error { error, .. }) => return Err(error.into()),
-            Ok(_) => {
-                let err = ClientError::SerdeJson {
-                    err: serde::de::Error::custom("unexpected notification over HTTP transport"),
-                    text: String::from_utf8_lossy(&body).to_string(),
+            res => {
+                if let Some(err) = status_err {
+                    return Err(err.into())
+                }
+                let err = match res {
+                    Ok(_) => {
+                        serde::de::Error::custom("unexpected notification over HTTP transport")
+                    }
+                    Err(err) => err,
                 };
-                return Err(err)
-            }
-            Err(err) => {
                 return Err(ClientError::SerdeJson {
                     err,
                     text: String::from_utf8_lossy(&body).to_string(),
@@ -82,9 +110,113 @@ impl JsonRpcClient for Provider {
 
         Ok(res)
     }
+
+    /// Sends the requests as a single JSON-RPC batch in one POST request. The node may answer
+    /// them in any order, the results are matched to the requests by their id.
+    fn request_batch<T, R>(&self, method: &str, params: Vec<T>) -> Result<Vec<R>, ClientError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        if params.is_empty() {
+            return Ok(Vec::new())
+        }
+        let len = params.len();
+        let first_id = self.id.fetch_add(len as u64, Ordering::SeqCst);
+        let payload: Vec<_> = params
+            .into_iter()
+            .zip(first_id..)
+            .map(|(params, id)| Request::new(id, method, params))
+            .collect();
+        let (body, status_err) = self.post(&payload, None)?;
+
+        let responses: Vec<Response<'_>> = match serde_json::from_slice(&body) {
+            Ok(responses) => responses,
+            Err(err) => {
+                // nodes which do not support batches answer with a single error
+                if let Ok(Response::Error { error, .. }) = serde_json::from_slice(&body) {
+                    return Err(error.into())
+                }
+                if let Some(err) = status_err {
+                    return Err(err.into())
+                }
+                return Err(ClientError::SerdeJson {
+                    err,
+                    text: String::from_utf8_lossy(&body).to_string(),
+                })
+            }
+        };
+
+        let invalid_batch = |msg: &str| ClientError::SerdeJson {
+            err: serde::de::Error::custom(msg),
+            text: String::from_utf8_lossy(&body).to_string(),
+        };
+        let mut results = vec![None; len];
+        for response in responses {
+            match response {
+                Response::Success { id, result } => {
+                    let slot = id.checked_sub(first_id).and_then(|i| results.get_mut(i as usize));
+                    *slot.ok_or_else(|| invalid_batch("unexpected response id in batch"))? =
+                        Some(result);
+                }
+                Response::Error { error, .. } => return Err(error.into()),
+                Response::Notification { .. } => {
+                    return Err(invalid_batch("unexpected notification over HTTP transport"))
+                }
+            }
+        }
+        results
+            .into_iter()
+            .map(|raw| {
+                let raw = raw.ok_or_else(|| invalid_batch("missing response in batch"))?;
+                serde_json::from_str(raw.get())
+                    .map_err(|err| ClientError::SerdeJson { err, text: raw.to_string() })
+            })
+            .collect()
+    }
 }
 
 impl Provider {
+    /// Posts the `payload` and reads the response body, along with the error for a status which
+    /// may come without a JSON-RPC response
+    fn post<P: Serialize>(
+        &self,
+        payload: &P,
+        max_size: Option<usize>,
+    ) -> Result<(Vec<u8>, Option<ReqwestError>), ClientError> {
+        let res = self
+            .client
+            .post(self.url.as_ref())
+            .headers(self.headers.clone())
+            .json(payload)
+            .send()?;
+        // throttled requests and server failures may not carry a JSON-RPC response, in which case
+        // their status is surfaced instead, see `ProviderError::kind`
+        let status = res.status();
+        let status_err = if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            res.error_for_status_ref().err()
+        } else {
+            None
+        };
+        if let Some(max_size) = max_size {
+            // bail out early if the server announces a larger body
+            if res.content_length().map_or(false, |len| len > max_size as u64) {
+                return Err(ClientError::ResponseTooLarge(max_size))
+            }
+        }
+        // the content length is not always announced, e.g. for chunked responses, so at most one
+        // byte more than the limit is read to tell whether the body exceeds it
+        let mut body = Vec::new();
+        res.take(max_size.map_or(u64::MAX, |max_size| max_size as u64 + 1))
+            .read_to_end(&mut body)?;
+        if let Some(max_size) = max_size {
+            if body.len() > max_size {
+                return Err(ClientError::ResponseTooLarge(max_size))
+            }
+        }
+        Ok((body, status_err))
+    }
+
     /// Initializes a new HTTP Client
     ///
     /// # Example
@@ -136,6 +268,24 @@ impl Provider {
         Ok(Self::new_with_client(url, client))
     }
 
+    /// Initializes a new HTTP Client which speaks HTTP/2 right away instead of starting with
+    /// HTTP/1.1, so that concurrent requests are multiplexed over a single connection. The node
+    /// (or the proxy in front of it) must accept HTTP/2 without an upgrade.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ethers_providers::Http;
+    /// use url::Url;
+    ///
+    /// let url = Url::parse("http://localhost:8545").unwrap();
+    /// let provider = Http::new_with_http2_prior_knowledge(url).unwrap();
+    /// ```
+    pub fn new_with_http2_prior_knowledge(url: impl Into<Url>) -> Result<Self, HttpClientError> {
+        let client = Client::builder().http2_prior_knowledge().build()?;
+        Ok(Self::new_with_client(url, client))
+    }
+
     /// Allows to customize the provider by providing your own http client
     ///
     /// # Example
@@ -149,7 +299,25 @@ impl Provider {
     /// let provider = Http::new_with_client(url, client);
     /// ```
     pub fn new_with_client(url: impl Into<Url>, client: reqwest::blocking::Client) -> Self {
-        Self { id: AtomicU64::new(1), client, url: url.into() }
+        Self { id: AtomicU64::new(1), client, url: url.into(), headers: HeaderMap::new() }
+    }
+
+    /// Adds a header which is sent with every request, e.g. `X-Request-Source` so that the calls
+    /// can be attributed in the logs of a gateway shared by several clients
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ethers_providers::Http;
+    /// use url::Url;
+    ///
+    /// let url = Url::parse("http://localhost:8545").unwrap();
+    /// let provider = Http::new(url).with_header("X-Request-Source", "indexer").unwrap();
+    /// ```
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, HttpClientError> {
+        let name = HeaderName::from_str(name)?;
+        self.headers.insert(name, HeaderValue::from_str(value)?);
+        Ok(self)
     }
 }
 
@@ -164,7 +332,12 @@ impl FromStr for Provider {
 
 impl Clone for Provider {
     fn clone(&self) -> Self {
-        Self { id: AtomicU64::new(1), client: self.client.clone(), url: self.url.clone() }
+        Self {
+            id: AtomicU64::new(1),
+            client: self.client.clone(),
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+        }
     }
 }
 
@@ -175,7 +348,221 @@ pub enum HttpClientError {
     #[error(transparent)]
     InvalidHeader(#[from] http::header::InvalidHeaderValue),
 
+    /// Thrown if a header name is invalid
+    #[error(transparent)]
+    InvalidHeaderName(#[from] http::header::InvalidHeaderName),
+
     /// Thrown if unable to build client
     #[error(transparent)]
     ClientBuild(#[from] reqwest::Error),
 }
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use crate::Middleware;
+    use ethers_core::types::{transaction::eip2718::TypedTransaction, Address, Filter, U256};
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::{TcpListener, TcpStream},
+        sync::mpsc::{self, Receiver},
+        thread,
+    };
+
+    /// Spawns a server answering every JSON-RPC request with the same `result`, and sending the
+    /// header lines of every request it receives to the returned channel
+    fn serve(result: String) -> (Url, Receiver<Vec<String>>) {
+        serve_with(move |stream| {
+            let body = format!(r#"{{"jsonrpc":"2.0","id":1,"result":{}}}"#, result);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        })
+    }
+
+    /// Spawns a server answering every request with `respond`, see [`serve`]
+    fn serve_with<F>(respond: F) -> (Url, Receiver<Vec<String>>)
+    where
+        F: Fn(&mut TcpStream) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let (headers_tx, headers_rx) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                // consume the request headers and body
+                let mut content_length = 0;
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break
+                    }
+                    if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                    headers.push(line.trim_end().to_lowercase());
+                }
+                reader.read_exact(&mut vec![0; content_length]).unwrap();
+                let _ = headers_tx.send(headers);
+                respond(&mut stream);
+            }
+        });
+        (url, headers_rx)
+    }
+
+    #[test]
+    fn rejects_oversized_responses() {
+        let result = format!(r#""0x{}""#, "ab".repeat(1000));
+        let (url, _) = serve(result);
+        let provider = crate::Provider::new(Provider::new(url));
+
+        // without a limit the response goes through
+        let tx = TypedTransaction::default();
+        assert_eq!(provider.call(&tx, None).unwrap().len(), 1000);
+
+        let provider = provider.max_response_size(1000);
+        let err = provider.call(&tx, None).unwrap_err();
+        assert!(matches!(err, ProviderError::ResponseTooLarge(1000)), "{:?}", err);
+
+        // the limit applies to `eth_getLogs` as well
+        let err = provider.get_logs(&Filter::new()).unwrap_err();
+        assert!(matches!(err, ProviderError::ResponseTooLarge(1000)), "{:?}", err);
+    }
+
+    #[test]
+    fn rejects_oversized_chunked_responses() {
+        // a chunked response without a content length, whose body never ends
+        let (url, _) = serve_with(|stream| {
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n";
+            let start = r#"{"jsonrpc":"2.0","id":1,"result":"0x"#;
+            let mut res = write!(stream, "{}{:x}\r\n{}\r\n", head, start.len(), start);
+            let chunk = "ab".repeat(512);
+            // stops once the client hangs up
+            while res.is_ok() {
+                res = write!(stream, "{:x}\r\n{}\r\n", chunk.len(), chunk);
+            }
+        });
+        let provider = crate::Provider::new(Provider::new(url)).max_response_size(1000);
+
+        let err = provider.call(&TypedTransaction::default(), None).unwrap_err();
+        assert!(matches!(err, ProviderError::ResponseTooLarge(1000)), "{:?}", err);
+    }
+
+    #[test]
+    fn json_rpc_errors_with_error_status() {
+        let respond = |status: &'static str, body: &'static str| {
+            move |stream: &mut TcpStream| {
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        };
+        let tx = TypedTransaction::default();
+
+        // the JSON-RPC error sent along the status is kept, along with its revert data
+        let (url, _) = serve_with(respond(
+            "500 Internal Server Error",
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted","data":"0x01"}}"#,
+        ));
+        let err = crate::Provider::new(Provider::new(url)).call(&tx, None).unwrap_err();
+        assert!(matches!(err, ProviderError::Revert(_)), "{:?}", err);
+        assert_eq!(err.to_string(), "execution reverted: 0x01");
+
+        // otherwise the status is returned
+        let (url, _) = serve_with(respond("429 Too Many Requests", "slow down"));
+        let err = crate::Provider::new(Provider::new(url)).get_block_number().unwrap_err();
+        assert!(matches!(err, ProviderError::HTTPError(_)), "{:?}", err);
+        assert_eq!(err.kind(), crate::RpcErrorKind::RateLimited);
+    }
+
+    #[test]
+    fn sends_batches_in_a_single_request() {
+        // the responses of a batch may come in any order
+        let (url, requests) = serve_with(|stream| {
+            let body = r#"[{"jsonrpc":"2.0","id":2,"result":"0x2"},{"jsonrpc":"2.0","id":1,"result":"0x1"}]"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        let provider = Provider::new(url);
+
+        let params = vec![(Address::zero(), "0x1"), (Address::zero(), "0x2")];
+        let balances: Vec<U256> = provider.request_batch("eth_getBalance", params).unwrap();
+        assert_eq!(balances, vec![1.into(), 2.into()]);
+        requests.recv().unwrap();
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[test]
+    fn sends_custom_headers() {
+        let (url, headers) = serve(r#""0x1""#.to_string());
+        let http = Provider::new(url).with_header("X-Request-Source", "indexer").unwrap();
+        let provider = crate::Provider::new(http.clone());
+
+        provider.get_block_number().unwrap();
+        assert!(headers.recv().unwrap().contains(&"x-request-source: indexer".to_string()));
+
+        // clones keep the headers
+        crate::Provider::new(http).get_block_number().unwrap();
+        assert!(headers.recv().unwrap().contains(&"x-request-source: indexer".to_string()));
+
+        assert!(Provider::from_str("http://localhost:8545")
+            .unwrap()
+            .with_header("invalid header", "indexer")
+            .is_err());
+    }
+
+    #[test]
+    fn speaks_http2_with_prior_knowledge() {
+        use hyper::{
+            service::{make_service_fn, service_fn},
+            Body, Response, Server, Version,
+        };
+
+        // an HTTP/2 only server answering every request with block number 1
+        let (url_tx, url_rx) = mpsc::channel();
+        thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(async move {
+                let make_svc = make_service_fn(|_| async {
+                    Ok::<_, hyper::Error>(service_fn(|req: hyper::Request<Body>| async move {
+                        assert_eq!(req.version(), Version::HTTP_2);
+                        let body = r#"{"jsonrpc":"2.0","id":1,"result":"0x1"}"#;
+                        Ok::<_, hyper::Error>(Response::new(Body::from(body)))
+                    }))
+                });
+                let server =
+                    Server::bind(&([127, 0, 0, 1], 0).into()).http2_only(true).serve(make_svc);
+                url_tx.send(format!("http://{}", server.local_addr())).unwrap();
+                server.await.unwrap();
+            });
+        });
+        let url = Url::parse(&url_rx.recv().unwrap()).unwrap();
+
+        let http = Provider::new_with_http2_prior_knowledge(url.clone()).unwrap();
+        let provider = crate::Provider::new(http);
+        assert_eq!(provider.get_block_number().unwrap(), 1u64.into());
+
+        // HTTP/1.1 clients are rejected by the server
+        assert!(crate::Provider::new(Provider::new(url)).get_block_number().is_err());
+    }
+}
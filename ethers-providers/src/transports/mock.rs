@@ -1,11 +1,14 @@
-use crate::{JsonRpcClient, ProviderError};
+use crate::{JsonRpcClient, JsonRpcError, ProviderError};
 
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 use std::{
     borrow::Borrow,
     collections::VecDeque,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 use thiserror::Error;
 
@@ -18,11 +21,55 @@ enum MockParams {
     Zst,
 }
 
+/// The params expected by the standard JSON-RPC methods, checked in strict mode: the method, the
+/// minimum and maximum number of params, and their shape for the error message
+const PARAM_SCHEMAS: &[(&str, usize, usize, &str)] = &[
+    ("eth_accounts", 0, 0, "[]"),
+    ("eth_blockNumber", 0, 0, "[]"),
+    ("eth_call", 1, 4, "[tx, block?, state_overrides?, block_overrides?]"),
+    ("eth_chainId", 0, 0, "[]"),
+    ("eth_coinbase", 0, 0, "[]"),
+    ("eth_createAccessList", 1, 2, "[tx, block?]"),
+    ("eth_estimateGas", 1, 2, "[tx, block?]"),
+    ("eth_feeHistory", 2, 3, "[block_count, newest_block, reward_percentiles?]"),
+    ("eth_gasPrice", 0, 0, "[]"),
+    ("eth_getBalance", 2, 2, "[address, block]"),
+    ("eth_getBlockByHash", 2, 2, "[hash, full_txs]"),
+    ("eth_getBlockByNumber", 2, 2, "[block, full_txs]"),
+    ("eth_getCode", 2, 2, "[address, block]"),
+    ("eth_getFilterChanges", 1, 1, "[filter_id]"),
+    ("eth_getFilterLogs", 1, 1, "[filter_id]"),
+    ("eth_getLogs", 1, 1, "[filter]"),
+    ("eth_getProof", 3, 3, "[address, storage_keys, block]"),
+    ("eth_getStorageAt", 3, 3, "[address, slot, block]"),
+    ("eth_getTransactionByHash", 1, 1, "[hash]"),
+    ("eth_getTransactionCount", 2, 2, "[address, block]"),
+    ("eth_getTransactionReceipt", 1, 1, "[hash]"),
+    ("eth_getUncleByBlockHashAndIndex", 2, 2, "[hash, index]"),
+    ("eth_getUncleByBlockNumberAndIndex", 2, 2, "[block, index]"),
+    ("eth_sendRawTransaction", 1, 1, "[raw_tx]"),
+    ("eth_sendTransaction", 1, 1, "[tx]"),
+    ("eth_sign", 2, 2, "[address, data]"),
+    ("eth_syncing", 0, 0, "[]"),
+    ("eth_uninstallFilter", 1, 1, "[filter_id]"),
+    ("net_version", 0, 0, "[]"),
+    ("web3_clientVersion", 0, 0, "[]"),
+    ("web3_sha3", 1, 1, "[data]"),
+];
+
+/// A queued response, either a successful result or a JSON-RPC error
+#[derive(Debug)]
+enum MockResponse {
+    Value(Value),
+    Error(JsonRpcError),
+}
+
 #[derive(Clone, Debug)]
 /// Mock transport used in test environments.
 pub struct MockProvider {
     requests: Arc<Mutex<VecDeque<(String, MockParams)>>>,
-    responses: Arc<Mutex<VecDeque<Value>>>,
+    responses: Arc<Mutex<VecDeque<MockResponse>>>,
+    strict: Arc<AtomicBool>,
 }
 
 impl Default for MockProvider {
@@ -46,12 +93,36 @@ impl JsonRpcClient for MockProvider {
         } else {
             MockParams::Value(serde_json::to_value(params)?)
         };
+        if self.strict.load(Ordering::SeqCst) {
+            validate_params(method, &params)?;
+        }
         self.requests.lock().unwrap().push_back((method.to_owned(), params));
         let mut data = self.responses.lock().unwrap();
-        let element = data.pop_back().ok_or(MockError::EmptyResponses)?;
-        let res: R = serde_json::from_value(element)?;
+        match data.pop_back().ok_or(MockError::EmptyResponses)? {
+            MockResponse::Value(element) => Ok(serde_json::from_value(element)?),
+            MockResponse::Error(err) => Err(MockError::JsonRpcError(err)),
+        }
+    }
 
-        Ok(res)
+    /// Pushes a single `(method, params)` for the whole batch, `params` being the array of the
+    /// params of each request, and pops the results of the batch as one array
+    fn request_batch<T, R>(&self, method: &str, params: Vec<T>) -> Result<Vec<R>, MockError>
+    where
+        T: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        if self.strict.load(Ordering::SeqCst) {
+            for params in &params {
+                validate_params(method, &MockParams::Value(serde_json::to_value(params)?))?;
+            }
+        }
+        let params = MockParams::Value(serde_json::to_value(params)?);
+        self.requests.lock().unwrap().push_back((method.to_owned(), params));
+        let mut data = self.responses.lock().unwrap();
+        match data.pop_back().ok_or(MockError::EmptyResponses)? {
+            MockResponse::Value(element) => Ok(serde_json::from_value(element)?),
+            MockResponse::Error(err) => Err(MockError::JsonRpcError(err)),
+        }
     }
 }
 
@@ -81,15 +152,46 @@ impl MockProvider {
         Self {
             requests: Arc::new(Mutex::new(VecDeque::new())),
             responses: Arc::new(Mutex::new(VecDeque::new())),
+            strict: Arc::new(AtomicBool::new(false)),
         }
     }
 
-    /// Pushes the data to the responses
+    /// Enables or disables strict mode, in which requests to the standard JSON-RPC methods fail
+    /// with [`MockError::InvalidParams`] if they are not sent with the expected number of params.
+    /// Applies to all clones of the mock, including the one held by the provider.
+    pub fn set_strict(&self, strict: bool) {
+        self.strict.store(strict, Ordering::SeqCst);
+    }
+
+    /// Pushes the data to the responses. Responses are returned last in, first out, so the
+    /// responses to a sequence of requests are pushed in reverse order.
     pub fn push<T: Serialize + Send + Sync, K: Borrow<T>>(&self, data: K) -> Result<(), MockError> {
         let value = serde_json::to_value(data.borrow())?;
-        self.responses.lock().unwrap().push_back(value);
+        self.responses.lock().unwrap().push_back(MockResponse::Value(value));
         Ok(())
     }
+
+    /// Pushes a JSON-RPC error to the responses, see [`push`](Self::push)
+    pub fn push_error(&self, error: JsonRpcError) {
+        self.responses.lock().unwrap().push_back(MockResponse::Error(error));
+    }
+}
+
+/// Checks the number of `params` against the method's [schema](PARAM_SCHEMAS), if it has one
+fn validate_params(method: &str, params: &MockParams) -> Result<(), MockError> {
+    let (_, min, max, shape) = match PARAM_SCHEMAS.iter().find(|schema| schema.0 == method) {
+        Some(schema) => schema,
+        None => return Ok(()),
+    };
+    let count = match params {
+        MockParams::Zst => 0,
+        MockParams::Value(Value::Array(params)) => params.len(),
+        MockParams::Value(_) => 1,
+    };
+    if count < *min || count > *max {
+        return Err(MockError::InvalidParams { method: method.to_owned(), count, shape })
+    }
+    Ok(())
 }
 
 #[derive(Error, Debug)]
@@ -103,6 +205,14 @@ pub enum MockError {
 
     #[error("empty responses array, please push some responses")]
     EmptyResponses,
+
+    #[error(transparent)]
+    JsonRpcError(JsonRpcError),
+
+    /// A request was sent with params not matching the method's, see
+    /// [`MockProvider::set_strict`]
+    #[error("`{method}` expects params {shape}, got {count} param(s)")]
+    InvalidParams { method: String, count: usize, shape: &'static str },
 }
 
 impl From<MockError> for ProviderError {
@@ -116,7 +226,7 @@ impl From<MockError> for ProviderError {
 mod tests {
     use super::*;
     use crate::Middleware;
-    use ethers_core::types::U64;
+    use ethers_core::types::{Address, U256, U64};
 
     #[test]
     fn pushes_request_and_response() {
@@ -149,6 +259,33 @@ mod tests {
         };
     }
 
+    #[test]
+    fn strict_mode_validates_params() {
+        let (provider, mock) = crate::Provider::mocked();
+        mock.set_strict(true);
+
+        // the block is missing
+        let err = mock.request::<_, U64>("eth_getBalance", [Address::zero()]).unwrap_err();
+        assert!(matches!(err, MockError::InvalidParams { count: 1, .. }), "{:?}", err);
+        let msg = "`eth_getBalance` expects params [address, block], got 1 param(s)";
+        assert_eq!(err.to_string(), msg);
+        let err = mock.request::<_, U64>("eth_blockNumber", [1]).unwrap_err();
+        assert!(matches!(err, MockError::InvalidParams { count: 1, .. }), "{:?}", err);
+
+        // well-formed requests and unknown methods go through
+        mock.push(U64::from(12)).unwrap();
+        mock.push(U64::from(1)).unwrap();
+        mock.push(U256::from(100)).unwrap();
+        assert_eq!(provider.get_balance(Address::zero(), None).unwrap(), 100.into());
+        let _: U64 = mock.request("custom_method", (1, 2, 3)).unwrap();
+        assert_eq!(provider.get_block_number().unwrap(), 12.into());
+
+        // validation can be turned off again
+        mock.set_strict(false);
+        mock.push(U64::from(12)).unwrap();
+        let _: U64 = mock.request("eth_getBalance", [Address::zero()]).unwrap();
+    }
+
     #[test]
     fn composes_with_provider() {
         let (provider, mock) = crate::Provider::mocked();
@@ -1,8 +1,15 @@
 mod common;
-pub use common::Authorization;
+pub use common::{Authorization, JsonRpcError};
 
 mod http;
 pub use self::http::{ClientError as HttpClientError, Provider as Http};
 
 mod mock;
 pub use mock::{MockError, MockProvider};
+
+mod recorder;
+pub use recorder::{RecordedRequest, Recorder, RecordingError, Replay};
+
+mod retry;
+pub(crate) use retry::is_retryable;
+pub use retry::{DefaultIdempotency, IdempotencyClassifier, RetryClient};
@@ -0,0 +1,238 @@
+use crate::{JsonRpcClient, JsonRpcError, ProviderError};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    fs::{self, File},
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+
+/// A JSON-RPC request together with the node's response to it, as recorded by a [`Recorder`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub params: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+#[derive(Clone, Debug)]
+/// Transport which records every request sent through the inner transport and the response to
+/// it in a file, one JSON object per line, so the session can be served again by a [`Replay`].
+///
+/// Failures of the inner transport which are not JSON-RPC errors (e.g. a dropped connection) are
+/// not part of the session and are not recorded.
+pub struct Recorder<C> {
+    inner: C,
+    file: Arc<Mutex<File>>,
+}
+
+impl<C: JsonRpcClient> Recorder<C> {
+    /// Records the requests sent through `inner` to the file at `path`, replacing its contents
+    pub fn new(inner: C, path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let file = File::create(path)?;
+        Ok(Self { inner, file: Arc::new(Mutex::new(file)) })
+    }
+
+    /// Returns the inner transport
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    fn record(&self, request: &RecordedRequest) -> Result<(), RecordingError> {
+        let mut file = self.file.lock().unwrap();
+        serde_json::to_writer(&mut *file, request)?;
+        writeln!(file)?;
+        Ok(())
+    }
+}
+
+impl<C: JsonRpcClient> JsonRpcClient for Recorder<C> {
+    type Error = ProviderError;
+
+    fn request<T, R>(&self, method: &str, params: T) -> Result<R, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        self.request_with_max_size(method, params, None)
+    }
+
+    fn request_with_max_size<T, R>(
+        &self,
+        method: &str,
+        params: T,
+        max_size: Option<usize>,
+    ) -> Result<R, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let mut request = RecordedRequest {
+            method: method.to_string(),
+            params: serde_json::to_value(&params)?,
+            result: None,
+            error: None,
+        };
+        let res: Result<Value, ProviderError> =
+            self.inner.request_with_max_size(method, params, max_size).map_err(Into::into);
+        match res {
+            Ok(result) => request.result = Some(result),
+            Err(err) => {
+                if let Some(error) = err.as_error_response() {
+                    request.error = Some(error.clone());
+                    self.record(&request)?;
+                }
+                return Err(err)
+            }
+        }
+        self.record(&request)?;
+        Ok(serde_json::from_value(request.result.unwrap_or_default())?)
+    }
+}
+
+#[derive(Debug)]
+/// Transport serving the responses of a session recorded by a [`Recorder`], for running tests
+/// against a snapshot of a real node without network access.
+///
+/// Like the [`MockProvider`](crate::MockProvider), it never talks to a node. The requests must be
+/// sent in the order they were recorded, a request which differs from the recorded one fails with
+/// [`RecordingError::UnexpectedRequest`].
+pub struct Replay {
+    requests: Mutex<VecDeque<RecordedRequest>>,
+}
+
+impl Replay {
+    /// Instantiates a replay of the given requests
+    pub fn new(requests: impl IntoIterator<Item = RecordedRequest>) -> Self {
+        Self { requests: Mutex::new(requests.into_iter().collect()) }
+    }
+
+    /// Reads the session recorded to the file at `path`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let requests = fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<RecordedRequest>, _>>()?;
+        Ok(Self::new(requests))
+    }
+
+    /// Returns `true` if every recorded request has been served
+    pub fn is_done(&self) -> bool {
+        self.requests.lock().unwrap().is_empty()
+    }
+}
+
+impl JsonRpcClient for Replay {
+    type Error = RecordingError;
+
+    fn request<T, R>(&self, method: &str, params: T) -> Result<R, RecordingError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let params = serde_json::to_value(params)?;
+        let recorded =
+            self.requests.lock().unwrap().pop_front().ok_or(RecordingError::Exhausted)?;
+        if recorded.method != method || recorded.params != params {
+            return Err(RecordingError::UnexpectedRequest {
+                method: method.to_string(),
+                params,
+                expected: recorded.method,
+                expected_params: recorded.params,
+            })
+        }
+        match recorded.error {
+            Some(err) => Err(RecordingError::JsonRpcError(err)),
+            None => Ok(serde_json::from_value(recorded.result.unwrap_or_default())?),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+/// Errors for the [`Recorder`] and the [`Replay`]
+pub enum RecordingError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+
+    /// The recorded response was a JSON-RPC error
+    #[error(transparent)]
+    JsonRpcError(JsonRpcError),
+
+    #[error("all recorded requests have been replayed")]
+    Exhausted,
+
+    #[error("unexpected request `{method}` {params}, expected `{expected}` {expected_params}")]
+    UnexpectedRequest { method: String, params: Value, expected: String, expected_params: Value },
+}
+
+impl From<RecordingError> for ProviderError {
+    fn from(src: RecordingError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(src))
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use crate::{Middleware, MockProvider, Provider};
+    use ethers_core::types::{Address, U256, U64};
+
+    // the same assertions hold for the recorded and the replayed session
+    fn session<P: JsonRpcClient>(provider: &Provider<P>) {
+        assert_eq!(provider.get_block_number().unwrap(), U64::from(12));
+        let balance = provider.get_balance(Address::repeat_byte(1), None).unwrap();
+        assert_eq!(balance, U256::from(100));
+        assert!(provider.get_coinbase().unwrap_err().is_method_not_found());
+    }
+
+    #[test]
+    fn records_and_replays_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+
+        let mock = MockProvider::new();
+        mock.push_error(JsonRpcError {
+            code: -32601,
+            message: "method not found".to_string(),
+            data: None,
+        });
+        mock.push(U256::from(100)).unwrap();
+        mock.push(U64::from(12)).unwrap();
+        session(&Provider::new(Recorder::new(mock, &path).unwrap()));
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        let replay = Replay::from_file(&path).unwrap();
+        let provider = Provider::new(replay);
+        session(&provider);
+        assert!(provider.as_ref().is_done());
+    }
+
+    #[test]
+    fn rejects_unexpected_request() {
+        let replay = Replay::new(vec![RecordedRequest {
+            method: "eth_blockNumber".to_string(),
+            params: Value::Null,
+            result: Some(serde_json::json!("0xc")),
+            error: None,
+        }]);
+
+        let err = replay.request::<_, U256>("eth_chainId", ()).unwrap_err();
+        assert!(matches!(err, RecordingError::UnexpectedRequest { .. }), "{:?}", err);
+        assert!(replay.is_done());
+        let err = replay.request::<_, U64>("eth_blockNumber", ()).unwrap_err();
+        assert!(matches!(err, RecordingError::Exhausted), "{:?}", err);
+    }
+}
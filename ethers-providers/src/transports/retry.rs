@@ -0,0 +1,269 @@
+use crate::{DefaultErrorClassifier, ErrorClassifier, JsonRpcClient, ProviderError, RpcErrorKind};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{fmt::Debug, sync::Arc, thread, time::Duration};
+
+/// Decides which methods a [`RetryClient`] may send again after a failure. Retrying a method
+/// which is not idempotent may execute it twice, e.g. broadcast a transaction which the node
+/// already accepted before the connection dropped.
+pub trait IdempotencyClassifier: Debug + Send + Sync {
+    /// Returns `true` if sending `method` again has no effect beyond the first request
+    fn is_idempotent(&self, method: &str) -> bool;
+}
+
+/// Treats every method as idempotent except the ones sending transactions
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultIdempotency;
+
+impl IdempotencyClassifier for DefaultIdempotency {
+    fn is_idempotent(&self, method: &str) -> bool {
+        !matches!(
+            method,
+            "eth_sendRawTransaction" | "eth_sendTransaction" | "personal_sendTransaction"
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Transport which retries the requests failing with a transport error (e.g. a dropped
+/// connection) or because the node rate limited them, backing off exponentially between
+/// attempts. Other JSON-RPC errors, such as reverts, are returned right away.
+///
+/// Only idempotent methods are retried, as decided by the [`IdempotencyClassifier`], which
+/// defaults to [`DefaultIdempotency`] so transactions are never broadcast twice.
+pub struct RetryClient<C> {
+    inner: C,
+    max_retries: u32,
+    initial_backoff: Duration,
+    idempotency: Arc<dyn IdempotencyClassifier>,
+    error_classifier: Arc<dyn ErrorClassifier>,
+}
+
+impl<C: JsonRpcClient> RetryClient<C> {
+    /// Retries the requests sent through `inner` up to `max_retries` times, waiting
+    /// `initial_backoff` before the first retry and doubling the wait on every retry
+    pub fn new(inner: C, max_retries: u32, initial_backoff: Duration) -> Self {
+        Self {
+            inner,
+            max_retries,
+            initial_backoff,
+            idempotency: Arc::new(DefaultIdempotency),
+            error_classifier: Arc::new(DefaultErrorClassifier),
+        }
+    }
+
+    /// Decides which methods may be retried with `classifier`
+    #[must_use]
+    pub fn with_idempotency(mut self, classifier: impl IdempotencyClassifier + 'static) -> Self {
+        self.idempotency = Arc::new(classifier);
+        self
+    }
+
+    /// Detects rate limited requests with `classifier`, for nodes whose error messages the
+    /// [`DefaultErrorClassifier`] does not know
+    #[must_use]
+    pub fn with_error_classifier(mut self, classifier: impl ErrorClassifier + 'static) -> Self {
+        self.error_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Returns the inner transport
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+/// Returns `true` if the request may succeed when sent again, i.e. if it failed to reach the node,
+/// the node failed with a server error or rate limited the request. Responses which can not be
+/// decoded and other errors of the transport, e.g. a mock without responses, are returned as is.
+pub(crate) fn is_retryable(err: &ProviderError, classifier: &dyn ErrorClassifier) -> bool {
+    match err {
+        ProviderError::HTTPError(err) => !err.is_builder() && !err.is_decode(),
+        ProviderError::JsonRpcClientError(_) => {
+            err.kind_with(classifier) == RpcErrorKind::RateLimited
+        }
+        _ => false,
+    }
+}
+
+impl<C: JsonRpcClient> JsonRpcClient for RetryClient<C> {
+    type Error = ProviderError;
+
+    fn request<T, R>(&self, method: &str, params: T) -> Result<R, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        self.request_with_max_size(method, params, None)
+    }
+
+    fn request_with_max_size<T, R>(
+        &self,
+        method: &str,
+        params: T,
+        max_size: Option<usize>,
+    ) -> Result<R, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        // the params are serialized once, so they can be sent again
+        let params = match serde_json::to_value(&params)? {
+            // keep sending no params for methods without any
+            Value::Null => None,
+            params => Some(params),
+        };
+        let mut retries = if self.idempotency.is_idempotent(method) { self.max_retries } else { 0 };
+        let mut backoff = self.initial_backoff;
+        loop {
+            let res = match params {
+                Some(ref params) => self.inner.request_with_max_size(method, params, max_size),
+                None => self.inner.request_with_max_size(method, (), max_size),
+            };
+            match res.map_err(Into::into) {
+                Err(err) if retries > 0 && is_retryable(&err, self.error_classifier.as_ref()) => {
+                    tracing::debug!(method, retries, "retrying request: {}", err);
+                    retries -= 1;
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Sends the batch to the inner transport, retrying the whole batch like a single request
+    fn request_batch<T, R>(&self, method: &str, params: Vec<T>) -> Result<Vec<R>, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        let params = params.into_iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+        let mut retries = if self.idempotency.is_idempotent(method) { self.max_retries } else { 0 };
+        let mut backoff = self.initial_backoff;
+        loop {
+            match self.inner.request_batch(method, params.iter().collect()).map_err(Into::into) {
+                Err(err) if retries > 0 && is_retryable(&err, self.error_classifier.as_ref()) => {
+                    tracing::debug!(method, retries, "retrying batch: {}", err);
+                    retries -= 1;
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use crate::{JsonRpcError, Middleware, MockProvider, Provider};
+    use ethers_core::types::{Address, Bytes, H256, U256};
+
+    fn rate_limited() -> JsonRpcError {
+        JsonRpcError { code: 429, message: "Too Many Requests".to_string(), data: None }
+    }
+
+    #[test]
+    fn retries_idempotent_methods_only() {
+        let mock = MockProvider::new();
+        let provider = Provider::new(RetryClient::new(mock.clone(), 3, Duration::ZERO));
+        let address = Address::repeat_byte(1);
+
+        mock.push(U256::from(100)).unwrap();
+        mock.push_error(rate_limited());
+        mock.push_error(rate_limited());
+        assert_eq!(provider.get_balance(address, None).unwrap(), U256::from(100));
+        for _ in 0..3 {
+            mock.assert_request("eth_getBalance", (address, "latest")).unwrap();
+        }
+
+        mock.push(H256::zero()).unwrap();
+        mock.push_error(rate_limited());
+        let raw = Bytes::from(vec![1, 2, 3]);
+        let err = provider.request::<_, H256>("eth_sendRawTransaction", [&raw]).unwrap_err();
+        assert_eq!(err.kind(), RpcErrorKind::RateLimited);
+        mock.assert_request("eth_sendRawTransaction", [&raw]).unwrap();
+        mock.assert_request("eth_sendRawTransaction", [&raw]).unwrap_err();
+
+        // other JSON-RPC errors are not retried
+        let mock = MockProvider::new();
+        let provider = Provider::new(RetryClient::new(mock.clone(), 3, Duration::ZERO));
+        mock.push(U256::from(100)).unwrap();
+        mock.push_error(JsonRpcError {
+            code: -32000,
+            message: "header not found".to_string(),
+            data: None,
+        });
+        assert!(provider.get_balance(address, None).is_err());
+        mock.assert_request("eth_getBalance", (address, "latest")).unwrap();
+        mock.assert_request("eth_getBalance", (address, "latest")).unwrap_err();
+    }
+
+    #[test]
+    fn does_not_retry_invalid_responses() {
+        let mock = MockProvider::new();
+        let provider = Provider::new(RetryClient::new(mock.clone(), 3, Duration::ZERO));
+        let address = Address::repeat_byte(1);
+
+        // a response which can not be decoded
+        mock.push::<&str, _>("not a balance").unwrap();
+        assert!(provider.get_balance(address, None).is_err());
+        mock.assert_request("eth_getBalance", (address, "latest")).unwrap();
+        mock.assert_request("eth_getBalance", (address, "latest")).unwrap_err();
+
+        // no response at all
+        assert!(provider.get_balance(address, None).is_err());
+        mock.assert_request("eth_getBalance", (address, "latest")).unwrap();
+        mock.assert_request("eth_getBalance", (address, "latest")).unwrap_err();
+    }
+
+    #[test]
+    fn custom_idempotency() {
+        #[derive(Debug)]
+        struct RetryEverything;
+
+        impl IdempotencyClassifier for RetryEverything {
+            fn is_idempotent(&self, _method: &str) -> bool {
+                true
+            }
+        }
+
+        let mock = MockProvider::new();
+        let client =
+            RetryClient::new(mock.clone(), 1, Duration::ZERO).with_idempotency(RetryEverything);
+        mock.push(H256::zero()).unwrap();
+        mock.push_error(rate_limited());
+        let hash: H256 = client.request("eth_sendRawTransaction", ["0x010203"]).unwrap();
+        assert_eq!(hash, H256::zero());
+    }
+    #[test]
+    fn custom_error_classifier() {
+        #[derive(Debug)]
+        struct SlowDown;
+
+        impl ErrorClassifier for SlowDown {
+            fn classify(&self, err: &JsonRpcError) -> RpcErrorKind {
+                match err.message.as_str() {
+                    "slow down" => RpcErrorKind::RateLimited,
+                    _ => RpcErrorKind::Other,
+                }
+            }
+        }
+
+        let mock = MockProvider::new();
+        let client =
+            RetryClient::new(mock.clone(), 1, Duration::ZERO).with_error_classifier(SlowDown);
+        mock.push(U256::from(100)).unwrap();
+        mock.push_error(JsonRpcError {
+            code: -39001,
+            message: "slow down".to_string(),
+            data: None,
+        });
+        let balance: U256 = client.request("eth_getBalance", ("0x01", "latest")).unwrap();
+        assert_eq!(balance, U256::from(100));
+    }
+}
@@ -7,7 +7,7 @@ mod eth_tests {
     use super::*;
     use ethers_core::{
         types::{Address, BlockId, TransactionRequest, H256},
-        utils::Anvil,
+        utils::{get_contract_address, Anvil},
     };
     use ethers_providers::RINKEBY;
 
@@ -32,6 +32,22 @@ mod eth_tests {
             .starts_with("Geth/v"));
     }
 
+    #[test]
+    fn wait_for_code() {
+        let anvil = Anvil::new().spawn();
+        let provider = Provider::<Http>::try_from(anvil.endpoint()).unwrap();
+        let from = anvil.addresses()[0];
+
+        // init code deploying the runtime code `0xfe`
+        let init_code = hex::decode("60fe60005360016000f3").unwrap();
+        let tx = TransactionRequest::new().from(from).data(init_code);
+        let _: H256 = provider.request("eth_sendTransaction", [tx]).unwrap();
+
+        let code =
+            provider.wait_for_code(get_contract_address(from, 0), Duration::from_secs(5)).unwrap();
+        assert_eq!(code.unwrap().as_ref(), &[0xfe]);
+    }
+
     #[test]
     fn eip1559_fee_estimation() {
         let provider = ethers_providers::MAINNET.provider();
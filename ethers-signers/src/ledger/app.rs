@@ -140,7 +140,9 @@ impl LedgerEthereum {
             };
 
             signature.v = match tx {
-                TypedTransaction::Eip2930(_) | TypedTransaction::Eip1559(_) => {
+                TypedTransaction::Eip2930(_) |
+                TypedTransaction::Eip1559(_) |
+                TypedTransaction::Eip4844(_) => {
                     if ecc_parity % 2 == 1 {
                         0
                     } else {
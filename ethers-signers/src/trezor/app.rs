@@ -163,6 +163,7 @@ impl TrezorEthereum {
         let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
 
         let signature = match tx {
+            TypedTransaction::Eip4844(_) => return Err(TrezorError::NoBlobTxSupport),
             TypedTransaction::Eip2930(_) | TypedTransaction::Legacy(_) => client.ethereum_sign_tx(
                 arr_path,
                 transaction.nonce,
@@ -51,6 +51,8 @@ pub enum TrezorError {
     UnsupportedFirmwareVersion(String),
     #[error("Does not support ENS.")]
     NoENSSupport,
+    #[error("Does not support blob transactions.")]
+    NoBlobTxSupport,
     #[error("Unable to access trezor cached session.")]
     CacheError(String),
 }
@@ -92,6 +94,7 @@ impl TrezorTransaction {
         let data = tx.data().map_or(vec![], |v| v.to_vec());
 
         match tx {
+            TypedTransaction::Eip4844(_) => Err(TrezorError::NoBlobTxSupport),
             TypedTransaction::Eip2930(_) | TypedTransaction::Legacy(_) => Ok(Self {
                 nonce,
                 gas,
@@ -65,7 +65,7 @@ async fn main() -> Result<()> {
     let logs = contract.value_changed_filter().from_block(0u64).query().await?;
 
     // 11. get the new value
-    let value = contract.get_value().call().await?;
+    let value = contract.get_value().call()?;
 
     println!("Value: {}. Logs: {}", value, serde_json::to_string(&logs)?);
 
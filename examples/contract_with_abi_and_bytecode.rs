@@ -29,7 +29,7 @@ async fn main() -> Result<()> {
         Greeter::deploy(client, "Hello World!".to_string()).unwrap().send().await.unwrap();
 
     // 6. call contract function
-    let greeting = greeter_contract.greet().call().await.unwrap();
+    let greeting = greeter_contract.greet().call().unwrap();
     assert_eq!("Hello World!", greeting);
 
     Ok(())
@@ -63,7 +63,7 @@ pub async fn deploy() {
     log!("Fetching logs...");
     let logs = contract.value_changed_filter().from_block(0u64).query().await.unwrap();
 
-    let value = contract.get_value().call().await.unwrap();
+    let value = contract.get_value().call().unwrap();
 
     console::log_2(
         &format!("Value: `{}`. Logs: ", value).into(),
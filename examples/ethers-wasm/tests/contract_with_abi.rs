@@ -45,7 +45,7 @@ async fn deploy<T: JsonRpcClient>(provider: Provider<T>, wallet: LocalWallet) {
     console_log!("deployed to {}", addr);
 
     let contract = SimpleContract::new(addr, client.clone());
-    let value = contract.get_value().call().await.unwrap();
+    let value = contract.get_value().call().unwrap();
 
     console_log!("value: {:?}", value);
 }
@@ -45,7 +45,7 @@ async fn main() -> Result<()> {
     let router = "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".parse::<Address>()?;
     let router = UniswapV2Router::new(router, provider.clone());
 
-    let (reserve0, reserve1, _) = pair.get_reserves().call().await?;
+    let (reserve0, reserve1, _) = pair.get_reserves().call()?;
 
     println!("Reserves (token A, Token B): ({}, {})", reserve0, reserve1);
 
@@ -64,8 +64,8 @@ async fn main() -> Result<()> {
 
     println!("Removing {} liquidity!", liquidity);
 
-    let token0 = pair.token_0().call().await?;
-    let token1 = pair.token_1().call().await?;
+    let token0 = pair.token_0().call()?;
+    let token1 = pair.token_1().call()?;
 
     let receipt = router
         .remove_liquidity(
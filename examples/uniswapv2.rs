@@ -23,7 +23,7 @@ async fn main() -> Result<()> {
     let pair = IUniswapV2Pair::new(address, Arc::clone(&client));
 
     // getReserves -> get_reserves
-    let (reserve0, reserve1, _timestamp) = pair.get_reserves().call().await?;
+    let (reserve0, reserve1, _timestamp) = pair.get_reserves().call()?;
     println!("Reserves (ETH, USDT): ({}, {})", reserve0, reserve1);
 
     let mid_price = f64::powi(10.0, 18 - 6) * reserve1 as f64 / reserve0 as f64;